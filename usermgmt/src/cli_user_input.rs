@@ -1,4 +1,9 @@
-use usermgmt_lib::prelude::{anyhow, AppResult, Context};
+use usermgmt_lib::{
+    cli::PasswordOptions,
+    config::MgmtConfig,
+    prelude::{anyhow, AppResult, Context},
+    util,
+};
 
 use crate::user_input;
 
@@ -19,3 +24,48 @@ pub fn ask_cli_password() -> AppResult<String> {
         .ok_or_else(|| anyhow!("No password provided"))?;
     Ok(password)
 }
+
+/// Password acquired according to the password options of the CLI
+pub enum NewPassword {
+    Entered(String),
+    Generated(String),
+}
+
+impl NewPassword {
+    /// Returns `None` if neither prompting nor generating is requested by parameter `options`
+    /// and parameter `prompt_by_default` is false.
+    ///
+    /// # Errors
+    ///
+    /// - If the password could not be entered. See [`user_input::ask_for_new_password`]
+    /// - If the password could not be generated. See [`util::generate_password`]
+    pub fn from_options(
+        options: &PasswordOptions,
+        config: &MgmtConfig,
+        prompt_by_default: bool,
+    ) -> AppResult<Option<Self>> {
+        if options.generate_password {
+            Ok(Some(Self::Generated(util::generate_password(config)?)))
+        } else if options.set_password || prompt_by_default {
+            let password = user_input::ask_for_new_password()
+                .context("Failed to retrieve new password from user")?;
+            Ok(Some(Self::Entered(password)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn plain(&self) -> &str {
+        match self {
+            NewPassword::Entered(password) | NewPassword::Generated(password) => password,
+        }
+    }
+
+    /// Prints the password to stdout if it was generated.
+    /// An entered password is already known to the user and therefore not printed.
+    pub fn print_if_generated(&self, username: &str) {
+        if let NewPassword::Generated(password) = self {
+            println!("Generated password for user {}: {}", username, password);
+        }
+    }
+}
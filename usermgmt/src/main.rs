@@ -3,11 +3,16 @@
 
 use clap::Parser;
 use cli_ssh_credential::CliSshCredential;
+use cli_user_input::NewPassword;
 use ldap_cli_credential::LdapCliCredential;
 use log::error;
+use std::fs;
 use std::process::ExitCode;
-use usermgmt_lib::cli::{self, Commands, GeneralArgs, OnWhichSystem};
+use usermgmt_lib::cli::{
+    self, Commands, ExportFormat, GeneralArgs, KeyCommands, OnWhichSystem, SlurmCommands,
+};
 use usermgmt_lib::config::{self};
+use usermgmt_lib::util::SshPublicKey;
 use usermgmt_lib::{operations, prelude::*, ChangesToUser, Entity};
 
 mod cli_ssh_credential;
@@ -51,19 +56,34 @@ pub fn run_mgmt(args: cli::GeneralArgs) -> AppResult {
         }
         Commands::Add {
             to_add,
+            password,
             on_which_sys,
         } => {
             let config = config::load_config(args.config_file)?.config;
             let ldap_credential = LdapCliCredential::new(&config);
             let on_which_sys = &OnWhichSystem::from_config_for_all(&config, &on_which_sys);
             let cli_ssh_credential = CliSshCredential::new(&config, on_which_sys.ssh_path());
+            let password = NewPassword::from_options(&password, &config, false)?;
+            let username = to_add.common_user_fields().username.to_string();
+            let to_add = to_add.with_password(
+                password
+                    .as_ref()
+                    .map(|password| password.plain().to_owned()),
+            );
+            // Printed right after the LDAP step, so a generated password is not lost
+            // if adding the user to Slurm or its directories fails afterwards.
             operations::add_user(
                 to_add,
                 on_which_sys,
                 &config,
                 ldap_credential,
                 cli_ssh_credential,
-            )?
+                || {
+                    if let Some(password) = &password {
+                        password.print_if_generated(&username);
+                    }
+                },
+            )?;
         }
         Commands::Modify { data, on_which_sys } => {
             let config = config::load_config(args.config_file)?.config;
@@ -80,7 +100,11 @@ pub fn run_mgmt(args: cli::GeneralArgs) -> AppResult {
                 cli_ssh_credential,
             )?
         }
-        Commands::Delete { user, on_which_sys } => {
+        Commands::Delete {
+            user,
+            cancel_jobs,
+            on_which_sys,
+        } => {
             let config = config::load_config(args.config_file)?.config;
             let ldap_credential = LdapCliCredential::new(&config);
             // let on_which_sys = &OnWhichSystem::from_config_for_slurm_ldap(&config, &on_which_sys);
@@ -88,15 +112,115 @@ pub fn run_mgmt(args: cli::GeneralArgs) -> AppResult {
             let cli_ssh_credential = CliSshCredential::new(&config, on_which_sys.ssh_path());
             operations::delete_user(
                 user.as_ref(),
+                cancel_jobs,
                 on_which_sys,
                 &config,
                 ldap_credential,
                 cli_ssh_credential,
             )?;
         }
+        Commands::ResetPassword {
+            user,
+            password,
+            force_change,
+        } => {
+            let config = config::load_config(args.config_file)?.config;
+            let ldap_credential = LdapCliCredential::new(&config);
+            let password = NewPassword::from_options(&password, &config, true)?
+                .ok_or_else(|| anyhow!("No new password provided"))?;
+            operations::reset_password(
+                user.as_ref(),
+                password.plain(),
+                force_change,
+                &config,
+                ldap_credential,
+            )?;
+            password.print_if_generated(user.as_ref());
+        }
+        Commands::Key { action } => {
+            let config = config::load_config(args.config_file)?.config;
+            let ldap_credential = LdapCliCredential::new(&config);
+            match action {
+                KeyCommands::Add { user, path } => {
+                    let keys = SshPublicKey::read_many_from_file(&path)?;
+                    operations::add_ssh_keys(user.as_ref(), &keys, &config, ldap_credential)?
+                }
+                KeyCommands::Remove { user, fingerprint } => operations::remove_ssh_key(
+                    user.as_ref(),
+                    fingerprint.as_ref(),
+                    &config,
+                    ldap_credential,
+                )?,
+                KeyCommands::List { user } => {
+                    operations::print_ssh_keys_to_stdout(user.as_ref(), &config, ldap_credential)?
+                }
+            }
+        }
+        Commands::Export { format, output } => {
+            let config = config::load_config(args.config_file)?.config;
+            let ldap_credential = LdapCliCredential::new(&config);
+            let exported = match format {
+                ExportFormat::Ldif => operations::export_ldif(&config, ldap_credential)?,
+            };
+            match output {
+                Some(path) => fs::write(&path, exported).with_context(|| {
+                    format!("Unable to write export to {}", path.to_string_lossy())
+                })?,
+                None => print!("{}", exported),
+            }
+        }
+        Commands::CheckLdapSchema => {
+            let config = config::load_config(args.config_file)?.config;
+            let ldap_credential = LdapCliCredential::new(&config);
+            operations::check_ldap_schema(&config, ldap_credential)?
+        }
+        Commands::ImportLdif { path } => {
+            let config = config::load_config(args.config_file)?.config;
+            let ldap_credential = LdapCliCredential::new(&config);
+            operations::import_ldif(&path, &config, ldap_credential)?
+        }
+        Commands::Usage {
+            user,
+            from,
+            to,
+            format,
+            clusters,
+            ssh_path,
+        } => {
+            let config = config::load_config(args.config_file)?.config;
+            let cli_ssh_credential = CliSshCredential::new(&config, &ssh_path);
+            operations::print_usage_of_user_to_stdout(
+                user.as_ref(),
+                from,
+                to,
+                format,
+                &clusters,
+                &config,
+                cli_ssh_credential,
+            )?;
+        }
+        Commands::Slurm { action, ssh_path } => {
+            let config = config::load_config(args.config_file)?.config;
+            let cli_ssh_credential = CliSshCredential::new(&config, &ssh_path);
+            match action {
+                SlurmCommands::Account { action, clusters } => operations::manage_slurm_account(
+                    action,
+                    &clusters,
+                    &config,
+                    cli_ssh_credential,
+                )?,
+                SlurmCommands::Qos { action } => {
+                    operations::manage_slurm_qos(action, &config, cli_ssh_credential)?
+                }
+                SlurmCommands::Bootstrap => {
+                    operations::bootstrap_slurm(&config, cli_ssh_credential)?
+                }
+            }
+        }
         Commands::List {
             on_which_sys,
             simple_output_for_ldap,
+            filter,
         } => {
             let config = config::load_config(args.config_file)?.config;
             let ldap_credential = LdapCliCredential::new(&config);
@@ -106,6 +230,7 @@ pub fn run_mgmt(args: cli::GeneralArgs) -> AppResult {
                 &config,
                 on_which_sys,
                 simple_output_for_ldap.unwrap_or(false),
+                &filter,
                 ldap_credential,
                 cli_ssh_credential,
             )?
@@ -1,6 +1,6 @@
 //! Functions to acquire information from the user by asking for it in the terminal.
 
-use std::io;
+use std::io::{self, IsTerminal};
 
 use usermgmt_lib::{
     prelude::{anyhow, AppResult, Context},
@@ -98,6 +98,29 @@ pub fn cli_ask_for_password(prompt: &str) -> AppResult<Option<String>> {
     Ok(trim_input(&password))
 }
 
+/// Ask the user for a new password over the terminal. The password has to be entered twice.
+/// If stdin is not a terminal, the first line of stdin is taken as the password instead.
+///
+/// # Errors
+///
+/// - if reading from the terminal or stdin does not work.
+/// - if no password or only white spaces are given.
+/// - if the password and its repetition differ.
+pub fn ask_for_new_password() -> AppResult<String> {
+    if !io::stdin().is_terminal() {
+        return line_input_from_user()?.ok_or_else(|| anyhow!("No password provided via stdin"));
+    }
+
+    let password = cli_ask_for_password("Enter the new password: ")?
+        .ok_or_else(|| anyhow!("No password provided"))?;
+    let repeated = cli_ask_for_password("Repeat the new password: ")?
+        .ok_or_else(|| anyhow!("No password repetition provided"))?;
+    if password != repeated {
+        return Err(anyhow!("Passwords do not match"));
+    }
+    Ok(password)
+}
+
 #[cfg(test)]
 mod testing {
     use super::*;
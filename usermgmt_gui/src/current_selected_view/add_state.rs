@@ -4,7 +4,7 @@ use usermgmt_lib::{
     util::TrimmedNonEmptyText,
 };
 
-use crate::io_resource_manager::IoResourceManager;
+use crate::{general_utils, io_resource_manager::IoResourceManager};
 
 #[derive(Default, Debug)]
 pub struct AddState {
@@ -16,6 +16,7 @@ pub struct AddState {
     pub default_qos: String,
     pub publickey: String,
     pub qos: Vec<String>,
+    pub extra_attributes: Vec<(String, String)>,
     pub adding_res_io: IoResourceManager<String>,
     pub last_added_username: String,
 }
@@ -53,6 +54,9 @@ impl AddState {
                 default_qos: some_if_not_blank_str(&self.default_qos),
                 publickey: some_if_not_blank_str(&self.publickey),
                 qos,
+                extra_attributes: general_utils::extra_attributes_from_pairs(
+                    &self.extra_attributes,
+                )?,
             },
         );
 
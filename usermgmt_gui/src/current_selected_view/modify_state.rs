@@ -14,6 +14,8 @@ pub struct ModifyState {
     pub default_qos: String,
     pub publickey: String,
     pub qos: Vec<String>,
+    pub extra_attributes: Vec<(String, String)>,
+    pub unset_extra_attributes: Vec<String>,
     pub res_io: IoResourceManager<String>,
     pub last_added_username: String,
 }
@@ -31,8 +33,15 @@ impl ModifyState {
             default_qos: general_utils::some_if_not_blank_str(&self.default_qos),
             publickey: general_utils::some_if_not_blank_str(&self.publickey),
             qos: self.qos.clone(),
+            extra_attributes: general_utils::extra_attributes_from_pairs(&self.extra_attributes)?,
         };
-        let entity = Entity::new(firstname, lastname, common_fields, config)?;
+        let unset_extra_attributes = self
+            .unset_extra_attributes
+            .iter()
+            .filter_map(|name| general_utils::some_if_not_blank_str(name))
+            .collect();
+        let entity = Entity::new(firstname, lastname, common_fields, config)?
+            .with_unset_extra_attributes(unset_extra_attributes, config)?;
         let changes = ChangesToUser::try_new(entity)?;
         Ok(changes)
     }
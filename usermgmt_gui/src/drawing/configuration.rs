@@ -243,11 +243,27 @@ fn construct_fields(config: &mut MgmtConfig, map: CacheForConfFields) -> Vec<Con
             )
                 .into()
         };
+        ($group:ident.$field:ident) => {
+            (
+                &mut config.$group.$field,
+                snake_to_label(
+                    concat!(stringify!($group), "_", stringify!($field)),
+                    map.clone(),
+                ),
+                None,
+            )
+                .into()
+        };
     }
 
     let mut fields: Vec<ConfiField> = vec![
         create_conf_field!(objectclass_common),
         create_conf_field!(ldap_add_created_at),
+        create_conf_field!(ldap_track_modifications),
+        create_conf_field!(ldap_page_size),
+        create_conf_field!(ldap_extra_attributes),
+        create_conf_field!(generated_password_length),
+        create_conf_field!(generated_password_charset),
         create_conf_field!(student_default_qos),
         create_conf_field!(staff_default_qos),
         create_conf_field!(default_ssh_user),
@@ -257,16 +273,24 @@ fn construct_fields(config: &mut MgmtConfig, map: CacheForConfFields) -> Vec<Con
         create_conf_field!(valid_qos),
         create_conf_field!(student_qos),
         create_conf_field!(staff_qos),
+        create_conf_field!(student_slurm_limits),
+        create_conf_field!(staff_slurm_limits),
         create_conf_field!(valid_slurm_groups),
         create_conf_field!(compute_nodes),
         create_conf_field!(ldap_default_user),
         create_conf_field!(ldap_domain_components),
         create_conf_field!(ldap_org_unit),
+        create_conf_field!(ldap_student_org_unit),
+        create_conf_field!(ldap_staff_org_unit),
+        create_conf_field!(ldap_faculty_org_unit),
         create_conf_field!(ldap_bind_org_unit),
         create_conf_field!(ldap_bind_prefix),
         create_conf_field!(ldap_readonly_user_prefix),
         create_conf_field!(ldap_readonly_bind),
         create_conf_field!(ldap_server),
+        create_conf_field!(ldap_writable_server),
+        create_conf_field!(ldap_connect_timeout_secs),
+        create_conf_field!(cancel_jobs_timeout_secs),
         create_conf_field!(ldap_readonly_user),
         create_conf_field!(ldap_readonly_pw),
         create_conf_field!(include_ldap),
@@ -287,11 +311,33 @@ fn construct_fields(config: &mut MgmtConfig, map: CacheForConfFields) -> Vec<Con
         create_conf_field!(quota_home_softlimit),
         create_conf_field!(quota_home_hardlimit),
         create_conf_field!(login_shell),
+        create_conf_field!(home_directory_template),
         create_conf_field!(student_gid),
         create_conf_field!(staff_gid),
         create_conf_field!(faculty_gid),
         create_conf_field!(sacctmgr_path),
         create_conf_field!(ssh_key_path),
+        create_conf_field!(slurm_rest_url),
+        create_conf_field!(slurm_rest_api_version),
+        create_conf_field!(slurm_rest_token_file),
+        create_conf_field!(slurm_rest_token_command),
+        create_conf_field!(ldap_attributes.username),
+        create_conf_field!(ldap_attributes.common_name),
+        create_conf_field!(ldap_attributes.uid_number),
+        create_conf_field!(ldap_attributes.gid_number),
+        create_conf_field!(ldap_attributes.firstname),
+        create_conf_field!(ldap_attributes.lastname),
+        create_conf_field!(ldap_attributes.mail),
+        create_conf_field!(ldap_attributes.default_qos),
+        create_conf_field!(ldap_attributes.qos),
+        create_conf_field!(ldap_attributes.publickey),
+        create_conf_field!(ldap_attributes.home_directory),
+        create_conf_field!(ldap_attributes.login_shell),
+        create_conf_field!(ldap_attributes.created_at),
+        create_conf_field!(ldap_attributes.modified_at),
+        create_conf_field!(ldap_attributes.modified_by),
+        create_conf_field!(ldap_attributes.password),
+        create_conf_field!(ldap_attributes.shadow_last_change),
     ];
     fields.sort();
     fields
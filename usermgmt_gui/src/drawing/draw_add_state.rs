@@ -62,6 +62,13 @@ pub fn draw(ui: &mut egui::Ui, window: &mut UsermgmtWindow) {
                 &mut adding_fields.qos,
                 &GroupDrawing::new(texts.qos()).add_tooltip(tooltips.qos()),
             );
+            draw_utils::key_value_list_view(
+                ui,
+                &window.settings,
+                &mut adding_fields.extra_attributes,
+                &GroupDrawing::new(texts.extra_attributes())
+                    .add_tooltip(tooltips.extra_attributes()),
+            );
         });
     }
 
@@ -109,6 +116,7 @@ pub fn draw(ui: &mut egui::Ui, window: &mut UsermgmtWindow) {
                         &prep.config,
                         prep.ldap_cred,
                         prep.ssh_cred,
+                        || {},
                     )?;
                     Ok(username)
                 },
@@ -17,6 +17,13 @@ pub fn draw(ui: &mut egui::Ui, window: &mut UsermgmtWindow) {
                     .with_tooltip(tooltips.username()),
             );
         });
+        let texts = settings.texts();
+        draw_utils::draw_box_group(ui, settings, &GroupDrawing::new(texts.optional()), |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut remove_state.cancel_jobs, texts.cancel_jobs());
+                draw_utils::tooltip_widget(ui, settings, tooltips.cancel_jobs());
+            });
+        });
         !remove_state.username.trim().is_empty()
     };
     draw_utils::draw_credentials(ui, window, false);
@@ -50,10 +57,12 @@ fn delete_user(window: &mut UsermgmtWindow) {
         general_utils::prep_conf_creds(window, |app| &mut app.remove_state.remove_res_io, false)
     {
         let username = window.remove_state.username.clone();
+        let cancel_jobs = window.remove_state.cancel_jobs;
         let _ = window.remove_state.remove_res_io.spawn_task(
             move || {
                 operations::delete_user(
                     &username,
+                    cancel_jobs,
                     &prep.on_which_sys,
                     &prep.config,
                     prep.ldap_cred,
@@ -4,8 +4,11 @@ use crate::current_selected_view::ConnectionState;
 use drawing::draw_utils::GroupDrawing;
 use egui_extras::{Size, StripBuilder};
 use usermgmt_lib::{
-    ldap::{list_ldap_users, LDAPConfig, LdapSearchResult, LdapSimpleCredential},
-    slurm::{self, ListedUser},
+    config::LdapAttributes,
+    ldap::{
+        list_ldap_users, LDAPConfig, LdapSession, LdapSimpleCredential, LdapUser, ListedLdapUsers,
+    },
+    slurm::{self, SlurmAssociation},
     ssh::{SshConnection, SshGivenCredential},
 };
 
@@ -19,6 +22,12 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
     slurm_list_btn(window, ui);
     ui.separator();
     let listing_state = &window.listin_state;
+    let attributes = window
+        .conf_state
+        .io_conf
+        .success()
+        .map(|loaded| loaded.config.ldap_attributes.clone())
+        .unwrap_or_default();
 
     let settings = &window.settings;
     StripBuilder::new(ui)
@@ -32,7 +41,7 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
         ) // top cell
         .vertical(|mut strip| {
             strip.cell(|ui| {
-                draw_listed_ldap_users(ui, listing_state, &window.settings);
+                draw_listed_ldap_users(ui, listing_state, &window.settings, &attributes);
             });
             strip.cell(|ui| {
                 draw_listed_slurm_users(ui, &window.settings, listing_state);
@@ -54,7 +63,7 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
             (
                 || texts.listing_slurm_init().to_string(),
                 || texts.listing_slurm_loading().to_string(),
-                |_: &ListedUser| texts.listing_slurm_success().to_string(),
+                |_: &Vec<SlurmAssociation>| texts.listing_slurm_success().to_string(),
                 || texts.listing_slurm_failure().to_string(),
             ),
         );
@@ -72,6 +81,7 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
         ui: &mut egui::Ui,
         listing_state: &ListingState,
         settings: &Settings,
+        attributes: &LdapAttributes,
     ) {
         let texts = settings.texts();
         let status = listing_state.list_ldap_res.status();
@@ -83,29 +93,36 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
             (
                 || texts.listing_ldap_init().to_string(),
                 || texts.listing_ldap_loading().to_string(),
-                |_: &LdapSearchResult| texts.listing_ldap_success().to_string(),
+                |_: &ListedLdapUsers| texts.listing_ldap_success().to_string(),
                 || texts.listing_ldap_failure().to_string(),
             ),
         );
         if let IoTaskStatus::Successful(ldap_users) = status {
             ui.separator();
-            draw_ldap_tables(ui, ldap_users, settings)
+            for invalid in ldap_users.invalid_entries.iter() {
+                ui.label(format!(
+                    "{} {}: {}",
+                    texts.listing_ldap_invalid_entry(),
+                    invalid.dn,
+                    invalid.reason
+                ));
+            }
+            draw_ldap_tables(ui, ldap_users, settings, attributes)
         }
     }
 
-    fn draw_slurm_table(ui: &mut egui::Ui, settings: &Settings, slurm_users: &ListedUser) {
+    fn draw_slurm_table(ui: &mut egui::Ui, settings: &Settings, slurm_users: &[SlurmAssociation]) {
         use egui_extras::{Column, TableBuilder};
         draw_table(ui, settings, slurm_users);
 
-        fn draw_table(ui: &mut egui::Ui, settings: &Settings, raw: &ListedUser) {
+        fn draw_table(ui: &mut egui::Ui, settings: &Settings, associations: &[SlurmAssociation]) {
             let mut table = TableBuilder::new(ui)
                 .striped(true)
                 .resizable(true)
                 .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
                 .min_scrolled_height(0.);
 
-            let headers = raw.headers();
-            let rows = raw.fields();
+            let headers = SlurmAssociation::headers();
             table = table
                 .columns(Column::auto(), headers.len().saturating_sub(1))
                 .column(Column::remainder());
@@ -118,9 +135,9 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
                     }
                 })
                 .body(|mut body| {
-                    for single_row in rows {
+                    for association in associations {
                         body.row(10., |mut row| {
-                            for column in single_row {
+                            for column in association.cells() {
                                 row.col(|ui| _ = ui.label(column));
                             }
                         });
@@ -171,12 +188,11 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
                 ),
             );
             let mgmt_conf = mgmt_conf.config.clone();
-            let failed_parsing_slurm = text.failed_parsing_slurm().clone();
             _ = window.listin_state.list_slurm_user_res.spawn_task(
                 move || {
                     let connection = SshConnection::from_head_node(&mgmt_conf, ssh_credentials);
-                    let slurm_users_raw = slurm::list_users(&mgmt_conf, &connection, true)?;
-                    ListedUser::new(&slurm_users_raw).ok_or(anyhow!(failed_parsing_slurm))
+                    let rest = slurm::SlurmRestClient::from_config_if_selected(&mgmt_conf)?;
+                    slurm::list_users(&mgmt_conf, &connection, rest.as_ref(), Default::default())
                 },
                 String::from("Getting slurm user"),
             );
@@ -223,7 +239,11 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
                             &mgmt_conf,
                             LdapSimpleCredential::new(username, password),
                         )?;
-                        list_ldap_users(&config)
+                        list_ldap_users(
+                            &mgmt_conf,
+                            &Default::default(),
+                            &mut LdapSession::from_ldap_config(config),
+                        )
                     },
                     "Listing ldap user".to_owned(),
                 );
@@ -270,11 +290,21 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
         no_owned.map(|unowned| unowned.to_string())
     }
 
-    fn draw_ldap_tables(ui: &mut egui::Ui, raw: &LdapSearchResult, settings: &Settings) {
+    fn draw_ldap_tables(
+        ui: &mut egui::Ui,
+        listed: &ListedLdapUsers,
+        settings: &Settings,
+        attributes: &LdapAttributes,
+    ) {
         use egui_extras::{Column, TableBuilder};
-        draw_table(ui, raw, settings);
+        draw_table(ui, listed, settings, attributes);
 
-        fn draw_table(ui: &mut egui::Ui, raw: &LdapSearchResult, settings: &Settings) {
+        fn draw_table(
+            ui: &mut egui::Ui,
+            listed: &ListedLdapUsers,
+            settings: &Settings,
+            attributes: &LdapAttributes,
+        ) {
             // Need to give manual id otherwise the next table causes a clash
             // on the scroll aread id.
             // Reference: https://docs.rs/egui_extras/latest/egui_extras/struct.TableBuilder.html
@@ -285,25 +315,26 @@ pub fn draw(window: &mut UsermgmtWindow, ui: &mut egui::Ui) {
                     .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
                     .min_scrolled_height(0.);
 
-                let headers = raw.headers();
-                let rows = raw.fields();
+                let columns = LdapUser::listing_columns(attributes);
                 table = table
-                    .columns(Column::auto(), headers.len().saturating_sub(1))
+                    .columns(Column::auto(), columns.len().saturating_sub(1))
                     .column(Column::remainder());
                 table
                     .header(settings.header_table_height, |mut header| {
-                        for &next_title in headers.iter() {
+                        for (next_title, _) in columns.iter() {
                             header.col(|ui| {
                                 ui.strong(next_title);
                             });
                         }
                     })
                     .body(|mut body| {
-                        for single_row in rows.iter() {
+                        for user in listed.users.iter() {
                             body.row(10., |mut row| {
-                                for column in single_row {
+                                for (_, values_of) in columns.iter() {
                                     row.col(|ui| {
-                                        _ = ui.label(column.join(&settings.ldap_multi_field_sep))
+                                        _ = ui.label(
+                                            values_of(user).join(&settings.ldap_multi_field_sep),
+                                        )
                                     });
                                 }
                             });
@@ -45,23 +45,54 @@ pub fn list_view(
                 }
             });
         }
-        if !to_delete.is_empty() {
-            let taken = std::mem::take(list_field);
-            *list_field = taken
-                .into_iter()
-                .enumerate()
-                .filter_map(|(index, element)| {
-                    if !to_delete.contains(&index) {
-                        Some(element)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
+        remove_from_list(list_field, &to_delete);
+    });
+}
+
+/// Same as [`list_view`] except every item consists of a name and a value.
+pub fn key_value_list_view(
+    ui: &mut egui::Ui,
+    settings: &Settings,
+    list_field: &mut Vec<(String, String)>,
+    group_drawing: &GroupDrawing,
+) {
+    let text = settings.texts();
+    draw_box_group(ui, settings, group_drawing, |ui| {
+        if ui.button(text.btn_new_item()).clicked() {
+            list_field.push(Default::default());
         }
+        let mut to_delete: Vec<usize> = Default::default();
+        for (index, (key, value)) in &mut list_field.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(key);
+                ui.label("=");
+                ui.text_edit_singleline(value);
+                if ui.button(text.btn_list_remove()).clicked() {
+                    to_delete.push(index);
+                }
+            });
+        }
+        remove_from_list(list_field, &to_delete);
     });
 }
 
+fn remove_from_list<T>(list_field: &mut Vec<T>, to_delete: &[usize]) {
+    if !to_delete.is_empty() {
+        let taken = std::mem::take(list_field);
+        *list_field = taken
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, element)| {
+                if !to_delete.contains(&index) {
+                    Some(element)
+                } else {
+                    None
+                }
+            })
+            .collect();
+    }
+}
+
 pub fn draw_file_path(ui: &mut egui::Ui, window: &mut UsermgmtWindow) {
     let conf_state = &window.conf_state;
     let mut path = window.conf_path_owned();
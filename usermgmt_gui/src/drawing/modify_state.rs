@@ -111,5 +111,18 @@ fn draw_typing_fields(ui: &mut egui::Ui, settings: &Settings, modify_state: &mut
             &mut modify_state.qos,
             &GroupDrawing::new(texts.qos()).add_tooltip(tooltips.qos()),
         );
+        draw_utils::key_value_list_view(
+            ui,
+            settings,
+            &mut modify_state.extra_attributes,
+            &GroupDrawing::new(texts.extra_attributes()).add_tooltip(tooltips.extra_attributes()),
+        );
+        draw_utils::list_view(
+            ui,
+            settings,
+            &mut modify_state.unset_extra_attributes,
+            &GroupDrawing::new(texts.unset_extra_attributes())
+                .add_tooltip(tooltips.unset_extra_attributes()),
+        );
     });
 }
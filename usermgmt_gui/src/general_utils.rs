@@ -8,9 +8,9 @@ use usermgmt_lib::{
     cli::OnWhichSystem,
     config::{self, LoadedMgmtConfig, MgmtConfig},
     ldap::LdapSimpleCredential,
-    prelude::{anyhow, AppResult},
+    prelude::{anyhow, AppResult, Context},
     ssh::SshGivenCredential,
-    util::TrimmedNonEmptyText,
+    util::{ExtraLdapAttribute, TrimmedNonEmptyText},
 };
 
 use crate::io_resource_manager::{IoResourceManager, IoTaskStatus};
@@ -25,6 +25,29 @@ where
 pub fn some_if_not_blank_str(input: &str) -> Option<TrimmedNonEmptyText> {
     input.try_into().ok()
 }
+/// Converts pairs of name and value from the GUI into extra LDAP attributes.
+/// Pairs where name and value are both blank are skipped.
+///
+/// # Errors
+///
+/// - If only one of name or value is blank.
+pub fn extra_attributes_from_pairs(
+    pairs: &[(String, String)],
+) -> AppResult<Vec<ExtraLdapAttribute>> {
+    pairs
+        .iter()
+        .filter(|(name, value)| !(name.trim().is_empty() && value.trim().is_empty()))
+        .map(|(name, value)| {
+            let name = TrimmedNonEmptyText::try_from(name.as_str()).with_context(|| {
+                format!("Name of extra LDAP attribute with value {} is empty", value)
+            })?;
+            let value = TrimmedNonEmptyText::try_from(value.as_str())
+                .with_context(|| format!("Value of extra LDAP attribute {} is empty", name))?;
+            Ok(ExtraLdapAttribute::new(name, value))
+        })
+        .collect()
+}
+
 pub fn is_some_and_not_empty(input: Option<&str>) -> bool {
     input
         .map(|input| some_if_not_blank_str(input).is_some())
@@ -81,6 +81,9 @@ pub struct Texts {
     default_qos: ReadonlyText,
     public_key: ReadonlyText,
     group: ReadonlyText,
+    extra_attributes: ReadonlyText,
+    unset_extra_attributes: ReadonlyText,
+    cancel_jobs: ReadonlyText,
     btn_action_conf_load: ReadonlyText,
     btn_action_conf_save: ReadonlyText,
     btn_action_conf_default: ReadonlyText,
@@ -100,6 +103,7 @@ pub struct Texts {
     listing_ldap_loading: ReadonlyText,
     listing_ldap_success: ReadonlyText,
     listing_ldap_failure: ReadonlyText,
+    listing_ldap_invalid_entry: ReadonlyText,
     modify_init: ReadonlyText,
     modify_loading: ReadonlyText,
     modify_success: ReadonlyText,
@@ -108,7 +112,6 @@ pub struct Texts {
     mode_ldap: ReadonlyText,
     mode_slurm: ReadonlyText,
     mode_directory: ReadonlyText,
-    failed_parsing_slurm: ReadonlyText,
     ldap_cred_missing: ReadonlyText,
     ssh_cred_missing: ReadonlyText,
 }
@@ -128,6 +131,9 @@ pub struct TextTooltip {
     group: String,
     default_qos: String,
     qos: String,
+    extra_attributes: String,
+    unset_extra_attributes: String,
+    cancel_jobs: String,
     conf_student_qos: String,
     conf_student_default_qos: String,
     conf_run_slurm_remote: String,
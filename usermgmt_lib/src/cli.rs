@@ -4,12 +4,15 @@ pub use on_which_system::{OnSlurmLdapOnlyCli, OnWhichSystem, OnWhichSystemCli, O
 
 mod on_which_system;
 
-use clap::{Args, Parser, Subcommand};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use const_format::concatcp;
 use derive_more::Into;
 
 use crate::prelude::*;
-use crate::util::TrimmedNonEmptyText;
+use crate::slurm::{AccountProperties, QosProperties};
+use crate::util::{ExtraLdapAttribute, SlurmLimit, TrimmedNonEmptyText, UserAssociation};
+use crate::{Group, MgmtConfig};
 
 pub const fn short_about() -> &'static str {
     "Simultaneous user management for Slurm and LDAP"
@@ -52,6 +55,8 @@ pub enum Commands {
         #[command(flatten)]
         to_add: UserToAdd,
         #[command(flatten)]
+        password: PasswordOptions,
+        #[command(flatten)]
         on_which_sys: OnWhichSystemCli,
     },
     /// Modify user in Slurm and/or LDAP
@@ -68,6 +73,10 @@ pub enum Commands {
         /// A valid username e.g. wagnerdo.
         #[clap(value_parser = trimmed_non_empty)]
         user: TrimmedNonEmptyText,
+        /// Cancel pending and running jobs of the user in Slurm and wait until they left the queue.
+        /// Without it, the deletion is refused if the user has jobs in Slurm.
+        #[clap(long, verbatim_doc_comment)]
+        cancel_jobs: bool,
         #[command(flatten)]
         on_which_sys: OnWhichSystemCli,
     },
@@ -79,12 +88,276 @@ pub enum Commands {
         /// Print a comma separated list of LDAP users instead of formatted table
         #[clap(long, verbatim_doc_comment)]
         simple_output_for_ldap: Option<bool>,
+        #[command(flatten)]
+        filter: ListFilter,
+    },
+    /// Replace the password of a user in LDAP.
+    /// Prompts for the new password unless --generate-password is given.
+    #[clap(visible_alias = "rp", verbatim_doc_comment)]
+    ResetPassword {
+        /// A valid username e.g. wagnerdo.
+        #[clap(value_parser = trimmed_non_empty)]
+        user: TrimmedNonEmptyText,
+        #[command(flatten)]
+        password: PasswordOptions,
+        /// Force the user to change the password at the next login by setting shadowLastChange to 0.
+        #[clap(long)]
+        force_change: bool,
+    },
+    /// Add, remove or list SSH public keys of a user in LDAP
+    #[clap(visible_alias = "k")]
+    Key {
+        #[clap(subcommand)]
+        action: KeyCommands,
+    },
+    /// Check if the LDAP server knows every object class in objectclass_common
+    /// and every attribute written by this application.
+    /// The same check is performed before a user is added to LDAP.
+    #[clap(verbatim_doc_comment)]
+    CheckLdapSchema,
+    /// Export all users in LDAP with the attributes managed by this application
+    Export {
+        /// Format of the exported users.
+        #[clap(long, value_enum, default_value_t)]
+        format: ExportFormat,
+        /// Write the export into this file instead of stdout.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Add all users within a LDIF file to LDAP, e.g. one created by the export command.
+    /// Nothing is added if any username or uidNumber within the file is already taken.
+    #[clap(verbatim_doc_comment)]
+    ImportLdif {
+        /// Path to the LDIF file.
+        path: PathBuf,
+    },
+    /// Show the usage of a user in Slurm, e.g. before deleting or downgrading the user.
+    /// Lists CPU hours, GPU hours and jobs per account and QOS via sacct and sreport.
+    #[clap(verbatim_doc_comment)]
+    Usage {
+        /// A valid username e.g. wagnerdo.
+        #[clap(value_parser = trimmed_non_empty)]
+        user: TrimmedNonEmptyText,
+        /// First day of the report like 2024-01-01. Defaults to 30 days before --to.
+        #[clap(long, value_parser = date)]
+        from: Option<NaiveDate>,
+        /// Last day of the report like 2024-01-31, inclusive. Defaults to today.
+        #[clap(long, value_parser = date)]
+        to: Option<NaiveDate>,
+        /// Format of the printed report.
+        #[clap(long, value_enum, default_value_t)]
+        format: UsageFormat,
+        /// Name of a Slurm cluster listed in slurm_clusters of conf.toml to report on.
+        /// Can be given multiple times. Reports on all listed clusters if omitted.
+        #[clap(long = "cluster", value_parser = slurm_name, verbatim_doc_comment)]
+        clusters: Vec<TrimmedNonEmptyText>,
+        /// Path to key pair used to establish the SSH connection.
+        /// Has priority over `ssh_key_path` provided in conf.toml.
+        #[arg(long, verbatim_doc_comment)]
+        ssh_path: Option<PathBuf>,
+    },
+    /// Manage accounts in Slurm
+    Slurm {
+        #[clap(subcommand)]
+        action: SlurmCommands,
+        /// Path to key pair used to establish the SSH connection.
+        /// Has priority over `ssh_key_path` provided in conf.toml.
+        #[arg(long, global = true, verbatim_doc_comment)]
+        ssh_path: Option<PathBuf>,
     },
     #[clap(visible_alias = "gc")]
     /// Prints a default configuration (conf.toml) to stdout.
     GenerateConfig,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Formats in which users can be exported
+pub enum ExportFormat {
+    /// LDAP Data Interchange Format (RFC 2849)
+    #[default]
+    Ldif,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Formats in which the usage of a user is printed
+pub enum UsageFormat {
+    /// Tables for humans
+    #[default]
+    Table,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+/// CLI sub commands for the SSH public keys of a user in LDAP
+pub enum KeyCommands {
+    /// Add the SSH public keys within a file to the keys of a user
+    Add {
+        /// A valid username e.g. wagnerdo.
+        #[clap(value_parser = trimmed_non_empty)]
+        user: TrimmedNonEmptyText,
+        /// Path to a file with SSH public keys in the OpenSSH format, one key per line.
+        path: PathBuf,
+    },
+    /// Remove a SSH public key from a user
+    Remove {
+        /// A valid username e.g. wagnerdo.
+        #[clap(value_parser = trimmed_non_empty)]
+        user: TrimmedNonEmptyText,
+        /// SHA256 fingerprint of the key as shown by the list command e.g. SHA256:v20uEHPX...
+        #[clap(value_parser = trimmed_non_empty)]
+        fingerprint: TrimmedNonEmptyText,
+    },
+    /// List the fingerprints, types and comments of all SSH public keys of a user
+    List {
+        /// A valid username e.g. wagnerdo.
+        #[clap(value_parser = trimmed_non_empty)]
+        user: TrimmedNonEmptyText,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+/// CLI sub commands for managing Slurm
+pub enum SlurmCommands {
+    /// Add, modify, delete or list accounts in Slurm
+    Account {
+        #[clap(subcommand)]
+        action: AccountCommands,
+        /// Name of a Slurm cluster listed in slurm_clusters of conf.toml to act on.
+        /// Can be given multiple times. Acts on all listed clusters if omitted.
+        #[clap(long = "cluster", global = true, value_parser = slurm_name, verbatim_doc_comment)]
+        clusters: Vec<TrimmedNonEmptyText>,
+    },
+    /// Create, modify, delete, list or check QOS in Slurm
+    Qos {
+        #[clap(subcommand)]
+        action: QosCommands,
+    },
+    /// Add every account of valid_slurm_groups in conf.toml which does not exist in Slurm yet.
+    /// Prepares a fresh Slurm database for adding users.
+    #[clap(verbatim_doc_comment)]
+    Bootstrap,
+}
+
+#[derive(Subcommand, Debug)]
+/// CLI sub commands for accounts in Slurm
+pub enum AccountCommands {
+    /// Add an account to Slurm
+    Add {
+        /// Name of the account e.g. staff.
+        #[clap(value_parser = slurm_name)]
+        name: TrimmedNonEmptyText,
+        #[command(flatten)]
+        properties: AccountFields,
+    },
+    /// Change properties of an account in Slurm
+    Modify {
+        /// Name of the account e.g. staff.
+        #[clap(value_parser = slurm_name)]
+        name: TrimmedNonEmptyText,
+        #[command(flatten)]
+        properties: AccountFields,
+    },
+    /// Delete an account from Slurm
+    Delete {
+        /// Name of the account e.g. staff.
+        #[clap(value_parser = slurm_name)]
+        name: TrimmedNonEmptyText,
+    },
+    /// List all accounts in Slurm with their parent, organization, description and fairshare
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+/// CLI sub commands for QOS in Slurm
+pub enum QosCommands {
+    /// Create a QOS in Slurm
+    Create {
+        /// Name of the QOS e.g. basic.
+        #[clap(value_parser = slurm_name)]
+        name: TrimmedNonEmptyText,
+        #[command(flatten)]
+        limits: QosFields,
+    },
+    /// Change limits of a QOS in Slurm
+    Modify {
+        /// Name of the QOS e.g. basic.
+        #[clap(value_parser = slurm_name)]
+        name: TrimmedNonEmptyText,
+        #[command(flatten)]
+        limits: QosFields,
+    },
+    /// Delete a QOS from Slurm
+    Delete {
+        /// Name of the QOS e.g. basic.
+        #[clap(value_parser = slurm_name)]
+        name: TrimmedNonEmptyText,
+    },
+    /// List all QOS in Slurm with their priority and limits
+    List,
+    /// Report QOS named in valid_qos, student_qos, staff_qos or the default QOS of conf.toml
+    /// which do not exist in Slurm and QOS in Slurm which are not listed in valid_qos.
+    #[clap(verbatim_doc_comment)]
+    Check,
+}
+
+/// Limits of a QOS in Slurm
+#[derive(Args, Debug, Clone)]
+pub struct QosFields {
+    /// Priority of jobs with this QOS.
+    #[clap(long)]
+    pub priority: Option<u32>,
+    /// Maximum wall clock time of a job e.g. 2-00:00:00 for 2 days or 90 for 90 minutes.
+    #[clap(long, value_parser = slurm_time)]
+    pub max_wall: Option<TrimmedNonEmptyText>,
+    /// Maximum trackable resources of all running jobs of a user e.g. cpu=16,gres/gpu=2.
+    #[clap(long, value_parser = slurm_tres)]
+    pub max_tres_per_user: Option<TrimmedNonEmptyText>,
+    /// Description of the QOS.
+    #[clap(long, value_parser = trimmed_non_empty)]
+    pub description: Option<TrimmedNonEmptyText>,
+}
+
+impl From<QosFields> for QosProperties {
+    fn from(value: QosFields) -> Self {
+        Self {
+            priority: value.priority,
+            max_wall: value.max_wall.map(|max_wall| max_wall.to_string()),
+            max_tres_per_user: value.max_tres_per_user.map(|tres| tres.to_string()),
+            description: value.description.map(|description| description.to_string()),
+        }
+    }
+}
+
+/// Properties of an account in Slurm
+#[derive(Args, Debug, Clone)]
+pub struct AccountFields {
+    /// Parent account e.g. root.
+    #[clap(long, value_parser = slurm_name)]
+    pub parent: Option<TrimmedNonEmptyText>,
+    /// Organization to which the account belongs.
+    #[clap(long, value_parser = trimmed_non_empty)]
+    pub organization: Option<TrimmedNonEmptyText>,
+    /// Description of the account.
+    #[clap(long, value_parser = trimmed_non_empty)]
+    pub description: Option<TrimmedNonEmptyText>,
+    /// Share of the parent account for the fair share scheduling.
+    #[clap(long)]
+    pub fairshare: Option<u32>,
+}
+
+impl From<AccountFields> for AccountProperties {
+    fn from(value: AccountFields) -> Self {
+        Self {
+            parent: value.parent.map(|parent| parent.to_string()),
+            organization: value
+                .organization
+                .map(|organization| organization.to_string()),
+            description: value.description.map(|description| description.to_string()),
+            fairshare: value.fairshare,
+        }
+    }
+}
+
 /// Defines options for modifying a user
 #[derive(Args, Debug, Clone, Into)]
 pub struct Modifiable {
@@ -96,6 +369,28 @@ pub struct Modifiable {
     lastname: Option<TrimmedNonEmptyText>,
     #[command(flatten)]
     common_user_fields: CommonUserFields,
+    /// Name of an extra LDAP attribute to remove from the user e.g. telephoneNumber.
+    /// Can be given multiple times. The name must be listed in ldap_extra_attributes of conf.toml.
+    #[clap(long = "unset-attr", value_parser = trimmed_non_empty, verbatim_doc_comment)]
+    unset_attributes: Vec<TrimmedNonEmptyText>,
+    /// Resource limit of the user in Slurm e.g. GrpTRES=cpu=64, MaxJobs=10 or MaxWall=2-00:00:00.
+    /// Can be given multiple times. The value -1 removes the limit.
+    /// Supported are GrpTRES, GrpTRESMins, GrpJobs, GrpSubmitJobs, MaxJobs, MaxSubmitJobs, MaxTRES and MaxWall.
+    #[clap(long = "limit", value_parser = slurm_limit, verbatim_doc_comment)]
+    slurm_limits: Vec<SlurmLimit>,
+    /// Slurm association to add to the user e.g. account=project-a,partition=gpu,qos=basic.
+    /// Only the account is required. The other associations stay in place. Can be given multiple times.
+    #[clap(long = "add-association", value_parser = user_association, verbatim_doc_comment)]
+    add_associations: Vec<UserAssociation>,
+    /// Slurm association to remove from the user e.g. account=project-a,partition=gpu.
+    /// Without partition or cluster, the associations with the account on every partition or cluster are removed.
+    /// The other associations stay in place. Can be given multiple times.
+    #[clap(long = "remove-association", value_parser = user_association, verbatim_doc_comment)]
+    remove_associations: Vec<UserAssociation>,
+    /// New default account of the user in Slurm.
+    /// If the association with the current default account is removed, the first remaining account becomes the default.
+    #[clap(long, value_parser = slurm_name, verbatim_doc_comment)]
+    default_account: Option<TrimmedNonEmptyText>,
 }
 
 impl Modifiable {
@@ -104,10 +399,110 @@ impl Modifiable {
             firstname: Default::default(),
             lastname: Default::default(),
             common_user_fields: CommonUserFields::new(username),
+            unset_attributes: Default::default(),
+            slurm_limits: Default::default(),
+            add_associations: Default::default(),
+            remove_associations: Default::default(),
+            default_account: Default::default(),
         }
     }
 }
 
+/// Options to list only the users matching all given options
+#[derive(Args, Debug, Clone, Default)]
+pub struct ListFilter {
+    /// Only users of this Unix group e.g. staff.
+    #[clap(long, value_parser = group)]
+    pub group: Option<Group>,
+    /// Only users with this QOS e.g. interactive.
+    #[clap(long, value_parser = trimmed_non_empty)]
+    pub qos: Option<TrimmedNonEmptyText>,
+    /// Only users with this default QOS e.g. basic.
+    #[clap(long, value_parser = trimmed_non_empty)]
+    pub default_qos: Option<TrimmedNonEmptyText>,
+    /// Only users created at or after this date e.g. 2024-01-31 or 2024-01-31T12:00:00+01:00.
+    /// Requires the creation date in LDAP (ldap_add_created_at in conf.toml).
+    #[clap(long, value_parser = date_or_timestamp, verbatim_doc_comment)]
+    pub created_after: Option<DateTime<FixedOffset>>,
+    /// Only users created before this date e.g. 2024-01-31 or 2024-01-31T12:00:00+01:00.
+    /// Requires the creation date in LDAP (ldap_add_created_at in conf.toml).
+    #[clap(long, value_parser = date_or_timestamp, verbatim_doc_comment)]
+    pub created_before: Option<DateTime<FixedOffset>>,
+    /// Only users without any SSH public key in LDAP.
+    #[clap(long)]
+    pub missing_pubkey: bool,
+    /// Only users whose username, first name, last name or mail contains this text.
+    #[clap(long = "match", value_parser = trimmed_non_empty)]
+    pub match_text: Option<TrimmedNonEmptyText>,
+}
+
+impl ListFilter {
+    /// Returns true if any option refers to an attribute which is only stored in LDAP.
+    /// These options can only be applied to Slurm via the usernames found in LDAP.
+    pub fn has_ldap_only_options(&self) -> bool {
+        self.default_qos.is_some()
+            || self.created_after.is_some()
+            || self.created_before.is_some()
+            || self.missing_pubkey
+            || self.match_text.is_some()
+    }
+
+    /// Returns true if parameter `created_at` is within the range of
+    /// `created_after` (inclusive) and `created_before` (exclusive).
+    /// Without a creation date, only a filter without range matches.
+    pub fn created_in_range(&self, created_at: Option<&DateTime<FixedOffset>>) -> bool {
+        match created_at {
+            Some(created_at) => {
+                self.created_after
+                    .map_or(true, |after| *created_at >= after)
+                    && self
+                        .created_before
+                        .map_or(true, |before| *created_at < before)
+            }
+            None => self.created_after.is_none() && self.created_before.is_none(),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// - If the QOS or default QOS is not listed in valid_qos of the configuration.
+    /// - If the date of `created_after` is not before the one of `created_before`.
+    pub fn validate(&self, config: &MgmtConfig) -> AppResult {
+        for qos in [&self.qos, &self.default_qos].into_iter().flatten() {
+            if !config.valid_qos.iter().any(|valid| valid == qos.as_ref()) {
+                bail!(
+                    "QOS {} is not listed in valid_qos of the configuration",
+                    qos
+                );
+            }
+        }
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before) {
+            if after >= before {
+                bail!(
+                    "Date of --created-after ({}) must be before the one of --created-before ({})",
+                    after,
+                    before
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Defines how the password of a user is acquired
+#[derive(Args, Debug, Clone, Default)]
+#[group(multiple = false)]
+pub struct PasswordOptions {
+    /// Set a password for the user in LDAP.
+    /// The password is prompted for or read from stdin if stdin is not a terminal.
+    #[clap(long, verbatim_doc_comment)]
+    pub set_password: bool,
+    /// Set a randomly generated password for the user in LDAP and print it to stdout.
+    /// Length and characters are taken from generated_password_length and generated_password_charset of conf.toml.
+    #[clap(long, verbatim_doc_comment)]
+    pub generate_password: bool,
+}
+
 /// Defines options for adding a user
 #[derive(Args, Debug, Clone, Into)]
 pub struct UserToAdd {
@@ -119,6 +514,17 @@ pub struct UserToAdd {
     lastname: TrimmedNonEmptyText,
     #[command(flatten)]
     common_user_fields: CommonUserFields,
+    /// Slurm association in addition to the one with the account of the group
+    /// e.g. account=project-a,partition=gpu,cluster=alpha,qos=basic,qos=interactive.
+    /// Only the account is required. Can be given multiple times.
+    #[clap(long = "association", value_parser = user_association, verbatim_doc_comment)]
+    associations: Vec<UserAssociation>,
+    /// Default account of the user in Slurm. Defaults to the account of the group.
+    #[clap(long, value_parser = slurm_name)]
+    default_account: Option<TrimmedNonEmptyText>,
+    /// Initial password in plain text. Stored as a salted hash in LDAP.
+    #[clap(skip)]
+    password: Option<String>,
 }
 
 impl UserToAdd {
@@ -131,9 +537,16 @@ impl UserToAdd {
             firstname,
             lastname,
             common_user_fields,
+            associations: Default::default(),
+            default_account: Default::default(),
+            password: None,
         }
     }
 
+    pub fn with_password(self, password: Option<String>) -> Self {
+        Self { password, ..self }
+    }
+
     pub fn common_user_fields(&self) -> &CommonUserFields {
         &self.common_user_fields
     }
@@ -154,12 +567,17 @@ pub struct CommonUserFields {
     /// Slurm default QOS for the user e.g. basic.
     #[clap(short, long, value_parser = trimmed_non_empty)]
     pub default_qos: Option<TrimmedNonEmptyText>,
-    /// Path to SSH publickey.
+    /// Path to a file with SSH public keys in the OpenSSH format, one key per line.
+    /// On modification, all existing keys of the user are replaced.
     #[clap(short, long, value_parser = trimmed_non_empty)]
     pub publickey: Option<TrimmedNonEmptyText>,
     /// List of QoS assigned to the user (must be valid QoS i.e., they must exist in valid_qos of conf.toml). Arguments must be provided as a whitespace separated list (e.g. interactive basic).
     #[clap(short, long, num_args(0..=20))]
     pub qos: Vec<String>,
+    /// Extra LDAP attribute in the form of name=value e.g. employeeNumber=4711.
+    /// Can be given multiple times. The name must be listed in ldap_extra_attributes of conf.toml.
+    #[clap(long = "attr", value_parser = extra_ldap_attribute, verbatim_doc_comment)]
+    pub extra_attributes: Vec<ExtraLdapAttribute>,
 }
 
 impl CommonUserFields {
@@ -171,13 +589,181 @@ impl CommonUserFields {
             default_qos: Default::default(),
             publickey: Default::default(),
             qos: Default::default(),
+            extra_attributes: Default::default(),
         }
     }
 }
 
+/// Used by argument parser to ensure that the argument is a valid name of an entity in Slurm
+/// like an account. Only letters, digits, '_', '-' and '.' are allowed.
+pub fn slurm_name(s: &str) -> AppResult<TrimmedNonEmptyText> {
+    let name = TrimmedNonEmptyText::try_from(s)?;
+    let is_valid = |char: char| char.is_ascii_alphanumeric() || "_-.".contains(char);
+    if !name.as_ref().chars().all(is_valid) {
+        bail!(
+            "{} is not a valid name in Slurm. Only letters, digits, '_', '-' and '.' are allowed",
+            name
+        );
+    }
+    Ok(name)
+}
+
+/// Used by argument parser to ensure that the argument is a time limit of Slurm
+/// like 90, 08:00:00 or 2-00:00:00. Only digits, '-' and ':' are allowed.
+pub fn slurm_time(s: &str) -> AppResult<TrimmedNonEmptyText> {
+    let time = TrimmedNonEmptyText::try_from(s)?;
+    let is_valid = |char: char| char.is_ascii_digit() || "-:".contains(char);
+    if !time.as_ref().chars().all(is_valid) {
+        bail!(
+            "{} is not a valid time limit of Slurm e.g. 90, 08:00:00 or 2-00:00:00",
+            time
+        );
+    }
+    Ok(time)
+}
+
+/// Used by argument parser to ensure that the argument is a comma separated list
+/// of trackable resources in Slurm like cpu=16,mem=64G,gres/gpu=2.
+pub fn slurm_tres(s: &str) -> AppResult<TrimmedNonEmptyText> {
+    let tres = TrimmedNonEmptyText::try_from(s)?;
+    let is_valid_name = |name: &str| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|char| char.is_ascii_alphanumeric() || "/_:-".contains(char))
+    };
+    let is_valid_count = |count: &str| {
+        count == "-1"
+            || (!count.is_empty() && count.chars().all(|char| char.is_ascii_alphanumeric()))
+    };
+    for resource in tres.as_ref().split(',') {
+        match resource.split_once('=') {
+            Some((name, count)) if is_valid_name(name) && is_valid_count(count) => (),
+            _ => bail!(
+                "{} is not a valid trackable resource of Slurm in the form of name=count e.g. cpu=16 or gres/gpu=2",
+                resource
+            ),
+        }
+    }
+    Ok(tres)
+}
+
 /// Used by argument parser to ensure that
 /// the argument is not empty and white spaces are trimmed
 pub fn trimmed_non_empty(s: &str) -> AppResult<TrimmedNonEmptyText> {
     let to_validate = TrimmedNonEmptyText::try_from(s)?;
     Ok(to_validate)
 }
+
+/// Used by argument parser to ensure that
+/// the argument is a valid group e.g. staff
+pub fn group(s: &str) -> AppResult<Group> {
+    s.parse()
+}
+
+/// Used by argument parser to accept a date like 2024-01-31 or
+/// a timestamp in the format of RFC 3339 like 2024-01-31T12:00:00+01:00.
+/// A date without time means the start of the day in UTC.
+pub fn date_or_timestamp(s: &str) -> AppResult<DateTime<FixedOffset>> {
+    let s = s.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(s) {
+        return Ok(timestamp);
+    }
+    let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").with_context(|| {
+        format!(
+            "{} is neither a date like 2024-01-31 nor a timestamp like 2024-01-31T12:00:00+01:00",
+            s
+        )
+    })?;
+    let start_of_day = date
+        .and_hms_opt(0, 0, 0)
+        .expect("Midnight is a valid time")
+        .and_utc();
+    Ok(start_of_day.fixed_offset())
+}
+
+/// Used by argument parser to accept a date like 2024-01-31
+pub fn date(s: &str) -> AppResult<NaiveDate> {
+    let s = s.trim();
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .with_context(|| format!("{} is not a date like 2024-01-31", s))
+}
+
+/// Used by argument parser to ensure that
+/// the argument is an extra LDAP attribute in the form of name=value
+pub fn extra_ldap_attribute(s: &str) -> AppResult<ExtraLdapAttribute> {
+    s.parse()
+}
+
+/// Used by argument parser to ensure that the argument is a valid association of a user in Slurm.
+/// See [`UserAssociation`]
+pub fn user_association(s: &str) -> AppResult<UserAssociation> {
+    s.parse()
+}
+
+/// Used by argument parser to ensure that the argument is a supported resource limit of Slurm
+/// in the form of name=value. See [`SlurmLimit`]
+pub fn slurm_limit(s: &str) -> AppResult<SlurmLimit> {
+    s.parse()
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_date_or_timestamp() {
+        let date = date_or_timestamp("2024-01-31").expect("Should be a valid date");
+        assert_eq!("2024-01-31T00:00:00+00:00", date.to_rfc3339());
+        let timestamp =
+            date_or_timestamp("2024-01-31T12:00:00+01:00").expect("Should be a valid timestamp");
+        assert_eq!("2024-01-31T12:00:00+01:00", timestamp.to_rfc3339());
+        assert!(date_or_timestamp("31.01.2024").is_err());
+    }
+
+    #[test]
+    fn created_in_range_with_inclusive_start_and_exclusive_end() {
+        let date = |text: &str| date_or_timestamp(text).expect("Should be a valid date");
+        let filter = ListFilter {
+            created_after: Some(date("2024-01-01")),
+            created_before: Some(date("2024-02-01")),
+            ..Default::default()
+        };
+        assert!(filter.created_in_range(Some(&date("2024-01-01"))));
+        assert!(filter.created_in_range(Some(&date("2024-01-31T23:59:59+00:00"))));
+        assert!(!filter.created_in_range(Some(&date("2024-02-01"))));
+        assert!(!filter.created_in_range(Some(&date("2023-12-31"))));
+        assert!(!filter.created_in_range(None));
+        assert!(ListFilter::default().created_in_range(None));
+    }
+
+    #[test]
+    fn validate_slurm_values() {
+        assert!(slurm_name("gpu_users-2.a").is_ok());
+        assert!(slurm_name("staff; rm -rf").is_err());
+        assert!(slurm_time("2-00:00:00").is_ok());
+        assert!(slurm_time("2 days").is_err());
+        assert!(slurm_tres("cpu=16,mem=64G,gres/gpu=2").is_ok());
+        assert!(slurm_tres("cpu=-1").is_ok());
+        assert!(slurm_tres("cpu").is_err());
+        assert!(slurm_tres("cpu=16 gpu=2").is_err());
+    }
+
+    #[test]
+    fn error_for_unknown_qos_or_reversed_dates() {
+        let config = MgmtConfig::default();
+        let qos = |text: &str| Some(TrimmedNonEmptyText::try_from(text).expect("Not empty"));
+        let unknown_qos = ListFilter {
+            qos: qos("unknown"),
+            ..Default::default()
+        };
+        assert!(unknown_qos.validate(&config).is_err());
+        let reversed = ListFilter {
+            default_qos: qos("basic"),
+            created_after: date_or_timestamp("2024-02-01").ok(),
+            created_before: date_or_timestamp("2024-01-01").ok(),
+            ..Default::default()
+        };
+        assert!(reversed.validate(&config).is_err());
+    }
+}
@@ -4,6 +4,9 @@ use clap::Args;
 use getset::{CopyGetters, Getters};
 
 use crate::config::MgmtConfig;
+use crate::util::TrimmedNonEmptyText;
+
+use super::slurm_name;
 pub type OptFilePath = Option<PathBuf>;
 
 /// Same as [`OnWhichSystem`] but without considering options from a configuration file.
@@ -36,6 +39,11 @@ pub struct OnSlurmLdapOnlyCli {
     #[arg(long, verbatim_doc_comment)]
     #[getset(get = "pub")]
     ssh_path: Option<PathBuf>,
+    /// Name of a Slurm cluster listed in slurm_clusters of conf.toml to act on.
+    /// Can be given multiple times. Acts on all listed clusters if omitted.
+    #[clap(long = "cluster", value_parser = slurm_name, verbatim_doc_comment)]
+    #[getset(get = "pub")]
+    clusters: Vec<TrimmedNonEmptyText>,
 }
 
 /// Toggle the systems (Slurm, LDAP, Directories) that will be affected by actions such as user creation.
@@ -51,6 +59,9 @@ pub struct OnWhichSystem {
     dirs: bool,
     #[getset(get = "pub")]
     ssh_path: OptFilePath,
+    /// Slurm clusters to act on. All configured clusters if empty.
+    #[getset(get = "pub")]
+    clusters: Vec<TrimmedNonEmptyText>,
 }
 
 impl OnWhichSystem {
@@ -60,6 +71,7 @@ impl OnWhichSystem {
             ldap,
             dirs,
             ssh_path,
+            clusters: Vec::new(),
         }
     }
 
@@ -79,6 +91,7 @@ impl OnWhichSystem {
                 .as_ref()
                 .cloned()
                 .or_else(|| config.ssh_key_path.clone()),
+            clusters: from_cli.clusters().clone(),
         }
     }
 
@@ -108,6 +121,7 @@ mod testing {
                 ldap: Some(true),
                 slurm: Some(true),
                 ssh_path: None,
+                clusters: Vec::new(),
             },
         );
 
@@ -125,6 +139,7 @@ mod testing {
                     ldap: None,
                     slurm: None,
                     ssh_path: None,
+                    clusters: Vec::new(),
                 },
                 dirs: None,
             },
@@ -138,6 +153,7 @@ mod testing {
                     ldap: None,
                     slurm: Some(false),
                     ssh_path: None,
+                    clusters: Vec::new(),
                 },
                 dirs: None,
             },
@@ -151,6 +167,7 @@ mod testing {
                     ldap: Some(false),
                     slurm: Some(false),
                     ssh_path: None,
+                    clusters: Vec::new(),
                 },
                 dirs: None,
             },
@@ -167,6 +184,7 @@ mod testing {
                     ldap: None,
                     slurm: None,
                     ssh_path: None,
+                    clusters: Vec::new(),
                 },
                 dirs: None,
             },
@@ -184,6 +202,7 @@ mod testing {
                     ldap: Some(false),
                     slurm: Some(false),
                     ssh_path: None,
+                    clusters: Vec::new(),
                 },
                 dirs: Some(true),
             },
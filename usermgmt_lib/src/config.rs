@@ -1,8 +1,17 @@
+pub use ldap_attributes::LdapAttributes;
 pub use path_sources::get_path_to_conf;
+pub use slurm_backend::SlurmBackend;
+pub use slurm_cluster::SlurmCluster;
 
+mod ldap_attributes;
 mod path_sources;
+mod slurm_backend;
+mod slurm_cluster;
 
-use std::path::{Path, PathBuf};
+use std::{
+    borrow::Borrow,
+    path::{Component, Path, PathBuf},
+};
 
 use anyhow::Context;
 use log::info;
@@ -19,19 +28,34 @@ pub struct MgmtConfig {
     pub staff_default_qos: String,
     pub student_qos: Vec<String>,
     pub staff_qos: Vec<String>,
+    #[serde(default)]
+    pub student_slurm_limits: Vec<String>,
+    #[serde(default)]
+    pub staff_slurm_limits: Vec<String>,
     pub valid_qos: Vec<String>,
     pub valid_slurm_groups: Vec<String>,
     pub objectclass_common: Vec<String>,
     pub compute_nodes: Vec<String>,
     pub login_shell: String,
+    #[serde(default = "default_home_directory_template")]
+    pub home_directory_template: String,
     pub student_gid: i32,
     pub staff_gid: i32,
     pub faculty_gid: i32,
     pub sacctmgr_path: String,
+    #[serde(default = "default_cancel_jobs_timeout_secs")]
+    pub cancel_jobs_timeout_secs: u32,
     pub ldap_default_user: Option<String>,
     pub ldap_domain_components: Option<String>,
     pub ldap_org_unit: Option<String>,
-    pub ldap_server: String,
+    pub ldap_student_org_unit: Option<String>,
+    pub ldap_staff_org_unit: Option<String>,
+    pub ldap_faculty_org_unit: Option<String>,
+    #[serde(deserialize_with = "one_or_many")]
+    pub ldap_server: Vec<String>,
+    pub ldap_writable_server: Option<String>,
+    #[serde(default = "default_ldap_connect_timeout_secs")]
+    pub ldap_connect_timeout_secs: u32,
     pub ldap_readonly_user: Option<String>,
     pub ldap_readonly_pw: Option<String>,
     pub ldap_readonly_bind: Option<String>,
@@ -39,6 +63,16 @@ pub struct MgmtConfig {
     pub ldap_bind_prefix: Option<String>,
     pub ldap_bind_org_unit: Option<String>,
     pub ldap_add_created_at: bool,
+    #[serde(default)]
+    pub ldap_track_modifications: bool,
+    #[serde(default = "default_ldap_page_size")]
+    pub ldap_page_size: u32,
+    #[serde(default)]
+    pub ldap_extra_attributes: Vec<String>,
+    #[serde(default = "default_generated_password_length")]
+    pub generated_password_length: u32,
+    #[serde(default = "default_generated_password_charset")]
+    pub generated_password_charset: String,
     pub home_host: String,
     pub nfs_host: Vec<String>,
     pub head_node: String,
@@ -62,8 +96,149 @@ pub struct MgmtConfig {
     pub ssh_port: u32,
     pub ssh_agent: bool,
     pub ssh_key_path: Option<PathBuf>,
+    #[serde(default)]
+    pub slurm_backend: SlurmBackend,
+    pub slurm_rest_url: Option<String>,
+    #[serde(default = "default_slurm_rest_api_version")]
+    pub slurm_rest_api_version: String,
+    pub slurm_rest_token_file: Option<String>,
+    pub slurm_rest_token_command: Option<String>,
+    #[serde(default)]
+    pub slurm_clusters: Vec<SlurmCluster>,
+    /// Name of the Slurm cluster to which the Slurm commands are restricted.
+    /// Not read from the configuration file, but set for every entry of `slurm_clusters`.
+    /// See [`MgmtConfig::slurm_cluster_configs`]
+    #[serde(skip)]
+    pub slurm_cluster: Option<String>,
+    #[serde(default)]
+    pub ldap_attributes: LdapAttributes,
 }
 impl MgmtConfig {
+    /// Returns the gid of every group with an own organizational unit in LDAP together with this unit.
+    pub fn ldap_group_org_units(&self) -> Vec<(i32, String)> {
+        [
+            (self.student_gid, &self.ldap_student_org_unit),
+            (self.staff_gid, &self.ldap_staff_org_unit),
+            (self.faculty_gid, &self.ldap_faculty_org_unit),
+        ]
+        .into_iter()
+        .filter_map(|(gid, org_unit)| Some((gid, org_unit.clone()?)))
+        .collect()
+    }
+
+    /// Returns a configuration for every Slurm cluster to act on,
+    /// with the head node, sacctmgr path and QOS sets of the cluster.
+    ///
+    /// Returns only this configuration if no `slurm_clusters` are configured.
+    /// Otherwise returns the clusters named in parameter `selected` or all clusters if none are named.
+    ///
+    /// # Errors
+    ///
+    /// - If a cluster is selected which is not listed in `slurm_clusters`.
+    pub fn slurm_cluster_configs(&self, selected: &[impl Borrow<str>]) -> AppResult<Vec<Self>> {
+        if self.slurm_clusters.is_empty() {
+            if !selected.is_empty() {
+                bail!("Slurm clusters are selected, but no slurm_clusters are configured");
+            }
+            return Ok(vec![self.clone()]);
+        }
+        for name in selected.iter().map(Borrow::borrow) {
+            if !self
+                .slurm_clusters
+                .iter()
+                .any(|cluster| cluster.name == name)
+            {
+                bail!("Slurm cluster {} is not listed in slurm_clusters", name);
+            }
+        }
+        Ok(self
+            .slurm_clusters
+            .iter()
+            .filter(|cluster| {
+                selected.is_empty() || selected.iter().any(|name| name.borrow() == cluster.name)
+            })
+            .map(|cluster| self.for_slurm_cluster(cluster))
+            .collect())
+    }
+
+    fn for_slurm_cluster(&self, cluster: &SlurmCluster) -> Self {
+        let or_own = |from_cluster: &Option<Vec<String>>, own: &Vec<String>| {
+            from_cluster.clone().unwrap_or_else(|| own.clone())
+        };
+        Self {
+            head_node: cluster.head_node.clone(),
+            sacctmgr_path: cluster.sacctmgr_path.clone(),
+            run_slurm_remote: cluster.run_slurm_remote,
+            valid_qos: or_own(&cluster.valid_qos, &self.valid_qos),
+            student_qos: or_own(&cluster.student_qos, &self.student_qos),
+            staff_qos: or_own(&cluster.staff_qos, &self.staff_qos),
+            student_default_qos: cluster
+                .student_default_qos
+                .clone()
+                .unwrap_or_else(|| self.student_default_qos.clone()),
+            staff_default_qos: cluster
+                .staff_default_qos
+                .clone()
+                .unwrap_or_else(|| self.staff_default_qos.clone()),
+            slurm_cluster: Some(cluster.name.clone()),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the home directory of the user with the name `username`
+    /// according to the field `home_directory_template`.
+    ///
+    /// # Errors
+    ///
+    /// - If the template is invalid. See [`MgmtConfig::check_home_directory_template`]
+    /// - If the directory is the parent directory of the template or contains `.` or `..`,
+    ///   e.g. for an empty username. Deleting such a directory would remove other homes.
+    pub fn home_directory(&self, username: &str) -> AppResult<String> {
+        self.check_home_directory_template()?;
+        let template = &self.home_directory_template;
+        let directory = template.replace(constants::USERNAME_PLACEHOLDER, username);
+        let parent = Path::new(template).parent().unwrap_or(Path::new(""));
+        let path = Path::new(&directory);
+        if path == parent
+            || path
+                .components()
+                .any(|component| matches!(component, Component::CurDir | Component::ParentDir))
+        {
+            bail!(
+                "Home directory {} of user {} is not below {}",
+                directory,
+                username,
+                parent.to_string_lossy()
+            );
+        }
+        Ok(directory)
+    }
+
+    /// # Errors
+    ///
+    /// - If the field `home_directory_template` does not contain the placeholder `{username}` exactly once.
+    pub fn check_home_directory_template(&self) -> AppResult {
+        let template = &self.home_directory_template;
+        let placeholders = template.matches(constants::USERNAME_PLACEHOLDER).count();
+        if placeholders != 1 {
+            bail!(
+                "home_directory_template {} must contain {} exactly once, but contains it {} times",
+                template,
+                constants::USERNAME_PLACEHOLDER,
+                placeholders
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the LDAP server for writing actions: `ldap_writable_server` if given,
+    /// otherwise the first entry of `ldap_server`.
+    pub fn writable_ldap_server(&self) -> Option<&str> {
+        self.ldap_writable_server
+            .as_deref()
+            .or_else(|| self.ldap_server.first().map(String::as_str))
+    }
+
     /// # Errors
     ///
     /// - If the parameter `path` can not be converted into an absolute path.
@@ -85,10 +260,17 @@ impl Default for MgmtConfig {
     fn default() -> Self {
         MgmtConfig {
             ldap_add_created_at: false,
+            ldap_track_modifications: false,
+            ldap_page_size: constants::DEFAULT_LDAP_PAGE_SIZE,
+            ldap_extra_attributes: Vec::new(),
+            generated_password_length: constants::DEFAULT_GENERATED_PASSWORD_LENGTH,
+            generated_password_charset: constants::DEFAULT_GENERATED_PASSWORD_CHARSET.to_string(),
             student_default_qos: "basic".to_string(),
             staff_default_qos: "advanced".to_string(),
             student_qos: vec!["interactive".to_string(), "basic".to_string()],
             staff_qos: vec!["interactive".to_string(), "advanced".to_string()],
+            student_slurm_limits: Vec::new(),
+            staff_slurm_limits: Vec::new(),
             valid_qos: vec![
                 "interactive".to_string(),
                 "basic".to_string(),
@@ -106,14 +288,21 @@ impl Default for MgmtConfig {
             ],
             valid_slurm_groups: vec!["staff".to_string(), "student".to_string()],
             login_shell: "/bin/bash".to_string(),
+            home_directory_template: constants::DEFAULT_HOME_DIRECTORY_TEMPLATE.to_string(),
             student_gid: 1002,
             staff_gid: 1001,
             faculty_gid: 1000,
             sacctmgr_path: "/usr/local/bin/sacctmgr".to_string(),
+            cancel_jobs_timeout_secs: constants::DEFAULT_CANCEL_JOBS_TIMEOUT_SECS,
             ldap_default_user: None,
             ldap_domain_components: None,
             ldap_org_unit: None,
-            ldap_server: "ldap://localhost:389".to_string(),
+            ldap_student_org_unit: None,
+            ldap_staff_org_unit: None,
+            ldap_faculty_org_unit: None,
+            ldap_server: vec!["ldap://localhost:389".to_string()],
+            ldap_writable_server: None,
+            ldap_connect_timeout_secs: constants::DEFAULT_LDAP_CONNECT_TIMEOUT_SECS,
             ldap_readonly_user: None,
             ldap_readonly_pw: None,
             ldap_readonly_user_prefix: None,
@@ -144,27 +333,86 @@ impl Default for MgmtConfig {
             ssh_port: 22,
             ssh_agent: false,
             ssh_key_path: None,
+            slurm_backend: SlurmBackend::default(),
+            slurm_rest_url: None,
+            slurm_rest_api_version: constants::DEFAULT_SLURM_REST_API_VERSION.to_string(),
+            slurm_rest_token_file: None,
+            slurm_rest_token_command: None,
+            slurm_clusters: Vec::new(),
+            slurm_cluster: None,
+            ldap_attributes: LdapAttributes::default(),
         }
     }
 }
 
+fn default_slurm_rest_api_version() -> String {
+    constants::DEFAULT_SLURM_REST_API_VERSION.to_string()
+}
+
+fn default_ldap_page_size() -> u32 {
+    constants::DEFAULT_LDAP_PAGE_SIZE
+}
+
+fn default_ldap_connect_timeout_secs() -> u32 {
+    constants::DEFAULT_LDAP_CONNECT_TIMEOUT_SECS
+}
+
+fn default_cancel_jobs_timeout_secs() -> u32 {
+    constants::DEFAULT_CANCEL_JOBS_TIMEOUT_SECS
+}
+
+fn default_generated_password_length() -> u32 {
+    constants::DEFAULT_GENERATED_PASSWORD_LENGTH
+}
+
+fn default_generated_password_charset() -> String {
+    constants::DEFAULT_GENERATED_PASSWORD_CHARSET.to_string()
+}
+
+fn default_home_directory_template() -> String {
+    constants::DEFAULT_HOME_DIRECTORY_TEMPLATE.to_string()
+}
+
+/// Accepts a single string as well as a list of strings.
+/// Allows a field to become a list without breaking configuration files with a single value.
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(single) => vec![single],
+        OneOrMany::Many(many) => many,
+    })
+}
+
 /// Attempts to load config.toml.
 ///
 /// # Error
 ///
 /// - Can not check if directory exists where conf.toml is located
 /// - Can not read or create a conf.toml file
+/// - If the field `home_directory_template` is invalid. See [`MgmtConfig::check_home_directory_template`]
 pub fn load_config(manual_path: Option<PathBuf>) -> AppResult<LoadedMgmtConfig> {
     let path = config::get_path_to_conf(manual_path)?;
 
     info!("Loading configuration file from path: {:?}", path);
     // Load (or create if nonexistent) configuration file conf.toml
-    let config = confy::load_path(&path).with_context(|| {
+    let config: MgmtConfig = confy::load_path(&path).with_context(|| {
         format!(
             "Error during loading or creating config file at {:?}",
             &path
         )
     })?;
+    config
+        .check_home_directory_template()
+        .with_context(|| format!("Invalid config file at {:?}", &path))?;
     let path = path
         .parent()
         .ok_or_else(|| anyhow!("{:?} must have a parent folder", &path))?
@@ -184,3 +432,93 @@ pub fn config_for_save() -> String {
     toml::to_string_pretty(&MgmtConfig::default())
         .expect("Unable to convert default configuration into .toml format")
 }
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn with_ldap_server(value: &str) -> MgmtConfig {
+        let mut text = config_for_save()
+            .lines()
+            .filter(|line| !line.starts_with("ldap_server"))
+            .collect::<Vec<&str>>()
+            .join("\n");
+        text = format!("ldap_server = {}\n{}", value, text);
+        toml::from_str(&text).expect("Should be a valid configuration")
+    }
+
+    #[test]
+    fn ldap_server_as_single_string_or_list() {
+        let single = with_ldap_server("'ldap://provider:389'");
+        assert_eq!(vec!["ldap://provider:389"], single.ldap_server);
+        assert_eq!(Some("ldap://provider:389"), single.writable_ldap_server());
+
+        let many = with_ldap_server("['ldap://consumer1:389', 'ldap://consumer2:389']");
+        assert_eq!(
+            vec!["ldap://consumer1:389", "ldap://consumer2:389"],
+            many.ldap_server
+        );
+        assert_eq!(Some("ldap://consumer1:389"), many.writable_ldap_server());
+
+        let writable = MgmtConfig {
+            ldap_writable_server: Some("ldap://provider:389".to_string()),
+            ..many
+        };
+        assert_eq!(Some("ldap://provider:389"), writable.writable_ldap_server());
+    }
+
+    #[test]
+    fn configs_of_selected_slurm_clusters() {
+        let cluster = |name: &str, valid_qos: Option<Vec<String>>| SlurmCluster {
+            name: name.to_string(),
+            head_node: format!("head.{}", name),
+            sacctmgr_path: "/usr/bin/sacctmgr".to_string(),
+            run_slurm_remote: true,
+            valid_qos,
+            student_qos: None,
+            staff_qos: None,
+            student_default_qos: Some("gpu".to_string()),
+            staff_default_qos: None,
+        };
+        let single = MgmtConfig::default();
+        let configs = single
+            .slurm_cluster_configs(&[] as &[&str])
+            .expect("Should return the configuration itself");
+        assert_eq!(vec![None], names_of(&configs));
+        assert!(single.slurm_cluster_configs(&["alpha"]).is_err());
+
+        let config = MgmtConfig {
+            slurm_clusters: vec![
+                cluster("alpha", None),
+                cluster("beta", Some(vec!["gpu".to_string()])),
+            ],
+            ..Default::default()
+        };
+        let all = config
+            .slurm_cluster_configs(&[] as &[&str])
+            .expect("Should return all clusters");
+        assert_eq!(
+            vec![Some("alpha".to_string()), Some("beta".to_string())],
+            names_of(&all)
+        );
+        assert_eq!("head.alpha", all[0].head_node);
+        assert!(all[0].run_slurm_remote);
+        assert_eq!(config.valid_qos, all[0].valid_qos);
+        assert_eq!(vec!["gpu"], all[1].valid_qos);
+        assert_eq!("gpu", all[1].student_default_qos);
+        assert_eq!(config.staff_default_qos, all[1].staff_default_qos);
+
+        let selected = config
+            .slurm_cluster_configs(&["beta"])
+            .expect("Should return the selected cluster");
+        assert_eq!(vec![Some("beta".to_string())], names_of(&selected));
+        assert!(config.slurm_cluster_configs(&["gamma"]).is_err());
+
+        fn names_of(configs: &[MgmtConfig]) -> Vec<Option<String>> {
+            configs
+                .iter()
+                .map(|config| config.slurm_cluster.clone())
+                .collect()
+        }
+    }
+}
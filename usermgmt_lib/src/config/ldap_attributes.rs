@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// Names of the LDAP attributes under which the fields of an user are stored.
+///
+/// Allows to adapt this application to LDAP schemas which differ from the default one,
+/// for example a different Slurm schema.
+/// Every field not given in the configuration file falls back to its default name.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct LdapAttributes {
+    pub username: String,
+    pub common_name: String,
+    pub uid_number: String,
+    pub gid_number: String,
+    pub firstname: String,
+    pub lastname: String,
+    pub mail: String,
+    pub default_qos: String,
+    pub qos: String,
+    pub publickey: String,
+    pub home_directory: String,
+    pub login_shell: String,
+    pub created_at: String,
+    pub modified_at: String,
+    pub modified_by: String,
+    pub password: String,
+    pub shadow_last_change: String,
+}
+
+impl LdapAttributes {
+    /// Returns the names of all attributes
+    pub fn all(&self) -> Vec<&str> {
+        vec![
+            self.username.as_str(),
+            self.common_name.as_str(),
+            self.uid_number.as_str(),
+            self.gid_number.as_str(),
+            self.firstname.as_str(),
+            self.lastname.as_str(),
+            self.mail.as_str(),
+            self.default_qos.as_str(),
+            self.qos.as_str(),
+            self.publickey.as_str(),
+            self.home_directory.as_str(),
+            self.login_shell.as_str(),
+            self.created_at.as_str(),
+            self.modified_at.as_str(),
+            self.modified_by.as_str(),
+            self.password.as_str(),
+            self.shadow_last_change.as_str(),
+        ]
+    }
+}
+
+impl Default for LdapAttributes {
+    fn default() -> Self {
+        Self {
+            username: "uid".to_string(),
+            common_name: "cn".to_string(),
+            uid_number: "uidNumber".to_string(),
+            gid_number: "gidNumber".to_string(),
+            firstname: "givenName".to_string(),
+            lastname: "sn".to_string(),
+            mail: "mail".to_string(),
+            default_qos: "slurmDefaultQos".to_string(),
+            qos: "slurmQos".to_string(),
+            publickey: "sshPublicKey".to_string(),
+            home_directory: "homeDirectory".to_string(),
+            login_shell: "loginShell".to_string(),
+            created_at: "createdAt".to_string(),
+            modified_at: "modifiedAt".to_string(),
+            modified_by: "modifiedBy".to_string(),
+            password: "userPassword".to_string(),
+            shadow_last_change: "shadowLastChange".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn fall_back_to_default_for_missing_attributes() {
+        let actual: LdapAttributes = toml::from_str(
+            r#"
+            default_qos = "slurmDefQos"
+            qos = "slurmQosList"
+            "#,
+        )
+        .expect("Should parse a partial table of attribute names");
+        let expected = LdapAttributes {
+            default_qos: "slurmDefQos".to_string(),
+            qos: "slurmQosList".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(expected, actual);
+    }
+}
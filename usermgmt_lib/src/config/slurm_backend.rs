@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Way in which users are managed in Slurm
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SlurmBackend {
+    /// Via sacctmgr, either locally or on the head node over ssh
+    #[default]
+    Sacctmgr,
+    /// Via the REST API of slurmrestd, authenticated by a JWT
+    Rest,
+}
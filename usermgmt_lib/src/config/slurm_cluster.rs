@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Slurm cluster on which users are managed.
+///
+/// Clusters can have separate slurmdbd instances or share one under different cluster names.
+/// Every QOS set which is not given falls back to the one at the top level of the configuration.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SlurmCluster {
+    /// Name of the cluster in Slurm (ClusterName in slurm.conf).
+    /// Associations of users are restricted to this cluster.
+    pub name: String,
+    pub head_node: String,
+    pub sacctmgr_path: String,
+    pub run_slurm_remote: bool,
+    pub valid_qos: Option<Vec<String>>,
+    pub student_qos: Option<Vec<String>>,
+    pub staff_qos: Option<Vec<String>>,
+    pub student_default_qos: Option<String>,
+    pub staff_default_qos: Option<String>,
+}
@@ -1,6 +1,24 @@
 use const_format::concatcp;
 
 pub const SSH_TIME_OUT_MILL_SECS: u32 = 6000;
+/// Time between two reads of the output of a remote command which has not finished yet
+pub const SSH_POLL_INTERVAL_MILL_SECS: u64 = 10;
+/// Number of entries requested per page for LDAP searches if not specified in the configuration.
+pub const DEFAULT_LDAP_PAGE_SIZE: u32 = 500;
+/// Seconds to wait for a connection to one LDAP server before trying the next one.
+pub const DEFAULT_LDAP_CONNECT_TIMEOUT_SECS: u32 = 5;
+/// Seconds to wait for cancelled jobs of a user to leave the Slurm queue before the user is deleted.
+pub const DEFAULT_CANCEL_JOBS_TIMEOUT_SECS: u32 = 120;
+/// Version of the slurmdb API of slurmrestd if not specified in the configuration.
+pub const DEFAULT_SLURM_REST_API_VERSION: &str = "v0.0.40";
+/// Placeholder within the template for the home directory which is replaced by the username.
+pub const USERNAME_PLACEHOLDER: &str = "{username}";
+pub const DEFAULT_HOME_DIRECTORY_TEMPLATE: &str = "/home/{username}";
+/// Number of characters of a generated password if not specified in the configuration.
+pub const DEFAULT_GENERATED_PASSWORD_LENGTH: u32 = 16;
+/// Characters a generated password is made of if not specified in the configuration.
+pub const DEFAULT_GENERATED_PASSWORD_CHARSET: &str =
+    "ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz23456789!#%+-.:=?@_";
 /// Name of the file in which all values for configuration of this app are located
 /// besides the CLI arguments.
 pub const NAME_CONFIG_FILE: &str = "conf.toml";
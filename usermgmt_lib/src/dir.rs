@@ -3,6 +3,7 @@ use log::{debug, info, warn};
 
 use crate::config::MgmtConfig;
 use crate::prelude::AppResult;
+use crate::slurm::quote_for_shell;
 use crate::ssh::{self, SshConnection, SshCredentials};
 use crate::util::ResultAccumulator;
 use crate::{Group, NewEntity};
@@ -186,7 +187,7 @@ where
     let sess = SshConnection::new(&config.home_host, config, credentials.clone());
 
     // Delete directory
-    let directory = format!("/home/{}", username);
+    let directory = config.home_directory(username)?;
     let (dir_exit_code, _) = delete_directory(&sess, &directory)?;
 
     if dir_exit_code == 0 {
@@ -428,7 +429,7 @@ where
     let sess = SshConnection::new(&config.home_host, config, credentials.clone());
 
     // Create directory
-    let directory = format!("/home/{}", entity.username);
+    let directory = config.home_directory(entity.username.as_ref())?;
 
     let (dir_exit_code, _) = if config.use_homedir_helper {
         make_home_directory(&sess, entity.username.as_ref())
@@ -491,8 +492,11 @@ where
 {
     debug!("Making directory {}", directory);
 
-    let cmd = format!("sudo mkdir -p {directory}");
-    ssh::run_remote_command(sess, &cmd)
+    ssh::run_remote_command(sess, &make_directory_cmd(directory))
+}
+
+fn make_directory_cmd(directory: &str) -> String {
+    format!("sudo mkdir -p {}", quote_for_shell(directory))
 }
 
 fn delete_directory<C>(sess: &SshConnection<C>, directory: &str) -> AppResult<(i32, String)>
@@ -501,8 +505,11 @@ where
 {
     debug!("Deleting directory {}", directory);
 
-    let cmd = format!("sudo rm -r {directory}");
-    ssh::run_remote_command(sess, &cmd)
+    ssh::run_remote_command(sess, &delete_directory_cmd(directory))
+}
+
+fn delete_directory_cmd(directory: &str) -> String {
+    format!("sudo rm -r {}", quote_for_shell(directory))
 }
 
 fn make_home_directory<C>(sess: &SshConnection<C>, username: &str) -> AppResult<(i32, String)>
@@ -526,7 +533,11 @@ where
 {
     debug!("Changing ownership for directory {}", directory);
 
-    let cmd = format!("sudo chown {username}:{group} {directory}");
+    let cmd = format!(
+        "sudo chown {} {}",
+        quote_for_shell(&format!("{username}:{group}")),
+        quote_for_shell(directory)
+    );
     ssh::run_remote_command(sess, &cmd)
 }
 
@@ -549,3 +560,47 @@ where
 
     ssh::run_remote_command(sess, &cmd)
 }
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn home_directory_from_template() {
+        let config = MgmtConfig {
+            home_directory_template: "/data/homes/{username}".to_string(),
+            ..Default::default()
+        };
+        let directory = config
+            .home_directory("alice")
+            .expect("Should be a valid home directory");
+        assert_eq!(
+            "sudo mkdir -p /data/homes/alice",
+            make_directory_cmd(&directory)
+        );
+        assert_eq!(
+            "sudo rm -r /data/homes/alice",
+            delete_directory_cmd(&directory)
+        );
+        assert_eq!(
+            "sudo rm -r '/data/homes/o'\\''brien x'",
+            delete_directory_cmd("/data/homes/o'brien x")
+        );
+    }
+
+    #[test]
+    fn refuse_home_directory_outside_of_template() {
+        for template in ["/home", "/data/homes/", "/home/{username}/{username}"] {
+            let config = MgmtConfig {
+                home_directory_template: template.to_string(),
+                ..Default::default()
+            };
+            assert!(config.check_home_directory_template().is_err());
+            assert!(config.home_directory("alice").is_err());
+        }
+        let config = MgmtConfig::default();
+        assert!(config.home_directory("").is_err());
+        assert!(config.home_directory("..").is_err());
+        assert!(config.home_directory("alice/..").is_err());
+    }
+}
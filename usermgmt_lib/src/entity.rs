@@ -1,7 +1,10 @@
 use crate::{
     cli::{CommonUserFields, Modifiable, UserToAdd},
     prelude::AppError,
-    util::{ResolvedGid, ValidGroupOfQos, ValidQos},
+    util::{
+        ensure_allowed_extra_attribute, ensure_unique_names, is_valid_qos, ExtraLdapAttribute,
+        ResolvedGid, SlurmLimit, SshPublicKey, UserAssociation, ValidGroupOfQos, ValidQos,
+    },
 };
 use anyhow::{ensure, Context};
 use log::debug;
@@ -19,9 +22,21 @@ pub struct Entity {
     pub mail: Option<TrimmedNonEmptyText>,
     pub group: Option<ResolvedGid>,
     pub default_qos: Option<ValidQos>,
-    /// TODO: Add validation if a present publickey is in valid format, OpenSsh
-    pub publickey: Option<TrimmedNonEmptyText>,
+    /// Validated SSH public keys in the OpenSSH format
+    pub publickey: Option<Vec<SshPublicKey>>,
     pub qos: Option<ValidGroupOfQos>,
+    /// Site-specific LDAP attributes which are set in addition to the other fields
+    pub extra_attributes: Vec<ExtraLdapAttribute>,
+    /// Names of site-specific LDAP attributes which are removed from the user
+    pub unset_extra_attributes: Vec<TrimmedNonEmptyText>,
+    /// Resource limits of the user which are set in Slurm
+    pub slurm_limits: Vec<SlurmLimit>,
+    /// Slurm associations which are added to the user
+    pub associations: Vec<UserAssociation>,
+    /// Slurm associations which are removed from the user
+    pub removed_associations: Vec<UserAssociation>,
+    /// Default account of the user in Slurm
+    pub default_account: Option<TrimmedNonEmptyText>,
 }
 
 impl Entity {
@@ -50,6 +65,8 @@ impl Entity {
     /// - If any given quality of service is not valid. See [`TrimmedNonEmptyText`]
     /// - If the default quality of service is not valid quality of service. See [`ValidQos`]
     /// - If loading the public key, parameter `on_load_pubkey`, of an user fails.
+    /// - If the loaded public keys are not valid. See [`SshPublicKey::parse_many`]
+    /// - If an extra LDAP attribute is not allowed by the configuration.
     pub fn new_inner(
         firstname: Option<TrimmedNonEmptyText>,
         lastname: Option<TrimmedNonEmptyText>,
@@ -57,7 +74,7 @@ impl Entity {
         config: &MgmtConfig,
         on_load_pubkey: impl Fn(&Path) -> AppResult<String>,
     ) -> AppResult<Self> {
-        let (username, group, mail, default_qos, publickey, qos) = to_add.into();
+        let (username, group, mail, default_qos, publickey, qos, extra_attributes) = to_add.into();
         let group = group
             .map(|group| {
                 let group_id = Group::from_str(group.as_ref().as_str())
@@ -94,10 +111,15 @@ impl Entity {
                 debug!("Trying to load public key at {}.", path);
 
                 let content = on_load_pubkey(Path::new(path.as_ref()))?;
-                TrimmedNonEmptyText::try_from(content)
+                SshPublicKey::parse_many(&content)
+                    .with_context(|| format!("Invalid SSH public key in file {}", path))
             })
             .transpose()?;
 
+        for next in extra_attributes.iter() {
+            ensure_allowed_extra_attribute(next.name(), &config.ldap_extra_attributes)?;
+        }
+
         Ok(Entity {
             username,
             firstname,
@@ -107,24 +129,132 @@ impl Entity {
             publickey,
             qos,
             mail,
+            extra_attributes,
+            unset_extra_attributes: Vec::new(),
+            slurm_limits: Vec::new(),
+            associations: Vec::new(),
+            removed_associations: Vec::new(),
+            default_account: None,
         })
     }
 
     /// # Errors
     ///
-    /// See [`Entity::new`]
+    /// - See [`Entity::new`]
+    /// - See [`Entity::with_unset_extra_attributes`]
+    /// - See [`Entity::with_slurm_limits`]
+    /// - See [`Entity::with_associations`]
     pub fn new_modifiable_conf(modif: Modifiable, conf: &MgmtConfig) -> AppResult<Self> {
-        let (firstname, lastname, common_user_fields) = modif.into();
-        Self::new(firstname, lastname, common_user_fields, conf)
+        let (
+            firstname,
+            lastname,
+            common_user_fields,
+            unset_extra_attributes,
+            slurm_limits,
+            add_associations,
+            remove_associations,
+            default_account,
+        ) = modif.into();
+        let entity = Self::new(firstname, lastname, common_user_fields, conf)?;
+        entity
+            .with_unset_extra_attributes(unset_extra_attributes, conf)?
+            .with_slurm_limits(slurm_limits)?
+            .with_associations(add_associations, remove_associations, default_account, conf)
+    }
+
+    /// # Errors
+    ///
+    /// - If a QOS of an association to add is not listed in valid_qos of the configuration.
+    /// - If an association to add is also removed at the same time.
+    pub fn with_associations(
+        self,
+        associations: Vec<UserAssociation>,
+        removed_associations: Vec<UserAssociation>,
+        default_account: Option<TrimmedNonEmptyText>,
+        conf: &MgmtConfig,
+    ) -> AppResult<Self> {
+        for association in associations.iter() {
+            let qos: Vec<&str> = association
+                .qos()
+                .iter()
+                .map(|qos| qos.as_ref().as_str())
+                .collect();
+            let valid_qos: Vec<&str> = conf.valid_qos.iter().map(String::as_str).collect();
+            ensure!(
+                is_valid_qos(&qos, &valid_qos),
+                "QOS ({:?}) of Slurm association {} are not all listed in valid_qos",
+                qos,
+                association
+            );
+            ensure!(
+                !removed_associations
+                    .iter()
+                    .any(|removed| removed.matches(association)),
+                "Slurm association {} can not be added and removed at the same time",
+                association
+            );
+        }
+
+        Ok(Self {
+            associations,
+            removed_associations,
+            default_account,
+            ..self
+        })
+    }
+
+    /// # Errors
+    ///
+    /// - If a Slurm limit is given more than once.
+    pub fn with_slurm_limits(self, slurm_limits: Vec<SlurmLimit>) -> AppResult<Self> {
+        ensure_unique_names(&slurm_limits)?;
+        Ok(Self {
+            slurm_limits,
+            ..self
+        })
+    }
+
+    /// # Errors
+    ///
+    /// - If an extra LDAP attribute to remove is not allowed by the configuration.
+    /// - If an extra LDAP attribute is to be set and removed at the same time.
+    pub fn with_unset_extra_attributes(
+        self,
+        unset_extra_attributes: Vec<TrimmedNonEmptyText>,
+        conf: &MgmtConfig,
+    ) -> AppResult<Self> {
+        for name in unset_extra_attributes.iter() {
+            ensure_allowed_extra_attribute(name.as_ref(), &conf.ldap_extra_attributes)?;
+            ensure!(
+                !self
+                    .extra_attributes
+                    .iter()
+                    .any(|to_set| to_set.name() == name.as_ref().as_str()),
+                "LDAP attribute {} can not be set and removed at the same time",
+                name
+            );
+        }
+
+        Ok(Self {
+            unset_extra_attributes,
+            ..self
+        })
     }
 
     /// # Errors
     ///
-    /// See [`Entity::new`]
+    /// - See [`Entity::new`]
+    /// - See [`Entity::with_associations`]
     pub fn new_user_addition_conf(to_add: UserToAdd, conf: &MgmtConfig) -> AppResult<Self> {
-        let (firstname, lastname, common_user_fields) = to_add.into();
+        let (firstname, lastname, common_user_fields, associations, default_account, _) =
+            to_add.into();
         let (firstname, lastname) = (Some(firstname), Some(lastname));
-        Self::new(firstname, lastname, common_user_fields, conf)
+        Self::new(firstname, lastname, common_user_fields, conf)?.with_associations(
+            associations,
+            Vec::new(),
+            default_account,
+            conf,
+        )
     }
 }
 
@@ -176,9 +306,93 @@ mod testing {
                 valid_qos: vec!["valid".into(), "basic".into()],
                 ..MgmtConfig::default()
             },
-            |_path| Ok("xxxxxx".to_string()),
+            |_path| {
+                Ok("ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIBSxyNRtflgd8DJXnINmlNjoEmPyajfAGqbtrP16+Qq4 alice@example".to_string())
+            },
         );
 
         insta::assert_debug_snapshot!(actual);
     }
+
+    #[test]
+    fn error_for_not_allowed_extra_attribute() {
+        let mut input = CommonUserFields::new("SomeUser".try_into().unwrap());
+        input.extra_attributes = vec!["userPassword=secret".parse().unwrap()];
+        let actual = Entity::new_inner(
+            None,
+            None,
+            input,
+            &MgmtConfig {
+                ldap_extra_attributes: vec!["employeeNumber".into()],
+                ..MgmtConfig::default()
+            },
+            |_| panic!(),
+        );
+
+        assert!(actual.is_err());
+    }
+
+    #[test]
+    fn error_for_setting_and_unsetting_same_extra_attribute() {
+        let config = MgmtConfig {
+            ldap_extra_attributes: vec!["employeeNumber".into(), "telephoneNumber".into()],
+            ..MgmtConfig::default()
+        };
+        let mut input = CommonUserFields::new("SomeUser".try_into().unwrap());
+        input.extra_attributes = vec!["employeeNumber=4711".parse().unwrap()];
+        let entity = Entity::new_inner(None, None, input, &config, |_| panic!()).unwrap();
+
+        assert!(entity
+            .clone()
+            .with_unset_extra_attributes(vec!["telephoneNumber".try_into().unwrap()], &config)
+            .is_ok());
+        assert!(entity
+            .with_unset_extra_attributes(vec!["employeeNumber".try_into().unwrap()], &config)
+            .is_err());
+    }
+
+    #[test]
+    fn error_for_invalid_associations() {
+        let config = MgmtConfig {
+            valid_qos: vec!["basic".into()],
+            ..MgmtConfig::default()
+        };
+        let entity = Entity::new_inner(
+            None,
+            None,
+            CommonUserFields::new("SomeUser".try_into().expect("Should be a valid username")),
+            &config,
+            |_| panic!(),
+        )
+        .expect("Should be a valid entity");
+        let parse =
+            |s: &str| -> UserAssociation { s.parse().expect("Should be a valid association") };
+
+        assert!(entity
+            .clone()
+            .with_associations(
+                vec![parse("account=project-a,qos=basic")],
+                vec![parse("project-b")],
+                None,
+                &config
+            )
+            .is_ok());
+        assert!(entity
+            .clone()
+            .with_associations(
+                vec![parse("account=project-a,qos=advanced")],
+                Vec::new(),
+                None,
+                &config
+            )
+            .is_err());
+        assert!(entity
+            .with_associations(
+                vec![parse("account=project-a,partition=gpu")],
+                vec![parse("project-a")],
+                None,
+                &config
+            )
+            .is_err());
+    }
 }
@@ -1,42 +1,88 @@
+mod concurrent_change;
 mod ldap_config;
 mod ldap_credential;
+mod ldap_filter;
+mod ldap_password_hash;
 mod ldap_paths;
-mod ldap_search_result;
+mod ldap_schema;
 mod ldap_session;
 mod ldap_simple_credential;
+mod ldap_user;
+pub mod ldif;
 pub mod text_list_output;
 
 use chrono::Utc;
 pub use ldap_config::LDAPConfig;
 pub use ldap_credential::LdapCredential;
-pub use ldap_search_result::LdapSearchResult;
+pub use ldap_filter::LdapFilter;
+pub use ldap_password_hash::ssha_password_hash;
+pub use ldap_schema::{LdapSchema, MissingSchemaItems};
 pub use ldap_session::LdapSession;
 pub use ldap_simple_credential::LdapSimpleCredential;
-use once_cell::sync::Lazy;
+pub use ldap_user::{InvalidLdapEntry, LdapUser, ListedLdapUsers, ListingColumn};
 
 #[cfg(test)]
 pub mod testing;
+use crate::cli::{CommonUserFields, ListFilter};
+use crate::config::LdapAttributes;
+use crate::ldap::ldif::LdifEntry;
 use crate::prelude::AppResult;
-use crate::util::{get_new_uid, hashset_from_vec_str};
-use crate::{prelude::*, NewEntity};
+use crate::util::{
+    ensure_not_duplicate, get_new_uid, hashset_from_vec_str, ExtraLdapAttribute, ResolvedGid,
+    SshPublicKey, TrimmedNonEmptyText,
+};
+use crate::{prelude::*, Entity, Group, NewEntity};
 use crate::{ChangesToUser, MgmtConfig};
+use ldap3::adapters::PagedResults;
 use ldap3::controls::{MakeCritical, RelaxRules};
-use ldap3::{LdapConn, LdapError, LdapResult, Mod, Scope, SearchEntry, SearchResult};
+use ldap3::{
+    dn_escape, LdapConn, LdapConnSettings, LdapError, LdapResult, Mod, Scope, SearchEntry,
+    SearchResult,
+};
 use log::{debug, info, warn};
 use maplit::hashset;
 use std::collections::HashSet;
 
+const OBJECT_CLASS: &str = "objectClass";
+/// Object class which allows the attribute `shadowLastChange`
+const SHADOW_ACCOUNT: &str = "shadowAccount";
+
 /// Tries to connect to a LDAP instance and authenticates as an user there.
 ///
+/// The servers of [`LDAPConfig::ldap_servers`] are tried in order.
+/// If a server can not be reached within [`LDAPConfig::connect_timeout`], the next one is tried.
+///
 /// # Errors
 ///
-/// - If the connection to the LDAP instance fails.
+/// - If the connection to every LDAP instance fails.
 /// - If the binding as the user fails aka authentication
 pub fn make_ldap_connection<T>(ldap_config: &LDAPConfig<T>) -> AppResult<LdapConn>
 where
     T: LdapCredential,
 {
-    let mut ldap = LdapConn::new(ldap_config.ldap_server())?;
+    let settings = LdapConnSettings::new().set_conn_timeout(ldap_config.connect_timeout());
+    let mut servers = ldap_config.ldap_servers().iter().peekable();
+    let mut ldap = loop {
+        let server = servers
+            .next()
+            .ok_or_else(|| anyhow!("No LDAP server is configured (ldap_server)"))?;
+        match LdapConn::with_settings(settings.clone(), server) {
+            Ok(connection) => {
+                debug!("LDAP connection established to {}", server);
+                break connection;
+            }
+            Err(error) => match servers.peek() {
+                Some(next) => warn!(
+                    "Could not connect to LDAP server {} ({}). Failing over to {}",
+                    server, error, next
+                ),
+                None => {
+                    return Err(error)
+                        .with_context(|| format!("Could not connect to LDAP server {}", server))
+                }
+            },
+        }
+    };
     let _ = ldap
         .simple_bind(ldap_config.bind(), ldap_config.password()?)
         .with_context(|| {
@@ -50,6 +96,7 @@ where
 
 /// # Errors
 ///
+/// - If the LDAP server does not know a configured object class or attribute. See [`check_ldap_schema`]
 /// - If the existence of the user can not be checked. See [`username_exists`]
 /// - If determining the next UID fails. See [`find_next_available_uid`]
 /// - If the adding of an user in the LDAP database failed.
@@ -61,7 +108,9 @@ pub fn add_ldap_user<T>(
 where
     T: LdapCredential,
 {
-    let exitence_of_username = username_exists(entity.username.as_ref(), ldap_session.config())?;
+    check_ldap_schema_before_adding(config, ldap_session)?;
+
+    let exitence_of_username = username_exists(entity.username.as_ref(), ldap_session)?;
     if exitence_of_username {
         warn!(
             "User {} already exists in LDAP. Skipping LDAP user creation.",
@@ -78,94 +127,153 @@ where
         ldap_session.config().bind()
     );
 
-    add_to_ldap_db(entity, uid_number, ldap_session, config)?;
+    let password_hash = entity.password.as_deref().map(ssha_password_hash);
+    add_to_ldap_db(
+        entity,
+        uid_number,
+        password_hash.as_deref(),
+        ldap_session,
+        config,
+    )?;
 
     info!("Added LDAP user {}", entity.username);
-    return Ok(());
+    Ok(())
+}
+
+/// Bails if the LDAP server does not know a configured object class or attribute.
+/// Only warns if the schema can not be read at all.
+fn check_ldap_schema_before_adding<T>(
+    config: &MgmtConfig,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    match check_ldap_schema(config, ldap_session) {
+        Ok(missing) if !missing.is_empty() => {
+            bail!(
+                "LDAP schema check before adding the user failed.\n{}",
+                missing
+            )
+        }
+        Ok(_) => debug!("LDAP schema check before adding the user succeeded"),
+        Err(error) => warn!(
+            "Skipping LDAP schema check because the schema could not be read: {:?}",
+            error
+        ),
+    }
+    Ok(())
+}
 
-    fn add_to_ldap_db<T>(
+/// Adds the user with the given uidNumber and already hashed password to LDAP.
+/// The username is escaped within the DN of the new entry.
+fn add_to_ldap_db<T>(
+    entity: &NewEntity,
+    uid: u32,
+    password_hash: Option<&str>,
+    ldap_session: &mut LdapSession<T>,
+    config: &MgmtConfig,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    fn add_fields<T>(
+        connection: &mut LdapConn,
         entity: &NewEntity,
-        uid: u32,
-        ldap_session: &mut LdapSession<T>,
-        config: &MgmtConfig,
+        ldap_config: &LDAPConfig<T>,
+        fields: Vec<(&str, HashSet<&str>)>,
     ) -> AppResult
     where
         T: LdapCredential,
     {
-        fn add_fields<T>(
-            connection: &mut LdapConn,
-            entity: &NewEntity,
-            ldap_config: &LDAPConfig<T>,
-            fields: Vec<(&str, HashSet<&str>)>,
-        ) -> AppResult
-        where
-            T: LdapCredential,
-        {
-            let result_from_adding = connection.add(
-                &format!("uid={},{}", entity.username, ldap_config.base()),
-                fields,
-            );
+        let result_from_adding = connection.add(
+            &format!(
+                "{}={},{}",
+                ldap_config.attributes().username,
+                dn_escape(entity.username.as_ref().as_str()),
+                ldap_config.base_of_gid(entity.group.gid())
+            ),
+            fields,
+        );
 
-            ldap_is_success(result_from_adding).context("Unable to create LDAP user!")?;
-            Ok(())
+        ldap_is_success(result_from_adding).context("Unable to create LDAP user!")?;
+        Ok(())
+    }
+
+    let un = entity.username.as_ref().as_str();
+    let gid = entity.group.gid().to_string();
+    let uid = uid.to_string();
+    let ln = entity.lastname.as_ref().as_str();
+    let gn = entity.firstname.as_ref().as_str();
+    let mail: &str = entity
+        .mail
+        .as_ref()
+        .map(|trimmmed| trimmmed.as_ref().as_str())
+        .unwrap_or("");
+
+    let def_qos = entity.default_qos.as_ref().as_str();
+    let home = &config.home_directory(un)?;
+    let qos: HashSet<&str> = (&entity.qos)
+        .into_iter()
+        .map(|qos| qos.as_ref().as_str())
+        .collect();
+    let pubkeys: Vec<String> = entity.publickey.iter().map(|key| key.to_string()).collect();
+    // Empty value as placeholder like for the mail if no key is given
+    let pubkey: HashSet<&str> = if pubkeys.is_empty() {
+        hashset! {""}
+    } else {
+        pubkeys.iter().map(String::as_str).collect()
+    };
+
+    ldap_session.action(|connection, ldap_config| {
+        let attributes = ldap_config.attributes();
+        let mut fields = vec![
+            (attributes.common_name.as_str(), hashset! {un}),
+            (
+                "objectClass",
+                hashset_from_vec_str(&config.objectclass_common).to_owned(),
+            ),
+            (attributes.gid_number.as_str(), hashset! {gid.as_str()}),
+            (attributes.uid_number.as_str(), hashset! {uid.as_str()}),
+            (attributes.username.as_str(), hashset! {un}),
+            (attributes.lastname.as_str(), hashset! {ln}),
+            (attributes.firstname.as_str(), hashset! {gn}),
+            (attributes.mail.as_str(), hashset! {mail}),
+            (attributes.default_qos.as_str(), hashset! {def_qos}),
+            (attributes.home_directory.as_str(), hashset! {home.as_str()}),
+            (attributes.qos.as_str(), qos.clone()),
+            (attributes.publickey.as_str(), pubkey.clone()),
+            (
+                attributes.login_shell.as_str(),
+                hashset! {config.login_shell.as_str()},
+            ),
+        ];
+        if let Some(hash) = password_hash {
+            fields.push((attributes.password.as_str(), hashset! {hash}));
+        }
+        let modified_at = Utc::now().to_rfc3339();
+        if ldap_config.track_modifications() {
+            fields.extend(modification_fields(
+                attributes,
+                &modified_at,
+                ldap_config.bind(),
+            ));
         }
 
-        let un = entity.username.as_ref().as_str();
-        let gid = entity.group.gid().to_string();
-        let uid = uid.to_string();
-        let ln = entity.lastname.as_ref().as_str();
-        let gn = entity.firstname.as_ref().as_str();
-        let mail: &str = entity
-            .mail
-            .as_ref()
-            .map(|trimmmed| trimmmed.as_ref().as_str())
-            .unwrap_or("");
-
-        let def_qos = entity.default_qos.as_ref().as_str();
-        let home = &format!("/home/{}", entity.username);
-        let qos: HashSet<&str> = (&entity.qos)
-            .into_iter()
-            .map(|qos| qos.as_ref().as_str())
-            .collect();
-        let pubkey = entity
-            .publickey
-            .as_ref()
-            .map(|trimmmed| trimmmed.as_ref().as_str())
-            .unwrap_or("");
-
-        ldap_session.action(|connection, ldap_config| {
-            let mut fields = vec![
-                ("cn", hashset! {un}),
-                (
-                    "objectClass",
-                    hashset_from_vec_str(&config.objectclass_common).to_owned(),
-                ),
-                ("gidNumber", hashset! {gid.as_str()}),
-                ("uidNumber", hashset! {uid.as_str()}),
-                ("uid", hashset! {un}),
-                ("sn", hashset! {ln}),
-                ("givenName", hashset! {gn}),
-                ("mail", hashset! {mail}),
-                ("slurmDefaultQos", hashset! {def_qos}),
-                ("homeDirectory", hashset! {home.as_str()}),
-                ("slurmQos", qos),
-                ("sshPublicKey", hashset! {pubkey}),
-                ("loginShell", hashset! {config.login_shell.as_str()}),
-            ];
-
-            if config.ldap_add_created_at {
-                let created_at = Utc::now().to_rfc3339();
-                let attr = hashset! {created_at.as_str()};
-                fields.push(("createdAt", attr));
-
-                add_fields(connection, entity, ldap_config, fields)?;
-                Ok(())
-            } else {
-                add_fields(connection, entity, ldap_config, fields)?;
-                Ok(())
-            }
-        })
-    }
+        if config.ldap_add_created_at {
+            let created_at = Utc::now().to_rfc3339();
+            let attr = hashset! {created_at.as_str()};
+            fields.push((attributes.created_at.as_str(), attr));
+            fields.extend(extra_attribute_fields(&entity.extra_attributes));
+
+            add_fields(connection, entity, ldap_config, fields)?;
+            Ok(())
+        } else {
+            fields.extend(extra_attribute_fields(&entity.extra_attributes));
+            add_fields(connection, entity, ldap_config, fields)?;
+            Ok(())
+        }
+    })
 }
 
 /// # Errors
@@ -200,10 +308,20 @@ where
     Ok(())
 }
 
+/// Changes the attributes of an user.
+///
+/// The modification is only applied if the user was not changed since reading the entry.
+/// This is ensured via the assertion control (RFC 4528) on the read QOS and modifyTimestamp.
+/// If the server does not support this control, only the deletion of the read QOS
+/// ensures that those are not changed concurrently.
+///
 /// # Errors
 ///
-/// - If finding the DN-LDAP  by the UID fails. See [`find_dn_by_uid`]
-/// - If finding the quality of service by the UID fails. See [`find_qos_by_uid`]
+/// - If reading the user fails or no user is found.
+/// - If the user was changed concurrently since it was read.
+/// - If the modification of the user in the LDAP database failed.
+/// - If the entry could not be moved to the path of a new group after its gidNumber was changed.
+///   The error names the command which moves the entry.
 pub fn modify_ldap_user<T>(
     modifiable: &ChangesToUser,
     ldap_session: &mut LdapSession<T>,
@@ -211,77 +329,671 @@ pub fn modify_ldap_user<T>(
 where
     T: LdapCredential,
 {
-    let dn = find_dn_by_uid(modifiable.username.as_ref(), ldap_session)
-        .with_context(|| {
-            format!(
-                "No DN found for username {}! Unable to modify user.",
-                modifiable.username.as_ref()
-            )
-        })?
-        .ok_or(anyhow!("No dn found for uid"))?;
+    let username = modifiable.username.as_ref();
+    let read = read_entry_for_modification(username, ldap_session)
+        .with_context(|| format!("Unable to read user {} for modification.", username))?
+        .ok_or_else(|| anyhow!("No dn found for uid {}! Unable to modify user.", username))?;
+    let dn = read.dn.clone();
 
     // Prepare replace operation
 
+    let attributes = ldap_session.config().attributes().clone();
     let old_qos = match &modifiable.qos {
-        Some(_) => find_qos_by_uid(modifiable.username.as_ref(), ldap_session),
-        None => Ok(Vec::default()),
-    }?;
-    let mod_vec = make_modification_vec(modifiable, &old_qos);
+        Some(_) => read.attrs.get(&attributes.qos).cloned().unwrap_or_default(),
+        None => Vec::default(),
+    };
+    debug!("Fetched QOS: {:?}", old_qos);
+    let modify_timestamp = read
+        .attrs
+        .get(concurrent_change::MODIFY_TIMESTAMP)
+        .and_then(|values| values.first());
+    let assertion = concurrent_change::unchanged_since_read(
+        &attributes,
+        &old_qos,
+        modify_timestamp.map(String::as_str),
+    );
+    let new_pubkeys: Option<Vec<String>> = modifiable
+        .publickey
+        .as_ref()
+        .map(|keys| keys.iter().map(|key| key.to_string()).collect());
+    let new_gid = modifiable
+        .group
+        .as_ref()
+        .map(|group| group.gid().to_string());
+    let mut mod_vec = make_modification_vec(modifiable, &old_qos, &new_pubkeys, &attributes);
+    if let Some(gid) = &new_gid {
+        info!("Changing the field: {}", attributes.gid_number);
+        mod_vec.push(Mod::Replace(
+            &attributes.gid_number,
+            hashset! {gid.as_str()},
+        ));
+    }
 
+    let applied = concurrent_change::applied_modifications(&mod_vec);
+    let mut attempts = 0;
     // Replace userPassword at given dn
-    ldap_session.action(|ldap_connection, _| {
-        let result = ldap_connection
-            .with_controls(RelaxRules.critical())
-            .modify(&dn, mod_vec);
-        ldap_is_success(result).context("User modification in LDAP failed!")
+    ldap_session.action(|ldap_connection, ldap_config| {
+        attempts += 1;
+        let mut mod_vec = mod_vec.clone();
+        let modified_at = Utc::now().to_rfc3339();
+        if ldap_config.track_modifications() {
+            let attributes = ldap_config.attributes();
+            let tracked = modification_fields(attributes, &modified_at, ldap_config.bind());
+            mod_vec.extend(
+                tracked
+                    .into_iter()
+                    .map(|(name, values)| Mod::Replace(name, values)),
+            );
+        }
+        let mut controls = vec![RelaxRules.critical().into()];
+        controls.extend(assertion.as_ref().map(concurrent_change::assertion_control));
+        let mut result = ldap_is_success(
+            ldap_connection
+                .with_controls(controls)
+                .modify(&dn, mod_vec.clone()),
+        );
+        if assertion.is_some()
+            && result
+                .as_ref()
+                .is_err_and(concurrent_change::is_unsupported_control)
+        {
+            warn!(
+                "LDAP server does not support the assertion control. \
+                 Modifying user {} without checking all concurrent changes.",
+                username
+            );
+            result = ldap_is_success(
+                ldap_connection
+                    .with_controls(RelaxRules.critical())
+                    .modify(&dn, mod_vec),
+            );
+        }
+        match result {
+            Err(error) if concurrent_change::is_concurrent_change(&error, !old_qos.is_empty()) => {
+                // The first attempt might have been applied before the connection was lost.
+                // Then the entry does not match the state read before anymore.
+                if attempts > 1 && is_already_applied(ldap_connection, &dn, applied.as_ref())? {
+                    info!(
+                        "Modification of user {} in LDAP was already applied by the first attempt",
+                        username
+                    );
+                    return Ok(());
+                }
+                bail!(
+                    "LDAP entry of user {} was changed concurrently since it was read. \
+                     Please retry the modification.",
+                    username
+                )
+            }
+            result => result.context("User modification in LDAP failed!"),
+        }
     })?;
 
+    if let Some(group) = &modifiable.group {
+        // The move is a separate LDAP operation after the modification.
+        // If it fails, the entry keeps the new gidNumber under the path of the old group.
+        move_to_base_of_gid(&dn, group.gid(), ldap_session).with_context(|| {
+            format!(
+                "User {} has the gidNumber of group {} now, but its LDAP entry is still at {}. \
+                 Run 'usermgmt modify {} --group {} --slurm false' to move it.",
+                username,
+                group.id(),
+                dn,
+                username,
+                group.id()
+            )
+        })?;
+    }
+
     info!("Successfully modified user {} in LDAP", modifiable.username);
     Ok(())
 }
 
-/// List all LDAP users and some attributes
+/// Searches the user with parameter `username` and returns its DN with the QOS and the modifyTimestamp.
+/// These values are needed to detect concurrent changes when modifying the user.
 ///
-/// It currently outputs all values in line separated by commas.
+/// # Errors
+///
+/// - If the connection to the LDAP instance fails
+fn read_entry_for_modification<T>(
+    username: &str,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult<Option<SearchEntry>>
+where
+    T: LdapCredential,
+{
+    let search = ldap_session.action(|connection, config| {
+        connection
+            .search(
+                config.search_base(),
+                config.search_scope(),
+                &LdapFilter::by_username(config.attributes(), username).to_string(),
+                vec![
+                    config.attributes().qos.as_str(),
+                    concurrent_change::MODIFY_TIMESTAMP,
+                ],
+            )
+            .context("LDAP search failed")
+    })?;
+    let entry = search.success()?.0.into_iter().next();
+    Ok(entry.map(SearchEntry::construct))
+}
+
+/// Returns true if the entry at parameter `dn` matches parameter `applied`.
+/// See [`concurrent_change::applied_modifications`]
 ///
 /// # Errors
 ///
-/// - If the connection to the LDAP instance fails. See [`make_ldap_connection`]
-/// - If the searching in LDAP failed
-pub fn list_ldap_users<T>(ldap_config: &LDAPConfig<T>) -> AppResult<LdapSearchResult>
+/// - If the LDAP search fails.
+fn is_already_applied(
+    connection: &mut LdapConn,
+    dn: &str,
+    applied: Option<&LdapFilter>,
+) -> AppResult<bool> {
+    let Some(applied) = applied else {
+        return Ok(false);
+    };
+    // Requests no attributes, see RFC 4511, 4.5.1.8
+    let search = connection
+        .search(dn, Scope::Base, &applied.to_string(), vec!["1.1"])
+        .context("LDAP search for the applied modification failed")?;
+    Ok(!search.success()?.0.is_empty())
+}
+
+/// Moves the entry at parameter `dn` into the path in which users with parameter `gid` are added.
+/// Does nothing if the entry is already there.
+///
+/// # Errors
+///
+/// - If the entry could not be moved in the LDAP database.
+fn move_to_base_of_gid<T>(dn: &str, gid: i32, ldap_session: &mut LdapSession<T>) -> AppResult
 where
     T: LdapCredential,
 {
-    // Establish LDAP connection and bind
-    let mut ldap =
-        make_ldap_connection(ldap_config).context("Error while connecting via LDAP !")?;
+    let (rdn, parent) = dn.split_once(',').unwrap_or((dn, ""));
+    let new_parent = ldap_session.config().base_of_gid(gid).to_string();
+    if parent.eq_ignore_ascii_case(&new_parent) {
+        return Ok(());
+    }
+    ldap_session.action(|ldap_connection, _| {
+        let result = ldap_connection.modifydn(dn, rdn, true, Some(&new_parent));
+        ldap_is_success(result)
+            .with_context(|| format!("Unable to move LDAP entry {} to {}!", dn, new_parent))
+    })?;
+    info!("Moved LDAP entry {} to {}", dn, new_parent);
+    Ok(())
+}
 
-    debug!(
-        "LDAP connection established to {}. Will search under {}",
-        ldap_config.bind(),
-        ldap_config.base()
-    );
+/// Replaces the password of an user with a salted hash of parameter `password`.
+/// If parameter `force_change` is true, the attribute `shadowLastChange` is set to 0
+/// which forces the user to change the password at the next login.
+/// The object class `shadowAccount` is added to the user if missing.
+///
+/// # Errors
+///
+/// - If finding the LDAP-DN by the UID fails. See [`find_dn_by_uid`]
+/// - If the object classes of the user can not be searched.
+/// - If the modification of the user in the LDAP database failed.
+pub fn reset_ldap_password<T>(
+    username: &str,
+    password: &str,
+    force_change: bool,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    let dn = find_dn_by_uid(username, ldap_session)
+        .with_context(|| format!("No DN found for username {}!", username))?
+        .ok_or_else(|| anyhow!("No dn found for uid {}", username))?;
+
+    let lacks_shadow_account = force_change
+        && !object_classes_of(&dn, ldap_session)?
+            .iter()
+            .any(|object_class| object_class.eq_ignore_ascii_case(SHADOW_ACCOUNT));
+
+    let password_hash = ssha_password_hash(password);
+    ldap_session.action(|ldap_connection, ldap_config| {
+        let attributes = ldap_config.attributes();
+        let mut mod_vec = vec![Mod::Replace(
+            attributes.password.as_str(),
+            hashset! {password_hash.as_str()},
+        )];
+        if lacks_shadow_account {
+            info!("Adding object class {} to {}", SHADOW_ACCOUNT, dn);
+            mod_vec.push(Mod::Add(OBJECT_CLASS, hashset! {SHADOW_ACCOUNT}));
+        }
+        if force_change {
+            mod_vec.push(Mod::Replace(
+                attributes.shadow_last_change.as_str(),
+                hashset! {"0"},
+            ));
+        }
+        let result = ldap_connection.modify(&dn, mod_vec);
+        ldap_is_success(result).context("Password reset in LDAP failed!")
+    })?;
+
+    info!("Successfully reset password of user {} in LDAP", username);
+    Ok(())
+}
+
+/// Returns the object classes of the entry with the DN `dn`.
+///
+/// # Errors
+///
+/// - If the searching in LDAP failed
+fn object_classes_of<T>(dn: &str, ldap_session: &mut LdapSession<T>) -> AppResult<Vec<String>>
+where
+    T: LdapCredential,
+{
+    let (entries, _) = ldap_session.action(|con, _| {
+        let filter = LdapFilter::any().to_string();
+        con.search(dn, Scope::Base, &filter, vec![OBJECT_CLASS])
+            .and_then(SearchResult::success)
+            .with_context(|| format!("LDAP search for object classes of {} failed", dn))
+    })?;
+    Ok(entries
+        .into_iter()
+        .flat_map(|entry| {
+            SearchEntry::construct(entry)
+                .attrs
+                .into_iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case(OBJECT_CLASS))
+                .flat_map(|(_, values)| values)
+        })
+        .collect())
+}
 
-    let attrs = SORTED_LDAP_LISTING_ATTRIBUTES.as_slice();
+/// List all LDAP users matching parameter `filter`
+/// with the attributes managed by this application except the password.
+///
+/// Entries which can not be parsed as user are returned separately with the reason.
+/// See [`ListedLdapUsers`].
+///
+/// # Errors
+///
+/// - If the connection to the LDAP instance fails. See [`LdapSession::action`]
+/// - If the searching in LDAP failed
+pub fn list_ldap_users<T>(
+    config: &MgmtConfig,
+    filter: &ListFilter,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult<ListedLdapUsers>
+where
+    T: LdapCredential,
+{
+    let attributes = ldap_session.config().attributes().clone();
+    let mut attrs: Vec<&str> = attributes
+        .all()
+        .into_iter()
+        .filter(|name| *name != attributes.password)
+        .collect();
+    attrs.extend(config.ldap_extra_attributes.iter().map(String::as_str));
+    let search_filter = listing_filter(filter, config);
     // Search for all entities under base dn
-    let search_result = ldap
-        .search(
-            ldap_config.base(),
-            Scope::OneLevel,
-            "(objectclass=*)",
-            attrs,
-        )
-        .context("Error during LDAP search!")?;
+    let entries = ldap_session.action(|connection, ldap_config| {
+        debug!(
+            "Searching for users with {} under {}",
+            search_filter,
+            ldap_config.search_base()
+        );
+        search_all_pages(connection, ldap_config, &search_filter, attrs.clone())
+            .context("Error during LDAP search!")
+    })?;
+
+    let mut listed = ListedLdapUsers::new(entries, &attributes, &config.ldap_extra_attributes);
+    // The creation date has no ordering rule in the LDAP schema.
+    // Therefore the range of dates can not be part of the search filter.
+    listed.users.retain(|user| {
+        filter.created_in_range(user.created_at.as_ref())
+            && (!filter.missing_pubkey || user.publickeys.is_empty())
+    });
+    Ok(listed)
+}
+
+/// Returns the search filter for the options of parameter `filter`.
+/// Only the presence of a creation date is checked, not its range.
+/// See [`ListFilter::created_in_range`].
+fn listing_filter(filter: &ListFilter, config: &MgmtConfig) -> LdapFilter {
+    let attributes = &config.ldap_attributes;
+    let mut conditions = vec![LdapFilter::any()];
+    if let Some(group) = filter.group {
+        let gid = ResolvedGid::new(group, config).gid();
+        conditions.push(LdapFilter::equality(
+            attributes.gid_number.as_str(),
+            gid.to_string(),
+        ));
+    }
+    if let Some(qos) = &filter.qos {
+        conditions.push(LdapFilter::equality(attributes.qos.as_str(), qos.as_ref()));
+    }
+    if let Some(default_qos) = &filter.default_qos {
+        conditions.push(LdapFilter::equality(
+            attributes.default_qos.as_str(),
+            default_qos.as_ref(),
+        ));
+    }
+    if filter.created_after.is_some() || filter.created_before.is_some() {
+        conditions.push(LdapFilter::present(attributes.created_at.as_str()));
+    }
+    if filter.missing_pubkey {
+        // Users without keys might have an empty value as placeholder.
+        conditions.push(LdapFilter::or([
+            LdapFilter::not(LdapFilter::present(attributes.publickey.as_str())),
+            LdapFilter::equality(attributes.publickey.as_str(), ""),
+        ]));
+    }
+    if let Some(text) = &filter.match_text {
+        conditions.push(LdapFilter::or(
+            [
+                &attributes.username,
+                &attributes.firstname,
+                &attributes.lastname,
+                &attributes.mail,
+            ]
+            .map(|attribute| LdapFilter::contains(attribute.as_str(), text.as_ref())),
+        ));
+    }
+    LdapFilter::and(conditions)
+}
+
+/// Returns every entry under the base DN with the attributes managed by this application:
+/// the ones within `ldap_attributes`, `ldap_extra_attributes` and the object classes.
+///
+/// # Errors
+///
+/// - If the searching in LDAP failed. See [`search_all_pages`]
+pub fn export_ldap_entries<T>(
+    config: &MgmtConfig,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult<Vec<LdifEntry>>
+where
+    T: LdapCredential,
+{
+    let entries = ldap_session.action(|connection, ldap_config| {
+        let mut attrs = ldap_config.attributes().all();
+        attrs.push(OBJECT_CLASS);
+        attrs.extend(config.ldap_extra_attributes.iter().map(String::as_str));
+        search_all_pages(connection, ldap_config, &LdapFilter::any(), attrs)
+            .context("Error during LDAP search for export!")
+    })?;
+
+    let mut exported: Vec<LdifEntry> = entries
+        .into_iter()
+        .map(|entry| LdifEntry::new(entry.dn, entry.attrs.into_iter().collect()))
+        .collect();
+    exported.sort_by(|left, right| left.dn.cmp(&right.dn));
+    Ok(exported)
+}
+
+/// Adds every entry of an LDIF file as new user under the base DN.
+///
+/// Every entry is validated and added like a user given via [`add_ldap_user`].
+/// Only the uidNumber and the password hash within the file are kept.
+/// The other attributes which this application derives itself, like the home directory
+/// or the time of creation, are determined anew.
+///
+/// All entries are checked before anything is added.
+/// Nothing is added if any entry conflicts with an existing user or another entry,
+/// has attributes which are not managed by this application or is not a valid user.
+/// See [`find_import_conflicts`] and [`new_entity_of_entry`].
+///
+/// # Errors
+///
+/// - If the LDAP server does not know a configured object class or attribute. See [`check_ldap_schema`]
+/// - If the searching for existing users in LDAP failed.
+/// - If there is any conflict.
+/// - If the adding of an user in the LDAP database failed.
+pub fn import_ldap_entries<T>(
+    entries: &[LdifEntry],
+    config: &MgmtConfig,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    check_ldap_schema_before_adding(config, ldap_session)?;
+
+    let attributes = ldap_session.config().attributes().clone();
+    let existing = ldap_session.action(|connection, ldap_config| {
+        let attrs = vec![attributes.username.as_str(), attributes.uid_number.as_str()];
+        search_all_pages(connection, ldap_config, &LdapFilter::any(), attrs)
+            .context("Error during LDAP search for existing users!")
+    })?;
+    let existing: Vec<LdifEntry> = existing
+        .into_iter()
+        .map(|entry| LdifEntry::new(entry.dn, entry.attrs.into_iter().collect()))
+        .collect();
 
-    let search_result = LdapSearchResult::from_ldap_raw_search(attrs.iter(), &search_result);
+    let mut conflicts = find_import_conflicts(entries, &existing, config);
+    let mut to_add = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let uid_number = entry
+            .first_value(&attributes.uid_number)
+            .and_then(|uid_number| uid_number.parse::<u32>().ok());
+        match (new_entity_of_entry(entry, config), uid_number) {
+            (Ok(entity), Some(uid_number)) => {
+                let password_hash = entry.first_value(&attributes.password);
+                to_add.push((entity, uid_number, password_hash));
+            }
+            // Missing or invalid uidNumbers are already reported by the conflict check.
+            (Ok(_), None) => {}
+            (Err(error), _) => {
+                conflicts.push(format!("Entry {} is no valid user: {:#}", entry.dn, error))
+            }
+        }
+    }
+    if !conflicts.is_empty() {
+        bail!(
+            "Nothing was imported because of the following conflicts:\n{}",
+            conflicts.join("\n")
+        );
+    }
+
+    for (entity, uid_number, password_hash) in to_add {
+        add_to_ldap_db(&entity, uid_number, password_hash, ldap_session, config)
+            .with_context(|| format!("Unable to import LDAP user {}!", entity.username))?;
+        info!("Imported LDAP user {}", entity.username);
+    }
+
+    Ok(())
+}
+
+/// Validates an entry to import like the input of [`add_ldap_user`].
+///
+/// The group is determined by the gidNumber of the entry.
+/// Empty values are treated as missing since they are placeholders for the mail and the SSH public key.
+///
+/// # Errors
+///
+/// - If the entry has no valid username, first name or last name.
+/// - If the gidNumber of the entry is not the one of a group within the configuration.
+/// - If the QOS, the SSH public keys or the extra attributes are not valid.
+fn new_entity_of_entry(entry: &LdifEntry, config: &MgmtConfig) -> AppResult<NewEntity> {
+    let attributes = &config.ldap_attributes;
+    let text = |name: &str| {
+        entry
+            .first_value(name)
+            .filter(|value| !value.is_empty())
+            .map(TrimmedNonEmptyText::try_from)
+            .transpose()
+            .with_context(|| format!("Invalid {}", name))
+    };
+
+    let username =
+        text(&attributes.username)?.ok_or_else(|| anyhow!("No {} given", attributes.username))?;
+    let gid_number = entry
+        .first_value(&attributes.gid_number)
+        .ok_or_else(|| anyhow!("No {} given", attributes.gid_number))?;
+    let group = [Group::Staff, Group::Student, Group::Faculty]
+        .into_iter()
+        .find(|group| ResolvedGid::new(*group, config).gid().to_string() == gid_number)
+        .ok_or_else(|| {
+            anyhow!(
+                "{} {} is not the one of any group",
+                attributes.gid_number,
+                gid_number
+            )
+        })?;
+    let extra_attributes = config
+        .ldap_extra_attributes
+        .iter()
+        .flat_map(|name| {
+            entry.values(name).iter().map(move |value| {
+                Ok(ExtraLdapAttribute::new(
+                    name.as_str().try_into()?,
+                    value.as_str().try_into()?,
+                ))
+            })
+        })
+        .collect::<AppResult<_>>()
+        .context("Invalid extra attribute")?;
+    let to_add = CommonUserFields {
+        username,
+        group: Some(group.to_string().as_str().try_into()?),
+        mail: text(&attributes.mail)?,
+        default_qos: text(&attributes.default_qos)?,
+        publickey: None,
+        qos: entry.values(&attributes.qos).to_vec(),
+        extra_attributes,
+    };
+    let (firstname, lastname) = (text(&attributes.firstname)?, text(&attributes.lastname)?);
+    let mut entity = Entity::new_inner(firstname, lastname, to_add, config, |_| {
+        unreachable!("No path to a public key is given")
+    })?;
+
+    let publickeys: Vec<&str> = entry
+        .values(&attributes.publickey)
+        .iter()
+        .map(String::as_str)
+        .filter(|key| !key.is_empty())
+        .collect();
+    if !publickeys.is_empty() {
+        entity.publickey = Some(
+            SshPublicKey::parse_many(&publickeys.join("\n")).context("Invalid SSH public key")?,
+        );
+    }
+
+    NewEntity::new(entity, config)
+}
+
+/// Returns a description for every conflict of the entries to import.
+///
+/// An entry conflicts if it has no username or uidNumber, if its uidNumber is no number
+/// or if its username or uidNumber is already taken by an existing user or another entry.
+/// It conflicts as well if it lacks an object class of `objectclass_common` or if it has an attribute
+/// which is not exported by [`export_ldap_entries`].
+/// Attribute names and object classes are compared case-insensitively like in LDAP.
+fn find_import_conflicts(
+    to_import: &[LdifEntry],
+    existing: &[LdifEntry],
+    config: &MgmtConfig,
+) -> Vec<String> {
+    let attributes = &config.ldap_attributes;
+    let mut allowed = attributes.all();
+    allowed.push(OBJECT_CLASS);
+    allowed.extend(config.ldap_extra_attributes.iter().map(String::as_str));
+    // LDAP compares usernames case-insensitively.
+    let mut taken_usernames: HashSet<String> = existing
+        .iter()
+        .filter_map(|entry| entry.first_value(&attributes.username))
+        .map(str::to_lowercase)
+        .collect();
+    let mut taken_uid_numbers: HashSet<&str> = existing
+        .iter()
+        .filter_map(|entry| entry.first_value(&attributes.uid_number))
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for entry in to_import {
+        for name in entry.attributes.keys() {
+            if !allowed
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(name))
+            {
+                conflicts.push(format!(
+                    "Entry {} has the unknown attribute {}",
+                    entry.dn, name
+                ));
+            }
+        }
+        let object_classes = entry.values(OBJECT_CLASS);
+        for required in &config.objectclass_common {
+            if !object_classes
+                .iter()
+                .any(|object_class| object_class.eq_ignore_ascii_case(required))
+            {
+                conflicts.push(format!(
+                    "Entry {} lacks the object class {}",
+                    entry.dn, required
+                ));
+            }
+        }
+        let (username, uid_number) = match (
+            entry.first_value(&attributes.username),
+            entry.first_value(&attributes.uid_number),
+        ) {
+            (Some(username), Some(uid_number)) => (username, uid_number),
+            _ => {
+                conflicts.push(format!(
+                    "Entry {} has no {} or no {}",
+                    entry.dn, attributes.username, attributes.uid_number
+                ));
+                continue;
+            }
+        };
+        if uid_number.parse::<u32>().is_err() {
+            conflicts.push(format!(
+                "{} {} of user {} is not a number",
+                attributes.uid_number, uid_number, username
+            ));
+        }
+        if !taken_usernames.insert(username.to_lowercase()) {
+            conflicts.push(format!("User {} already exists", username));
+        }
+        if !taken_uid_numbers.insert(uid_number) {
+            conflicts.push(format!(
+                "{} {} of user {} is already taken",
+                attributes.uid_number, uid_number, username
+            ));
+        }
+    }
+    conflicts
+}
 
-    Ok(search_result)
+/// Groups the values of extra attributes by their names.
+/// This way an attribute given multiple times ends up as one multi-valued attribute.
+fn extra_attribute_fields(extra_attributes: &[ExtraLdapAttribute]) -> Vec<(&str, HashSet<&str>)> {
+    let mut fields: Vec<(&str, HashSet<&str>)> = Vec::new();
+    for next in extra_attributes {
+        match fields.iter_mut().find(|(name, _)| *name == next.name()) {
+            Some((_, values)) => {
+                values.insert(next.value());
+            }
+            None => fields.push((next.name(), hashset! {next.value()})),
+        }
+    }
+    fields
+}
+
+/// Attributes `modified_at` and `modified_by` with the time and the bind DN of a change to a user.
+fn modification_fields<'a>(
+    attributes: &'a LdapAttributes,
+    modified_at: &'a str,
+    bind: &'a str,
+) -> [(&'a str, HashSet<&'a str>); 2] {
+    [
+        (attributes.modified_at.as_str(), hashset! {modified_at}),
+        (attributes.modified_by.as_str(), hashset! {bind}),
+    ]
 }
 
 /// Creates modification parameters which are used by `ldap3` library to modify an user in LDAP.
 fn make_modification_vec<'a>(
     modifiable: &'a ChangesToUser,
     old_qos: &'a Vec<String>,
+    new_pubkeys: &'a Option<Vec<String>>,
+    attributes: &'a LdapAttributes,
 ) -> Vec<Mod<&'a str>> {
     macro_rules! may_push_simple_modification {
         ($name:expr, $modifiable:ident, $modification:ident, $field:ident) => {
@@ -294,26 +1006,46 @@ fn make_modification_vec<'a>(
     let mut modifications: Vec<Mod<&str>> = Vec::new();
 
     let modifiable = modifiable.as_ref();
-    may_push_simple_modification!("givenName", modifiable, modifications, firstname);
-    may_push_simple_modification!("sn", modifiable, modifications, lastname);
-    may_push_simple_modification!("mail", modifiable, modifications, mail);
-    may_push_simple_modification!("slurmDefaultQos", modifiable, modifications, default_qos);
-    may_push_simple_modification!("publickey", modifiable, modifications, publickey);
+    may_push_simple_modification!(&attributes.firstname, modifiable, modifications, firstname);
+    may_push_simple_modification!(&attributes.lastname, modifiable, modifications, lastname);
+    may_push_simple_modification!(&attributes.mail, modifiable, modifications, mail);
+    may_push_simple_modification!(
+        &attributes.default_qos,
+        modifiable,
+        modifications,
+        default_qos
+    );
+    if let Some(pubkeys) = new_pubkeys {
+        info_log(&attributes.publickey);
+        let pubkeys: HashSet<&str> = pubkeys.iter().map(String::as_str).collect();
+        modifications.push(Mod::Replace(&attributes.publickey, pubkeys));
+    }
 
     let replace_old_with_new_qos = !old_qos.is_empty();
     if replace_old_with_new_qos {
         // first we delete all old qos
-        const SLURM_QOS: &str = "slurmQos";
-        info_log(SLURM_QOS);
+        let slurm_qos = attributes.qos.as_str();
+        info_log(slurm_qos);
         for q in old_qos {
-            modifications.push(Mod::Delete(SLURM_QOS, HashSet::from([q.as_str()])))
+            modifications.push(Mod::Delete(slurm_qos, HashSet::from([q.as_str()])))
         }
         // then we add all new qos
         for q in modifiable.qos.iter() {
             let q: HashSet<&str> = q.into_iter().map(|qos| qos.as_ref().as_str()).collect();
-            modifications.push(Mod::Add(SLURM_QOS, q))
+            modifications.push(Mod::Add(slurm_qos, q))
         }
     }
+
+    for (name, values) in extra_attribute_fields(&modifiable.extra_attributes) {
+        info_log(name);
+        modifications.push(Mod::Replace(name, values));
+    }
+    // A replace without values removes the attribute and does not fail if it is absent.
+    for name in modifiable.unset_extra_attributes.iter() {
+        let name = name.as_ref().as_str();
+        info_log(name);
+        modifications.push(Mod::Replace(name, HashSet::new()));
+    }
     return modifications;
 
     fn info_log(field: &str) {
@@ -342,32 +1074,30 @@ where
             config.bind(),
         );
 
-        debug!("Search under {}", config.base());
+        debug!("Search under {}", config.search_base());
     }
 
     // Search for all uidNumbers under base dn
     let search_result = ldap_session.action(|connection, config| {
-        connection
-            .search(
-                config.base(),
-                Scope::OneLevel,
-                "(objectclass=*)",
-                vec!["uidNumber"],
-            )
+        let attribute = config.attributes().uid_number.as_str();
+        search_all_pages(connection, config, &LdapFilter::any(), vec![attribute])
             .context("Error during uid search!")
     })?;
+    let attribute = ldap_session.config().attributes().uid_number.as_str();
     let mut uids: Vec<u32> = Vec::new();
-    for elem in search_result.0.iter() {
-        let search_result = SearchEntry::construct(elem.to_owned());
-        debug!("UID: {:?}", SearchEntry::construct(elem.to_owned()));
+    for search_result in search_result.iter() {
+        debug!("UID: {:?}", search_result);
         let uid = {
-            const ATTRIBUTE: &str = "uidNumber";
-            let unparsed = &search_result.attrs[ATTRIBUTE].first().ok_or_else(|| {
-                anyhow!(
-                    "No uid under the attribute `{}` in the LDPA search ",
-                    ATTRIBUTE
-                )
-            })?;
+            let unparsed = search_result
+                .attrs
+                .get(attribute)
+                .and_then(|values| values.first())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No uid under the attribute `{}` in the LDPA search ",
+                        attribute
+                    )
+                })?;
             unparsed.parse::<u32>().with_context(|| format!("Uid `{}` for ldap operation could not be parsed into unsigned integer 32 value", unparsed))?
         };
 
@@ -383,6 +1113,7 @@ where
 ///
 /// - If the connection to a LDAP instance can not be established
 /// - If nothing is found in the LDAP query under the given user aka parameter `username`
+/// - If more than one entry is found under the given user aka parameter `username`
 pub fn find_dn_by_uid<T>(
     username: &str,
     ldap_session: &mut LdapSession<T>,
@@ -398,19 +1129,26 @@ where
     // Search for all uids under base dn and return dn of user
     let search: SearchResult = ldap_session.action(|con, config| {
         con.search(
-            config.base(),
-            Scope::OneLevel,
-            &format!("(uid={username})"),
+            config.search_base(),
+            config.search_scope(),
+            &LdapFilter::by_username(config.attributes(), username).to_string(),
             vec!["dn"],
         )
         .context("LDAP search failed")
     })?;
 
-    let entry = search
-        .0
-        .into_iter()
+    let mut entries = search.0.into_iter();
+    let entry = entries
         .next()
         .with_context(|| format!("No LDAP entry found for user {}", username))?;
+    let further_matches = entries.count();
+    if further_matches > 0 {
+        bail!(
+            "Found {} LDAP entries for user {} instead of exactly one. Refusing to proceed.",
+            further_matches + 1,
+            username
+        );
+    }
 
     let sr = SearchEntry::construct(entry);
     debug!("Search result for deletion: {:?}", sr);
@@ -430,40 +1168,252 @@ pub fn find_qos_by_uid<T>(
 where
     T: LdapCredential,
 {
-    let mut fetched_all_qos: Vec<String> = Vec::new();
+    let attribute = ldap_session.config().attributes().qos.clone();
+    let fetched_all_qos = find_values_by_uid(username, &attribute, ldap_session)?;
+    debug!("Fetched QOS: {:?}", fetched_all_qos);
+    Ok(fetched_all_qos)
+}
+
+/// Search for a specific uid and return the corresponding SSH public keys as stored in LDAP.
+/// Empty values are left out.
+///
+/// # Errors
+///
+/// - If the connection to the LDAP instance fails
+pub fn find_publickeys_by_uid<T>(
+    username: &str,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult<Vec<String>>
+where
+    T: LdapCredential,
+{
+    let attribute = ldap_session.config().attributes().publickey.clone();
+    let keys = find_values_by_uid(username, &attribute, ldap_session)?
+        .into_iter()
+        .filter(|key| !key.trim().is_empty())
+        .collect();
+    Ok(keys)
+}
+
+/// Adds SSH public keys to the ones of an user.
+///
+/// # Errors
+///
+/// - If finding the LDAP-DN by the UID fails. See [`find_dn_by_uid`]
+/// - If one of the given keys is already stored for the user.
+/// - If the modification of the user in the LDAP database failed.
+pub fn add_ldap_publickeys<T>(
+    username: &str,
+    keys: &[SshPublicKey],
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    let dn = find_dn_by_uid(username, ldap_session)?
+        .ok_or_else(|| anyhow!("No dn found for uid {}", username))?;
+    let attribute = ldap_session.config().attributes().publickey.clone();
+    let stored = find_values_by_uid(username, &attribute, ldap_session)?;
+
+    let mut known_keys: Vec<SshPublicKey> =
+        stored.iter().filter_map(|key| key.parse().ok()).collect();
+    for key in keys {
+        ensure_not_duplicate(key, &known_keys)
+            .with_context(|| format!("Can not add SSH public key to user {}", username))?;
+        known_keys.push(key.clone());
+    }
+
+    let new_keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+    let mut mod_vec = vec![Mod::Add(
+        attribute.as_str(),
+        new_keys.iter().map(String::as_str).collect(),
+    )];
+    // Users added without a key have an empty value as placeholder
+    let placeholders: HashSet<&str> = stored
+        .iter()
+        .map(String::as_str)
+        .filter(|key| key.trim().is_empty())
+        .collect();
+    if !placeholders.is_empty() {
+        mod_vec.push(Mod::Delete(attribute.as_str(), placeholders));
+    }
+
+    ldap_session.action(|ldap_connection, _| {
+        let result = ldap_connection.modify(&dn, mod_vec.clone());
+        ldap_is_success(result).context("Adding SSH public keys in LDAP failed!")
+    })?;
+
+    info!(
+        "Successfully added {} SSH public key(s) to user {} in LDAP",
+        keys.len(),
+        username
+    );
+    Ok(())
+}
+
+/// Removes the SSH public key with the given SHA256 fingerprint from an user.
+///
+/// # Errors
+///
+/// - If finding the LDAP-DN by the UID fails. See [`find_dn_by_uid`]
+/// - If no key with the given fingerprint is stored for the user.
+/// - If the modification of the user in the LDAP database failed.
+pub fn remove_ldap_publickey<T>(
+    username: &str,
+    fingerprint: &str,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    let dn = find_dn_by_uid(username, ldap_session)?
+        .ok_or_else(|| anyhow!("No dn found for uid {}", username))?;
+    let attribute = ldap_session.config().attributes().publickey.clone();
+    let stored = find_publickeys_by_uid(username, ldap_session)?;
+
+    let to_remove: HashSet<&str> = stored
+        .iter()
+        .filter(|key| {
+            key.parse::<SshPublicKey>()
+                .map(|key| key.fingerprint() == fingerprint)
+                .unwrap_or(false)
+        })
+        .map(String::as_str)
+        .collect();
+    if to_remove.is_empty() {
+        bail!(
+            "User {} has no SSH public key with the fingerprint {}",
+            username,
+            fingerprint
+        );
+    }
+
+    ldap_session.action(|ldap_connection, _| {
+        let result = ldap_connection.modify(
+            &dn,
+            vec![Mod::Delete(attribute.as_str(), to_remove.clone())],
+        );
+        ldap_is_success(result).context("Removing SSH public key in LDAP failed!")
+    })?;
+
+    info!(
+        "Successfully removed SSH public key {} from user {} in LDAP",
+        fingerprint, username
+    );
+    Ok(())
+}
 
+/// Returns all values of the attribute `attribute` of the user with the name `username`.
+fn find_values_by_uid<T>(
+    username: &str,
+    attribute: &str,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult<Vec<String>>
+where
+    T: LdapCredential,
+{
     debug!(
         "LDAP connection established to {}",
         ldap_session.config().bind()
     );
 
-    // Search for all uid under base dn and return dn of user
     let search = ldap_session.action(|ldap_connection, ldap_config| {
         ldap_connection
             .search(
-                ldap_config.base(),
-                Scope::OneLevel,
-                &format!("(uid={})", username),
-                vec!["slurmQos"],
+                ldap_config.search_base(),
+                ldap_config.search_scope(),
+                &LdapFilter::by_username(ldap_config.attributes(), username).to_string(),
+                vec![attribute],
             )
             .with_context(|| {
                 format!(
-                    "search did not find any slurmQos for the user with uid {}",
-                    username
+                    "search did not find any {} for the user with uid {}",
+                    attribute, username
                 )
             })
     })?;
 
-    for elem in search.0.iter() {
-        let search_result = SearchEntry::construct(elem.to_owned());
-        let q = &search_result.attrs["slurmQos"];
-        for one_qos in q {
-            debug!("Fetched QOS: {:?}", one_qos);
-            fetched_all_qos.push(one_qos.clone());
-        }
-    }
+    let values = search
+        .0
+        .into_iter()
+        .map(SearchEntry::construct)
+        .flat_map(|mut entry| entry.attrs.remove(attribute).unwrap_or_default())
+        .collect();
+    Ok(values)
+}
 
-    Ok(fetched_all_qos)
+/// Returns the object classes and attributes used with parameter `config`
+/// which are not known to the LDAP server.
+///
+/// # Errors
+///
+/// - If the schema could not be read from the server. See [`fetch_ldap_schema`]
+pub fn check_ldap_schema<T>(
+    config: &MgmtConfig,
+    ldap_session: &mut LdapSession<T>,
+) -> AppResult<MissingSchemaItems>
+where
+    T: LdapCredential,
+{
+    let schema = fetch_ldap_schema(ldap_session)?;
+    Ok(schema.missing(config))
+}
+
+/// Reads all object classes and attribute types from the subschema entry of the LDAP server.
+/// The DN of the subschema entry is taken from the attribute subschemaSubentry of the root DSE.
+///
+/// # Errors
+///
+/// - If the root DSE or the subschema entry could not be read.
+/// - If the root DSE has no subschemaSubentry.
+pub fn fetch_ldap_schema<T>(ldap_session: &mut LdapSession<T>) -> AppResult<LdapSchema>
+where
+    T: LdapCredential,
+{
+    const SUBSCHEMA_SUBENTRY: &str = "subschemaSubentry";
+    const OBJECT_CLASSES: &str = "objectClasses";
+    const ATTRIBUTE_TYPES: &str = "attributeTypes";
+
+    let mut subschema = ldap_session.action(|connection, _| {
+        let (root_dse, _) = connection
+            .search(
+                "",
+                Scope::Base,
+                &LdapFilter::any().to_string(),
+                vec![SUBSCHEMA_SUBENTRY],
+            )?
+            .success()
+            .context("Unable to read the root DSE of the LDAP server")?;
+        let subschema_dn = root_dse
+            .into_iter()
+            .next()
+            .map(SearchEntry::construct)
+            .and_then(|entry| entry.attrs.get(SUBSCHEMA_SUBENTRY)?.first().cloned())
+            .ok_or_else(|| anyhow!("Root DSE of the LDAP server has no {}", SUBSCHEMA_SUBENTRY))?;
+        debug!("Reading LDAP schema from {}", subschema_dn);
+
+        let (entries, _) = connection
+            .search(
+                &subschema_dn,
+                Scope::Base,
+                &LdapFilter::equality(OBJECT_CLASS, "subschema").to_string(),
+                vec![OBJECT_CLASSES, ATTRIBUTE_TYPES],
+            )?
+            .success()
+            .with_context(|| format!("Unable to read the LDAP schema at {}", subschema_dn))?;
+        entries
+            .into_iter()
+            .next()
+            .map(SearchEntry::construct)
+            .ok_or_else(|| anyhow!("No LDAP schema found at {}", subschema_dn))
+    })?;
+
+    let object_classes = subschema.attrs.remove(OBJECT_CLASSES).unwrap_or_default();
+    let attribute_types = subschema.attrs.remove(ATTRIBUTE_TYPES).unwrap_or_default();
+    Ok(LdapSchema::from_definitions(
+        &object_classes,
+        &attribute_types,
+    ))
 }
 
 /// Check if username already exists in ldap.
@@ -471,23 +1421,24 @@ where
 ///
 /// # Errors
 ///
-/// - If the connection to the LDAP instance fails
+/// - If the connection to the LDAP instance fails. See [`LdapSession::action`]
 /// - If nothing is found in the LDAP query under the given user aka parameter `username`
-pub fn username_exists<T>(username: &String, ldap_config: &LDAPConfig<T>) -> AppResult<bool>
+pub fn username_exists<T>(username: &String, ldap_session: &mut LdapSession<T>) -> AppResult<bool>
 where
     T: LdapCredential,
 {
     let mut username_exists = false;
-    let mut ldap = make_ldap_connection(ldap_config)?;
-    debug!("LDAP connection established to {}", ldap_config.bind());
 
     // Search for all uid under base dn and return dn of user
-    let search_result = ldap.search(
-        ldap_config.base(),
-        Scope::OneLevel,
-        &format!("(uid={username})"),
-        vec!["dn"],
-    )?;
+    let search_result = ldap_session.action(|ldap, ldap_config| {
+        let result = ldap.search(
+            ldap_config.search_base(),
+            ldap_config.search_scope(),
+            &LdapFilter::by_username(ldap_config.attributes(), username).to_string(),
+            vec!["dn"],
+        )?;
+        Ok(result)
+    })?;
     match search_result.0.into_iter().next() {
         Some(entry) => {
             // User found. Good.
@@ -499,6 +1450,77 @@ where
     Ok(username_exists)
 }
 
+/// Searches for all entries matching `filter` one level under the base of `ldap_config`.
+///
+/// The entries are fetched page by page via the Simple Paged Results control (RFC 2696).
+/// This way the size limit of the LDAP server does not silently truncate the result.
+/// The size of a page is determined by [`LDAPConfig::page_size`].
+///
+/// # Errors
+///
+/// - If the search itself fails.
+/// - If the server still truncated the result because of a size or administrative limit.
+fn search_all_pages<T>(
+    connection: &mut LdapConn,
+    ldap_config: &LDAPConfig<T>,
+    filter: &LdapFilter,
+    attrs: Vec<&str>,
+) -> AppResult<Vec<SearchEntry>>
+where
+    T: LdapCredential,
+{
+    const SIZE_LIMIT_EXCEEDED: u32 = 4;
+    const ADMIN_LIMIT_EXCEEDED: u32 = 11;
+
+    let page_size = i32::try_from(ldap_config.page_size())
+        .ok()
+        .filter(|&page_size| page_size > 0)
+        .ok_or_else(|| {
+            anyhow!(
+                "LDAP page size ({}) must be greater than zero and at most {}",
+                ldap_config.page_size(),
+                i32::MAX
+            )
+        })?;
+    debug!(
+        "Paged LDAP search under {} with filter {} and page size {}",
+        ldap_config.search_base(),
+        filter,
+        page_size
+    );
+
+    let mut stream = connection.streaming_search_with(
+        PagedResults::new(page_size),
+        ldap_config.search_base(),
+        ldap_config.search_scope(),
+        &filter.to_string(),
+        attrs,
+    )?;
+
+    let mut entries = Vec::new();
+    while let Some(entry) = stream.next()? {
+        if entry.is_ref() || entry.is_intermediate() {
+            continue;
+        }
+        entries.push(SearchEntry::construct(entry));
+    }
+
+    let result = stream.result();
+    match result.rc {
+        SIZE_LIMIT_EXCEEDED | ADMIN_LIMIT_EXCEEDED => bail!(
+            "LDAP search result was truncated by the server after {} entries ({}). \
+            Lower the field ldap_page_size in the configuration below the size limit of the server.",
+            entries.len(),
+            result
+        ),
+        _ => {
+            result.success()?;
+        }
+    }
+
+    Ok(entries)
+}
+
 /// If ok is returned then ldap operation happened with zero error code, LDAP_SUCCESS
 ///
 /// Even if a call to ldap returns ok it has an error code inside it. Only if the code is zero
@@ -535,20 +1557,3 @@ where
 
     Ok((ldap_user.trim().to_owned(), ldap_pass.trim().to_owned()))
 }
-
-static SORTED_LDAP_LISTING_ATTRIBUTES: Lazy<Vec<&str>> = Lazy::new(|| {
-    // Make sure the keys are sorted alphabetic
-    // This way the order fields in the final output deterministic
-    let mut to_sort = vec![
-        "uid",
-        "uidNumber",
-        "givenName",
-        "sn",
-        "mail",
-        "slurmDefaultQos",
-        "slurmQos",
-        "createdAt",
-    ];
-    to_sort.sort();
-    to_sort
-});
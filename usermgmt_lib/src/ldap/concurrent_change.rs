@@ -0,0 +1,182 @@
+//! Detection of concurrent changes to an LDAP entry between reading and modifying it.
+//!
+//! A modification is sent with the assertion control (RFC 4528) whose filter describes
+//! the entry as it was read. The server applies the modification only if the entry still matches.
+
+use ldap3::{controls::Assertion, controls::RawControl, LdapError, Mod};
+
+use crate::config::LdapAttributes;
+
+use super::LdapFilter;
+
+/// Operational attribute with the time of the last modification of an entry (RFC 4512, 3.4.4)
+pub const MODIFY_TIMESTAMP: &str = "modifyTimestamp";
+
+const RESULT_CODE_UNAVAILABLE_CRITICAL_EXTENSION: u32 = 12;
+const RESULT_CODE_NO_SUCH_ATTRIBUTE: u32 = 16;
+const RESULT_CODE_ASSERTION_FAILED: u32 = 122;
+
+/// Returns the filter which an entry must still match to be unchanged since it was read.
+/// Returns none if nothing was read to compare with.
+///
+/// - Parameter `old_qos`: values of the QOS which are replaced by the modification.
+/// - Parameter `modify_timestamp`: value of [`MODIFY_TIMESTAMP`] when the entry was read.
+pub fn unchanged_since_read(
+    attributes: &LdapAttributes,
+    old_qos: &[String],
+    modify_timestamp: Option<&str>,
+) -> Option<LdapFilter> {
+    let mut conditions: Vec<LdapFilter> = old_qos
+        .iter()
+        .map(|qos| LdapFilter::equality(attributes.qos.as_str(), qos.as_str()))
+        .collect();
+    if let Some(timestamp) = modify_timestamp {
+        conditions.push(LdapFilter::equality(MODIFY_TIMESTAMP, timestamp));
+    }
+    (!conditions.is_empty()).then(|| LdapFilter::and(conditions))
+}
+
+/// Returns the filter which an entry matches once parameter `modifications` are applied to it.
+/// Returns none if the modifications change nothing which could be checked.
+///
+/// Used to tell if a modification, which was sent again after the connection was lost,
+/// was already applied by the first attempt.
+/// Values which are deleted and added again by the same modification must be present afterwards.
+pub fn applied_modifications(modifications: &[Mod<&str>]) -> Option<LdapFilter> {
+    let mut conditions = Vec::new();
+    for (index, modification) in modifications.iter().enumerate() {
+        match modification {
+            Mod::Add(attribute, values) => conditions.extend(
+                values
+                    .iter()
+                    .map(|value| LdapFilter::equality(*attribute, *value)),
+            ),
+            Mod::Replace(attribute, values) if values.is_empty() => {
+                conditions.push(LdapFilter::not(LdapFilter::present(*attribute)))
+            }
+            Mod::Replace(attribute, values) => conditions.extend(
+                values
+                    .iter()
+                    .map(|value| LdapFilter::equality(*attribute, *value)),
+            ),
+            Mod::Delete(attribute, values) => {
+                let is_set_later = |value: &str| {
+                    modifications[index + 1..].iter().any(|later| match later {
+                        Mod::Add(other, values) | Mod::Replace(other, values) => {
+                            other == attribute && values.contains(value)
+                        }
+                        _ => false,
+                    })
+                };
+                conditions.extend(
+                    values
+                        .iter()
+                        .filter(|value| !is_set_later(value))
+                        .map(|value| LdapFilter::not(LdapFilter::equality(*attribute, *value))),
+                )
+            }
+            Mod::Increment(..) => {}
+        }
+    }
+    (!conditions.is_empty()).then(|| LdapFilter::and(conditions))
+}
+
+/// Critical assertion control with parameter `filter`.
+/// Critical, so that a server without support for it rejects the operation instead of ignoring the control.
+pub fn assertion_control(filter: &LdapFilter) -> RawControl {
+    let mut control = Assertion::new(filter.to_string());
+    control.crit = true;
+    control
+}
+
+/// Returns true if parameter `error` shows that the entry was changed since it was read.
+///
+/// The assertion fails if the entry does not match anymore.
+/// Without the assertion control, the deletion of an old value fails if it was already removed.
+/// Parameter `deletes_old_values` tells if the modification deleted values read before.
+pub fn is_concurrent_change(error: &LdapError, deletes_old_values: bool) -> bool {
+    match error {
+        LdapError::LdapResult { result } => {
+            result.rc == RESULT_CODE_ASSERTION_FAILED
+                || (deletes_old_values && result.rc == RESULT_CODE_NO_SUCH_ATTRIBUTE)
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if the server rejected an operation because it does not support a critical control.
+pub fn is_unsupported_control(error: &LdapError) -> bool {
+    matches!(
+        error,
+        LdapError::LdapResult { result } if result.rc == RESULT_CODE_UNAVAILABLE_CRITICAL_EXTENSION
+    )
+}
+
+#[cfg(test)]
+mod testing {
+    use std::collections::HashSet;
+
+    use ldap3::LdapResult;
+
+    use super::*;
+
+    fn ldap_result(rc: u32) -> LdapError {
+        let result = LdapResult {
+            rc,
+            matched: String::new(),
+            text: String::new(),
+            refs: Vec::new(),
+            ctrls: Vec::new(),
+        };
+        LdapError::LdapResult { result }
+    }
+
+    #[test]
+    fn filter_of_read_qos_and_timestamp() {
+        let attributes = LdapAttributes::default();
+        let old_qos = ["basic".to_string(), "interactive".to_string()];
+        assert_eq!(
+            "(&(slurmQos=basic)(slurmQos=interactive)(modifyTimestamp=20240509104934Z))",
+            unchanged_since_read(&attributes, &old_qos, Some("20240509104934Z"))
+                .expect("Should have a filter")
+                .to_string()
+        );
+        assert_eq!(
+            "(&(modifyTimestamp=20240509104934Z))",
+            unchanged_since_read(&attributes, &[], Some("20240509104934Z"))
+                .expect("Should have a filter")
+                .to_string()
+        );
+        assert!(unchanged_since_read(&attributes, &[], None).is_none());
+    }
+
+    #[test]
+    fn filter_of_applied_modifications() {
+        let modifications = vec![
+            Mod::Replace("mail", HashSet::from(["alice@example.com"])),
+            Mod::Delete("slurmQos", HashSet::from(["basic"])),
+            Mod::Delete("slurmQos", HashSet::from(["interactive"])),
+            Mod::Add("slurmQos", HashSet::from(["interactive"])),
+            Mod::Replace("telephoneNumber", HashSet::new()),
+        ];
+        assert_eq!(
+            "(&(mail=alice@example.com)(!(slurmQos=basic))(slurmQos=interactive)(!(telephoneNumber=*)))",
+            applied_modifications(&modifications)
+                .expect("Should have a filter")
+                .to_string()
+        );
+        assert!(applied_modifications(&[]).is_none());
+    }
+
+    #[test]
+    fn detect_concurrent_change() {
+        assert!(is_concurrent_change(&ldap_result(122), false));
+        assert!(is_concurrent_change(&ldap_result(16), true));
+        assert!(!is_concurrent_change(&ldap_result(16), false));
+        assert!(!is_concurrent_change(&ldap_result(32), true));
+        assert!(!is_concurrent_change(&LdapError::EndOfStream, true));
+
+        assert!(is_unsupported_control(&ldap_result(12)));
+        assert!(!is_unsupported_control(&ldap_result(122)));
+    }
+}
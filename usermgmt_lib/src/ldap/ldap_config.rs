@@ -1,14 +1,21 @@
+use std::time::Duration;
+
+use ldap3::Scope;
 use log::info;
 
-use crate::{prelude::AppResult, MgmtConfig};
+use crate::{config::LdapAttributes, prelude::AppResult, MgmtConfig};
 
 use super::{ldap_paths::LdapPaths, LdapCredential};
 #[derive(Debug, Default)]
 /// Contains all information for creating/deleting and modifying an user aka writing actions
 pub struct LDAPConfig<T> {
-    ldap_server: String,
+    ldap_servers: Vec<String>,
+    connect_timeout: Duration,
     ldap_credentials: T,
     ldap_paths: LdapPaths,
+    page_size: u32,
+    attributes: LdapAttributes,
+    track_modifications: bool,
 }
 
 impl<T> LDAPConfig<T>
@@ -16,7 +23,6 @@ where
     T: LdapCredential,
 {
     pub fn new_readonly(config: &MgmtConfig, mut credentials: T) -> AppResult<Self> {
-        let ldap_server = config.ldap_server.clone();
         let (ldap_user, ldap_pass) = super::ask_credentials_if_not_provided(
             config.ldap_readonly_user.as_deref(),
             config.ldap_readonly_pw.as_deref(),
@@ -42,19 +48,23 @@ where
             bind,
             prefix,
             ldap_user,
-        );
+        )
+        .with_group_org_units(config.ldap_group_org_units());
 
         Ok(Self {
             ldap_paths,
             ldap_credentials: credentials,
-            ldap_server,
+            ldap_servers: config.ldap_server.clone(),
+            connect_timeout: connect_timeout(config),
+            page_size: config.ldap_page_size,
+            attributes: config.ldap_attributes.clone(),
+            track_modifications: config.ldap_track_modifications,
         })
     }
 
     pub fn new(config: &MgmtConfig, credentials: T) -> AppResult<Self> {
-        let (bind_prefix, ldap_server, dc, org_unit, bind_org_unit) = (
+        let (bind_prefix, dc, org_unit, bind_org_unit) = (
             &config.ldap_bind_prefix,
-            &config.ldap_server,
             &config.ldap_domain_components,
             &config.ldap_org_unit,
             &config.ldap_bind_org_unit,
@@ -69,12 +79,20 @@ where
             bind_org_unit.clone(),
             bind_prefix.clone(),
             ldap_user.to_string(),
-        );
+        )
+        .with_group_org_units(config.ldap_group_org_units());
 
         Ok(Self {
-            ldap_server: ldap_server.to_string(),
+            ldap_servers: config
+                .writable_ldap_server()
+                .map(|server| vec![server.to_string()])
+                .unwrap_or_default(),
+            connect_timeout: connect_timeout(config),
             ldap_credentials: credentials,
             ldap_paths,
+            page_size: config.ldap_page_size,
+            attributes: config.ldap_attributes.clone(),
+            track_modifications: config.ldap_track_modifications,
         })
     }
 
@@ -84,6 +102,23 @@ where
     pub fn base(&self) -> &str {
         self.ldap_paths.base()
     }
+    /// Path in which users with parameter `gid` are added
+    pub fn base_of_gid(&self, gid: i32) -> &str {
+        self.ldap_paths.base_of_gid(gid)
+    }
+    /// Path under which users are searched
+    pub fn search_base(&self) -> &str {
+        self.ldap_paths.search_base()
+    }
+    /// Scope of searches for users under [`Self::search_base`].
+    /// If users of groups are added in different paths, the whole subtree is searched.
+    pub fn search_scope(&self) -> Scope {
+        if self.ldap_paths.has_group_bases() {
+            Scope::Subtree
+        } else {
+            Scope::OneLevel
+        }
+    }
     pub fn username(&self) -> &str {
         self.ldap_paths.username()
     }
@@ -91,11 +126,39 @@ where
         self.ldap_credentials.password()
     }
 
-    pub fn ldap_server(&self) -> &str {
-        &self.ldap_server
+    /// LDAP servers to try in order until a connection succeeds.
+    /// Contains only the writable server for writing actions
+    /// and all configured servers for reading actions.
+    pub fn ldap_servers(&self) -> &[String] {
+        &self.ldap_servers
+    }
+
+    /// Time to wait for a connection to one LDAP server
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+
+    /// Number of entries requested per page for searches over many entries
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+    }
+
+    /// Names of the LDAP attributes under which the fields of an user are stored
+    pub fn attributes(&self) -> &LdapAttributes {
+        &self.attributes
+    }
+
+    /// If true, the time and the bind DN of every addition or modification are written
+    /// into the attributes `modified_at` and `modified_by`.
+    pub fn track_modifications(&self) -> bool {
+        self.track_modifications
     }
 }
 
+fn connect_timeout(config: &MgmtConfig) -> Duration {
+    Duration::from_secs(config.ldap_connect_timeout_secs.into())
+}
+
 #[cfg(test)]
 mod testing {
     use crate::ldap::ldap_simple_credential::LdapSimpleCredential;
@@ -166,4 +229,28 @@ mod testing {
             (ldap_config.username(), ldap_config.password().unwrap())
         );
     }
+
+    #[test]
+    fn writing_uses_writable_server_and_reading_all_servers() {
+        let config = MgmtConfig {
+            ldap_server: vec![
+                "ldap://consumer1:389".to_string(),
+                "ldap://consumer2:389".to_string(),
+            ],
+            ldap_writable_server: Some("ldap://provider:389".to_string()),
+            ldap_readonly_user: Some("User".to_string()),
+            ldap_readonly_pw: Some("Password".to_string()),
+            ..Default::default()
+        };
+        let credentials = LdapSimpleCredential::new(String::from("User"), String::from("Password"));
+
+        let writing = LDAPConfig::new(&config, credentials.clone()).unwrap();
+        assert_eq!(vec!["ldap://provider:389"], writing.ldap_servers());
+
+        let reading = LDAPConfig::new_readonly(&config, credentials).unwrap();
+        assert_eq!(
+            vec!["ldap://consumer1:389", "ldap://consumer2:389"],
+            reading.ldap_servers()
+        );
+    }
 }
@@ -0,0 +1,142 @@
+use std::fmt::{self, Display};
+
+use ldap3::ldap_escape;
+
+use crate::config::LdapAttributes;
+
+/// Search filter for LDAP queries as described in RFC 4515.
+///
+/// Values are escaped when the filter is rendered as text.
+/// This way characters like `*`, `(` or `)` within a value, a username for example,
+/// can not change the meaning of a search.
+///
+/// # Example
+///
+/// ```
+/// use usermgmt_lib::ldap::LdapFilter;
+///
+/// let filter = LdapFilter::and([
+///     LdapFilter::present("objectClass"),
+///     LdapFilter::equality("uid", "*"),
+/// ]);
+/// assert_eq!(r"(&(objectClass=*)(uid=\2a))", filter.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LdapFilter {
+    /// Matches if the attribute has exactly the given value: (attribute=value)
+    Equality { attribute: String, value: String },
+    /// Matches if the attribute has any value: (attribute=*)
+    Present { attribute: String },
+    /// Matches if a value of the attribute contains the given text: (attribute=*text*)
+    Contains { attribute: String, value: String },
+    /// Matches if all inner filters match
+    And(Vec<LdapFilter>),
+    /// Matches if at least one inner filter matches
+    Or(Vec<LdapFilter>),
+    /// Matches if the inner filter does not match
+    Not(Box<LdapFilter>),
+}
+
+impl LdapFilter {
+    pub fn equality(attribute: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::Equality {
+            attribute: attribute.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn present(attribute: impl Into<String>) -> Self {
+        Self::Present {
+            attribute: attribute.into(),
+        }
+    }
+
+    pub fn contains(attribute: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::Contains {
+            attribute: attribute.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn and(filters: impl IntoIterator<Item = LdapFilter>) -> Self {
+        Self::And(filters.into_iter().collect())
+    }
+
+    pub fn or(filters: impl IntoIterator<Item = LdapFilter>) -> Self {
+        Self::Or(filters.into_iter().collect())
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(filter: LdapFilter) -> Self {
+        Self::Not(Box::new(filter))
+    }
+
+    /// Filter which matches every entry. Used for listing all entries under a base.
+    pub fn any() -> Self {
+        Self::present("objectClass")
+    }
+
+    /// Filter which matches an user entry by its username.
+    pub fn by_username(attributes: &LdapAttributes, username: &str) -> Self {
+        Self::equality(attributes.username.as_str(), username)
+    }
+}
+
+impl Display for LdapFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LdapFilter::Equality { attribute, value } => {
+                write!(f, "({}={})", attribute, ldap_escape(value.as_str()))
+            }
+            LdapFilter::Present { attribute } => write!(f, "({}=*)", attribute),
+            LdapFilter::Contains { attribute, value } => {
+                write!(f, "({}=*{}*)", attribute, ldap_escape(value.as_str()))
+            }
+            LdapFilter::And(filters) => write_list(f, '&', filters),
+            LdapFilter::Or(filters) => write_list(f, '|', filters),
+            LdapFilter::Not(filter) => write!(f, "(!{})", filter),
+        }
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, operator: char, filters: &[LdapFilter]) -> fmt::Result {
+    write!(f, "({}", operator)?;
+    for next in filters {
+        write!(f, "{}", next)?;
+    }
+    write!(f, ")")
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn by_uid(username: &str) -> LdapFilter {
+        LdapFilter::by_username(&LdapAttributes::default(), username)
+    }
+
+    #[test]
+    fn escape_special_chars_in_values() {
+        assert_eq!("(uid=alice)", by_uid("alice").to_string());
+        assert_eq!(r"(uid=\2a)", by_uid("*").to_string());
+        assert_eq!(r"(uid=a\29\28uid=\2a)", by_uid("a)(uid=*").to_string());
+        assert_eq!(r"(uid=back\5cslash)", by_uid(r"back\slash").to_string());
+        assert_eq!(r"(uid=nul\00)", by_uid("nul\0").to_string());
+    }
+
+    #[test]
+    fn render_nested_filters() {
+        let actual = LdapFilter::and([
+            LdapFilter::any(),
+            LdapFilter::or([
+                LdapFilter::equality("slurmQos", "basic"),
+                LdapFilter::not(LdapFilter::present("sshPublicKey")),
+                LdapFilter::contains("mail", "*@example"),
+            ]),
+        ]);
+        assert_eq!(
+            r"(&(objectClass=*)(|(slurmQos=basic)(!(sshPublicKey=*))(mail=*\2a@example*)))",
+            actual.to_string()
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::RngCore;
+use sha1::{Digest, Sha1};
+
+const SSHA_SCHEME: &str = "{SSHA}";
+const SALT_LENGTH: usize = 8;
+
+/// Returns a salted SHA-1 hash of parameter `password` in the format expected by the
+/// attribute `userPassword`: `{SSHA}` followed by base64(SHA-1(password + salt) + salt).
+///
+/// A new random salt is used for every call.
+pub fn ssha_password_hash(password: &str) -> String {
+    let mut salt = [0u8; SALT_LENGTH];
+    rand::thread_rng().fill_bytes(&mut salt);
+    ssha_password_hash_with_salt(password, &salt)
+}
+
+fn ssha_password_hash_with_salt(password: &str, salt: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(password.as_bytes());
+    hasher.update(salt);
+    let mut digest_and_salt = hasher.finalize().to_vec();
+    digest_and_salt.extend_from_slice(salt);
+    format!("{}{}", SSHA_SCHEME, STANDARD.encode(digest_and_salt))
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn hash_with_given_salt() {
+        let actual = ssha_password_hash_with_salt("secret", b"saltsalt");
+        assert_eq!("{SSHA}1G904nLkTkGWjKNnQuB/hpWXC/hzYWx0c2FsdA==", actual);
+    }
+
+    #[test]
+    fn different_salt_for_every_hash() {
+        let (first, second) = (ssha_password_hash("secret"), ssha_password_hash("secret"));
+        assert!(first.starts_with(SSHA_SCHEME));
+        assert_ne!(first, second);
+    }
+}
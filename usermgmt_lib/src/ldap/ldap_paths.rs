@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use getset::Getters;
 use log::info;
 const DEFAULT_ORG_UNIT: &str = "";
@@ -17,6 +19,17 @@ pub struct LdapPaths {
     /// and ldap_user is example
     bind: String,
     username: String,
+    /// Paths in which users of a group are added if they differ from `base`, keyed by gid.
+    /// Example: ou=students,ou=people,dc=example,dc=com
+    /// if ldap_dc is dc=example,dc=com and the org unit of the group is ou=students,ou=people
+    #[getset(skip)]
+    group_bases: HashMap<i32, String>,
+    /// Common parent of `base` and all paths of groups. Users are searched under it.
+    /// Example: ou=people,dc=example,dc=com
+    /// for ou=students,ou=people,dc=example,dc=com and ou=staff,ou=people,dc=example,dc=com
+    search_base: String,
+    #[getset(skip)]
+    domain_components: String,
 }
 
 impl LdapPaths {
@@ -47,9 +60,12 @@ impl LdapPaths {
         info!("LDAP DN binding for user login: {}", &ldap_bind);
 
         return Self {
+            search_base: ldap_base.clone(),
             base: ldap_base,
             bind: ldap_bind,
             username,
+            group_bases: HashMap::new(),
+            domain_components: ldap_dc,
         };
 
         fn by_config_or_default(to_resolve: &Option<String>, default_val: &str) -> String {
@@ -58,13 +74,127 @@ impl LdapPaths {
                 None => default_val.to_owned(),
             }
         }
-        fn concat_by_comma_if_both_not_empty(left_part: String, right_part: String) -> String {
-            match (left_part.is_empty(), right_part.is_empty()) {
-                (true, true) => String::new(),
-                (true, false) => right_part,
-                (false, true) => left_part,
-                (false, false) => format!("{},{}", left_part, right_part),
+    }
+
+    /// Adds the org units in which users of a group with the given gid are added
+    /// instead of the base. The search base becomes the common parent of all these paths.
+    pub fn with_group_org_units(
+        mut self,
+        org_units: impl IntoIterator<Item = (i32, String)>,
+    ) -> Self {
+        for (gid, org_unit) in org_units {
+            let group_base =
+                concat_by_comma_if_both_not_empty(org_unit, self.domain_components.clone());
+            if group_base != self.base {
+                info!("LDAP DN for users with gid {}: {}", gid, &group_base);
+                self.group_bases.insert(gid, group_base);
             }
         }
+        self.search_base = common_parent(
+            std::iter::once(self.base.as_str())
+                .chain(self.group_bases.values().map(String::as_str)),
+        );
+        if self.has_group_bases() {
+            info!(
+                "LDAP DN under which users are searched: {}",
+                &self.search_base
+            );
+        }
+        self
+    }
+
+    /// Path in which users with parameter `gid` are added
+    pub fn base_of_gid(&self, gid: i32) -> &str {
+        self.group_bases.get(&gid).unwrap_or(&self.base)
+    }
+
+    /// Returns true if users of at least one group are added in another path than `base`.
+    pub fn has_group_bases(&self) -> bool {
+        !self.group_bases.is_empty()
+    }
+}
+
+fn concat_by_comma_if_both_not_empty(left_part: String, right_part: String) -> String {
+    match (left_part.is_empty(), right_part.is_empty()) {
+        (true, true) => String::new(),
+        (true, false) => right_part,
+        (false, true) => left_part,
+        (false, false) => format!("{},{}", left_part, right_part),
+    }
+}
+
+/// Returns the longest common suffix of RDNs of all given DNs.
+/// The names of attributes are compared case insensitive.
+fn common_parent<'a>(dns: impl IntoIterator<Item = &'a str>) -> String {
+    fn rdns(dn: &str) -> Vec<&str> {
+        dn.split(',')
+            .map(str::trim)
+            .filter(|rdn| !rdn.is_empty())
+            .rev()
+            .collect()
+    }
+    let mut dns = dns.into_iter();
+    let Some(first) = dns.next() else {
+        return String::new();
+    };
+    let mut common = rdns(first);
+    for dn in dns {
+        let other = rdns(dn);
+        let shared = common
+            .iter()
+            .zip(other.iter())
+            .take_while(|(left, right)| left.eq_ignore_ascii_case(right))
+            .count();
+        common.truncate(shared);
+    }
+    common.reverse();
+    common.join(",")
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn common_parent_of_org_units() {
+        assert_eq!(
+            "ou=people,dc=example,dc=com",
+            common_parent([
+                "ou=people,dc=example,dc=com",
+                "ou=students,ou=people,dc=example,dc=com",
+                "ou=Staff, OU=People,dc=example,dc=com",
+            ])
+        );
+        assert_eq!(
+            "dc=example,dc=com",
+            common_parent([
+                "ou=students,dc=example,dc=com",
+                "ou=staff,dc=example,dc=com"
+            ])
+        );
+        assert_eq!("", common_parent(["ou=students", "ou=staff"]));
+    }
+
+    #[test]
+    fn base_of_group_falls_back_to_base() {
+        let paths = LdapPaths::new(
+            Some("dc=example,dc=com".to_string()),
+            Some("ou=people".to_string()),
+            None,
+            None,
+            "alice".to_string(),
+        )
+        .with_group_org_units([
+            (1001, "ou=students,ou=people".to_string()),
+            (1002, "ou=people".to_string()),
+        ]);
+        assert_eq!(
+            "ou=students,ou=people,dc=example,dc=com",
+            paths.base_of_gid(1001)
+        );
+        assert_eq!("ou=people,dc=example,dc=com", paths.base_of_gid(1002));
+        assert_eq!("ou=people,dc=example,dc=com", paths.base_of_gid(1003));
+        assert_eq!("ou=people,dc=example,dc=com", paths.search_base());
+        assert!(paths.has_group_bases());
     }
 }
@@ -0,0 +1,237 @@
+//! Verification of the LDAP schema of the server against the object classes
+//! and attributes used by this application.
+
+use std::{collections::HashSet, fmt};
+
+use crate::MgmtConfig;
+
+/// Names of all object classes and attribute types known to a LDAP server.
+/// Names are stored in lower case because LDAP compares them case insensitive.
+#[derive(Debug, Default)]
+pub struct LdapSchema {
+    object_classes: HashSet<String>,
+    attribute_types: HashSet<String>,
+}
+
+impl LdapSchema {
+    /// Creates the schema from the values of the attributes `objectClasses`
+    /// and `attributeTypes` of a subschema entry (RFC 4512, 4.2).
+    pub fn from_definitions<'a>(
+        object_classes: impl IntoIterator<Item = &'a String>,
+        attribute_types: impl IntoIterator<Item = &'a String>,
+    ) -> Self {
+        let all_names = |definitions: &mut dyn Iterator<Item = &'a String>| {
+            definitions
+                .flat_map(|definition| names_of_definition(definition))
+                .map(|name| name.to_lowercase())
+                .collect()
+        };
+        Self {
+            object_classes: all_names(&mut object_classes.into_iter()),
+            attribute_types: all_names(&mut attribute_types.into_iter()),
+        }
+    }
+
+    /// Returns the object classes and attributes used with parameter `config`
+    /// which are not known to the server.
+    pub fn missing(&self, config: &MgmtConfig) -> MissingSchemaItems {
+        let object_classes = config
+            .objectclass_common
+            .iter()
+            .filter(|name| !self.object_classes.contains(&name.to_lowercase()))
+            .cloned()
+            .collect();
+        let attributes = written_attributes(config)
+            .into_iter()
+            .filter(|name| !self.attribute_types.contains(&name.to_lowercase()))
+            .map(ToString::to_string)
+            .collect();
+        MissingSchemaItems {
+            object_classes,
+            attributes,
+        }
+    }
+}
+
+/// Object classes and attributes which are used by this application but not known to the server.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MissingSchemaItems {
+    pub object_classes: Vec<String>,
+    pub attributes: Vec<String>,
+}
+
+impl MissingSchemaItems {
+    pub fn is_empty(&self) -> bool {
+        self.object_classes.is_empty() && self.attributes.is_empty()
+    }
+}
+
+impl fmt::Display for MissingSchemaItems {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(
+                f,
+                "All configured object classes and attributes are known to the LDAP server."
+            );
+        }
+        if !self.object_classes.is_empty() {
+            writeln!(
+                f,
+                "Object classes unknown to the LDAP server (objectclass_common):"
+            )?;
+            for name in self.object_classes.iter() {
+                writeln!(f, "  - {}", name)?;
+            }
+        }
+        if !self.attributes.is_empty() {
+            writeln!(
+                f,
+                "Attributes unknown to the LDAP server (ldap_attributes, ldap_extra_attributes):"
+            )?;
+            for name in self.attributes.iter() {
+                writeln!(f, "  - {}", name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns all attributes which this application writes with parameter `config`.
+fn written_attributes(config: &MgmtConfig) -> Vec<&str> {
+    let attributes = &config.ldap_attributes;
+    let mut written: Vec<&str> = attributes
+        .all()
+        .into_iter()
+        .filter(|name| config.ldap_add_created_at || *name != attributes.created_at)
+        .filter(|name| {
+            config.ldap_track_modifications
+                || (*name != attributes.modified_at && *name != attributes.modified_by)
+        })
+        .collect();
+    written.extend(config.ldap_extra_attributes.iter().map(String::as_str));
+    written
+}
+
+/// Returns the names of a schema definition like
+/// `( 2.5.4.3 NAME ( 'cn' 'commonName' ) SUP name )`.
+fn names_of_definition(definition: &str) -> Vec<String> {
+    let tokens = tokenize(definition);
+    let mut tokens = tokens
+        .iter()
+        .skip_while(|token| **token != Token::Word("NAME"));
+    let _name_keyword = tokens.next();
+    match tokens.next() {
+        Some(Token::Quoted(name)) => vec![name.to_string()],
+        Some(Token::Open) => tokens
+            .map_while(|token| match token {
+                Token::Quoted(name) => Some(name.to_string()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Open,
+    Close,
+    Quoted(&'a str),
+    Word(&'a str),
+}
+
+/// Splits a schema definition into parentheses, quoted strings and other words.
+/// Quoted strings may contain white spaces and parentheses, e.g. within a description.
+fn tokenize(definition: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = definition.trim_start();
+    while let Some(next) = rest.chars().next() {
+        let consumed = match next {
+            '(' => {
+                tokens.push(Token::Open);
+                1
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                1
+            }
+            '\'' => {
+                let end = rest[1..].find('\'').map_or(rest.len(), |end| end + 1);
+                tokens.push(Token::Quoted(&rest[1..end]));
+                (end + 1).min(rest.len())
+            }
+            _ => {
+                let end = rest
+                    .find(|char: char| char.is_whitespace() || char == '(' || char == ')')
+                    .unwrap_or(rest.len());
+                tokens.push(Token::Word(&rest[..end]));
+                end
+            }
+        };
+        rest = rest[consumed..].trim_start();
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::config::LdapAttributes;
+
+    #[test]
+    fn names_of_single_and_multiple_names() {
+        assert_eq!(
+            vec!["posixAccount"],
+            names_of_definition(
+                "( 1.3.6.1.1.1.2.0 NAME 'posixAccount' DESC 'Abstraction of an account (NAME)' \
+                 SUP top AUXILIARY MUST ( cn $ uid ) )"
+            )
+        );
+        assert_eq!(
+            vec!["cn", "commonName"],
+            names_of_definition("( 2.5.4.3 NAME ( 'cn' 'commonName' ) SUP name )")
+        );
+        assert!(names_of_definition("( 2.5.4.3 SUP name )").is_empty());
+    }
+
+    #[test]
+    fn report_missing_object_classes_and_attributes() {
+        let object_classes: Vec<String> = [
+            "( 2.5.6.0 NAME 'top' ABSTRACT )",
+            "( 2.5.6.6 NAME 'Person' SUP top )",
+        ]
+        .map(String::from)
+        .to_vec();
+        let known_attributes = LdapAttributes::default();
+        let attribute_types: Vec<String> = known_attributes
+            .all()
+            .into_iter()
+            .filter(|name| *name != "slurmQos" && *name != "sshPublicKey")
+            .map(|name| format!("( 1.2.3 NAME '{}' )", name.to_uppercase()))
+            .collect();
+        let schema = LdapSchema::from_definitions(&object_classes, &attribute_types);
+        let config = MgmtConfig {
+            objectclass_common: vec![
+                "top".to_string(),
+                "person".to_string(),
+                "slurmRole".to_string(),
+            ],
+            ldap_extra_attributes: vec!["employeeNumber".to_string()],
+            ..Default::default()
+        };
+
+        let actual = schema.missing(&config);
+        assert_eq!(
+            MissingSchemaItems {
+                object_classes: vec!["slurmRole".to_string()],
+                attributes: vec![
+                    "slurmQos".to_string(),
+                    "sshPublicKey".to_string(),
+                    "employeeNumber".to_string()
+                ],
+            },
+            actual
+        );
+        insta::assert_snapshot!(actual.to_string());
+    }
+}
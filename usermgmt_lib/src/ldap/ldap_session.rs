@@ -1,20 +1,24 @@
 use crate::{AppError, AppResult};
-use anyhow::anyhow;
-use ldap3::LdapConn;
+use ldap3::{LdapConn, LdapError};
+use log::{debug, info};
 
 use crate::config::MgmtConfig;
 use crate::ldap;
 
 use super::{LDAPConfig, LdapCredential};
 
-/// API of the ldap crate requires us to mutate the connection
-/// to establish a session.
-/// We can not work with once_cell here because of it.
-type MutableLdapConnection = Option<AppResult<LdapConn>>;
+/// Result code of a LDAP server which is shutting down or closed the connection,
+/// for example via a notice of disconnection (RFC 4511, 4.4.1).
+const RESULT_CODE_UNAVAILABLE: u32 = 52;
 
+/// Connection to a LDAP instance which is established on the first action
+/// and reused by all following actions.
+///
+/// If the server closed the connection in the meantime, for example after an idle timeout,
+/// the session connects and binds again.
 pub struct LdapSession<T> {
     config: LDAPConfig<T>,
-    connection: MutableLdapConnection,
+    connection: Option<LdapConn>,
 }
 
 impl<T> LdapSession<T>
@@ -26,47 +30,124 @@ where
     /// - If username or password for an LDAP session could not be retrieved.
     pub fn new(config: &MgmtConfig, credentials: T) -> AppResult<Self> {
         let config = LDAPConfig::new(config, credentials)?;
-        let connection = None;
-        Ok(Self { config, connection })
+        Ok(Self::from_ldap_config(config))
     }
 
     pub fn from_ldap_readonly_config(config: &MgmtConfig, credentials: T) -> AppResult<Self> {
         let config = LDAPConfig::new_readonly(config, credentials)?;
-        let connection = None;
-        Ok(Self { config, connection })
+        Ok(Self::from_ldap_config(config))
+    }
+
+    pub fn from_ldap_config(config: LDAPConfig<T>) -> Self {
+        Self {
+            config,
+            connection: None,
+        }
     }
 
     pub fn config(&self) -> &LDAPConfig<T> {
         &self.config
     }
 
+    /// Performs parameter `action` on the connection of this session.
+    ///
+    /// Connects first if there is no open connection.
+    /// If the action fails because the connection was lost, the session connects again
+    /// and performs the action a second time.
+    ///
     /// # Errors
     ///
     /// - If establishing of connection to the LDAP fails
+    /// - If the action itself fails
     pub fn action<RT>(
         &mut self,
-        action: impl FnOnce(&mut LdapConn, &LDAPConfig<T>) -> AppResult<RT>,
+        mut action: impl FnMut(&mut LdapConn, &LDAPConfig<T>) -> AppResult<RT>,
     ) -> Result<RT, AppError> {
         self.establish_connection()?;
-        let config = &self.config;
-        let connection = {
-            let is_there = self
-                .connection
-                .as_mut()
-                .expect("Is Some because of establishing connection");
-            is_there
-                .as_mut()
-                .expect("Is ok because of establishing connection")
-        };
-        action(connection, config)
+        let connection = self
+            .connection
+            .as_mut()
+            .expect("Is Some because of establishing connection");
+        match action(connection, &self.config) {
+            Err(error) if is_connection_lost(&error) => {
+                info!("Lost connection to LDAP ({}). Reconnecting", error);
+                self.connection = None;
+                self.establish_connection()?;
+                let connection = self
+                    .connection
+                    .as_mut()
+                    .expect("Is Some because of establishing connection");
+                action(connection, &self.config)
+            }
+            result => result,
+        }
     }
 
+    /// Connects and binds to the LDAP instance
+    /// unless there is already a connection which is not closed.
+    ///
+    /// # Errors
+    ///
+    /// - If establishing of connection to the LDAP fails. See [`ldap::make_ldap_connection`]
     pub fn establish_connection(&mut self) -> AppResult {
-        let _ = self
-            .connection
-            .get_or_insert_with(|| ldap::make_ldap_connection(&self.config))
-            .as_mut()
-            .map_err(|error| anyhow!("{}", error))?;
+        if let Some(connection) = self.connection.as_mut() {
+            if !connection.is_closed() {
+                return Ok(());
+            }
+            debug!("LDAP connection was closed by the server");
+        }
+        self.connection = None;
+        let connection = ldap::make_ldap_connection(&self.config)?;
+        self.connection = Some(connection);
         Ok(())
     }
 }
+
+/// Returns true if parameter `error` is caused by a connection which was closed before
+/// the request could be sent.
+/// Errors while receiving a result are not included because the request might
+/// have been performed by the server.
+fn is_connection_lost(error: &AppError) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<LdapError>())
+        .any(|ldap_error| match ldap_error {
+            LdapError::OpSend { .. } | LdapError::Io { .. } => true,
+            LdapError::LdapResult { result } => result.rc == RESULT_CODE_UNAVAILABLE,
+            _ => false,
+        })
+}
+
+#[cfg(test)]
+mod testing {
+    use anyhow::Context;
+    use ldap3::LdapResult;
+
+    use super::*;
+
+    fn ldap_result(rc: u32) -> AppError {
+        let result = LdapResult {
+            rc,
+            matched: String::new(),
+            text: String::new(),
+            refs: Vec::new(),
+            ctrls: Vec::new(),
+        };
+        AppError::from(LdapError::LdapResult { result })
+    }
+
+    #[test]
+    fn detect_lost_connection() {
+        let io = LdapError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        let wrapped: AppResult = Err(io.into());
+        let wrapped = wrapped
+            .context("Error during LDAP search")
+            .expect_err("Is an error");
+        assert!(is_connection_lost(&wrapped));
+        assert!(is_connection_lost(&ldap_result(RESULT_CODE_UNAVAILABLE)));
+
+        assert!(!is_connection_lost(&ldap_result(32)));
+        assert!(!is_connection_lost(&AppError::from(LdapError::EndOfStream)));
+        assert!(!is_connection_lost(&anyhow::anyhow!("No user found")));
+    }
+}
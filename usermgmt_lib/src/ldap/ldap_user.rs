@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, FixedOffset};
+use ldap3::SearchEntry;
+use log::warn;
+
+use crate::config::LdapAttributes;
+
+/// User as stored in LDAP with the attributes managed by this application.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LdapUser {
+    pub dn: String,
+    pub username: String,
+    pub uid_number: u32,
+    pub gid_number: Option<u32>,
+    pub firstname: Option<String>,
+    pub lastname: Option<String>,
+    pub mail: Option<String>,
+    pub default_qos: Option<String>,
+    pub qos: Vec<String>,
+    pub publickeys: Vec<String>,
+    pub created_at: Option<DateTime<FixedOffset>>,
+    pub modified_at: Option<DateTime<FixedOffset>>,
+    /// DN of the LDAP user who made the last change
+    pub modified_by: Option<String>,
+    /// Values of the attributes listed in `ldap_extra_attributes`, keyed by attribute name.
+    pub extra_attributes: BTreeMap<String, Vec<String>>,
+}
+
+/// Entry found in LDAP which could not be parsed as [`LdapUser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidLdapEntry {
+    pub dn: String,
+    pub reason: String,
+}
+
+/// Result of listing all users in LDAP.
+/// Entries which are not valid users do not fail the whole listing but are collected separately.
+#[derive(Debug, Clone, Default)]
+pub struct ListedLdapUsers {
+    pub users: Vec<LdapUser>,
+    pub invalid_entries: Vec<InvalidLdapEntry>,
+}
+
+impl ListedLdapUsers {
+    pub fn new(
+        entries: impl IntoIterator<Item = SearchEntry>,
+        attributes: &LdapAttributes,
+        extra_attributes: &[String],
+    ) -> Self {
+        let mut listed = Self::default();
+        for entry in entries {
+            match LdapUser::from_search_entry(entry, attributes, extra_attributes) {
+                Ok(user) => listed.users.push(user),
+                Err(invalid) => listed.invalid_entries.push(invalid),
+            }
+        }
+        listed
+    }
+}
+
+/// Column when listing users: name of the LDAP attribute as title and the values of an user.
+pub type ListingColumn = (String, fn(&LdapUser) -> Vec<String>);
+
+impl LdapUser {
+    /// Parses the attributes of a LDAP entry.
+    /// A creation or modification date which is not in the format of RFC 3339 is logged as warning and left out.
+    ///
+    /// # Errors
+    ///
+    /// - If the username or uidNumber is missing.
+    /// - If the uidNumber or gidNumber is not a number.
+    pub fn from_search_entry(
+        entry: SearchEntry,
+        attributes: &LdapAttributes,
+        extra_attributes: &[String],
+    ) -> Result<Self, InvalidLdapEntry> {
+        let SearchEntry { dn, mut attrs, .. } = entry;
+        let invalid = |reason: String| InvalidLdapEntry {
+            dn: dn.clone(),
+            reason,
+        };
+
+        let username = first(&mut attrs, &attributes.username)
+            .ok_or_else(|| invalid(format!("No {}", attributes.username)))?;
+        let uid_number = first(&mut attrs, &attributes.uid_number)
+            .ok_or_else(|| invalid(format!("No {}", attributes.uid_number)))?;
+        let uid_number = parse_number(&attributes.uid_number, &uid_number).map_err(invalid)?;
+        let gid_number = first(&mut attrs, &attributes.gid_number)
+            .map(|gid| parse_number(&attributes.gid_number, &gid))
+            .transpose()
+            .map_err(invalid)?;
+        let mut date_or_warn = |name: &str| {
+            let date = first(&mut attrs, name)?;
+            parse_date(name, &date)
+                .map_err(|reason| warn!("Ignoring {} of LDAP entry {}: {}", name, dn, reason))
+                .ok()
+        };
+        let created_at = date_or_warn(&attributes.created_at);
+        let modified_at = date_or_warn(&attributes.modified_at);
+        let extra_attributes = extra_attributes
+            .iter()
+            .filter_map(|name| Some((name.clone(), attrs.get(name)?.clone())))
+            .collect();
+
+        Ok(Self {
+            username,
+            uid_number,
+            gid_number,
+            firstname: first(&mut attrs, &attributes.firstname),
+            lastname: first(&mut attrs, &attributes.lastname),
+            mail: first(&mut attrs, &attributes.mail),
+            default_qos: first(&mut attrs, &attributes.default_qos),
+            qos: attrs.remove(&attributes.qos).unwrap_or_default(),
+            publickeys: attrs
+                .remove(&attributes.publickey)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|key| !key.is_empty())
+                .collect(),
+            created_at,
+            modified_at,
+            modified_by: first(&mut attrs, &attributes.modified_by),
+            extra_attributes,
+            dn,
+        })
+    }
+
+    /// Returns the columns shown when listing users, sorted by the name of the LDAP attribute.
+    /// This way the order of columns in the output is deterministic.
+    pub fn listing_columns(attributes: &LdapAttributes) -> Vec<ListingColumn> {
+        let mut columns: Vec<ListingColumn> = vec![
+            (attributes.username.clone(), |user| {
+                vec![user.username.clone()]
+            }),
+            (attributes.uid_number.clone(), |user| {
+                vec![user.uid_number.to_string()]
+            }),
+            (attributes.firstname.clone(), |user| {
+                user.firstname.iter().cloned().collect()
+            }),
+            (attributes.lastname.clone(), |user| {
+                user.lastname.iter().cloned().collect()
+            }),
+            (attributes.mail.clone(), |user| {
+                user.mail.iter().cloned().collect()
+            }),
+            (attributes.default_qos.clone(), |user| {
+                user.default_qos.iter().cloned().collect()
+            }),
+            (attributes.qos.clone(), |user| user.qos.clone()),
+            (attributes.created_at.clone(), |user| {
+                user.created_at
+                    .map(|date| date.to_rfc3339())
+                    .into_iter()
+                    .collect()
+            }),
+            (attributes.modified_at.clone(), |user| {
+                user.modified_at
+                    .map(|date| date.to_rfc3339())
+                    .into_iter()
+                    .collect()
+            }),
+            (attributes.modified_by.clone(), |user| {
+                user.modified_by.iter().cloned().collect()
+            }),
+        ];
+        columns.sort_by(|left, right| left.0.cmp(&right.0));
+        columns
+    }
+}
+
+/// Removes the attribute `name` and returns its first value.
+fn first(attrs: &mut HashMap<String, Vec<String>>, name: &str) -> Option<String> {
+    attrs
+        .remove(name)
+        .and_then(|values| values.into_iter().next())
+}
+
+fn parse_number(name: &str, value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} {} is not a number", name, value))
+}
+
+fn parse_date(name: &str, value: &str) -> Result<DateTime<FixedOffset>, String> {
+    DateTime::parse_from_rfc3339(value).map_err(|error| {
+        format!(
+            "{} {} is not a date in the format of RFC 3339 ({})",
+            name, value, error
+        )
+    })
+}
+
+#[cfg(test)]
+mod testing {
+    use maplit::hashmap;
+
+    use super::*;
+
+    fn entry(attrs: HashMap<String, Vec<String>>) -> SearchEntry {
+        SearchEntry {
+            dn: "uid=alice,ou=people,dc=example,dc=org".to_string(),
+            attrs,
+            bin_attrs: HashMap::new(),
+        }
+    }
+
+    fn values(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn parse_user_with_all_attributes() {
+        let given = entry(hashmap! {
+            "uid".to_string() => values(&["alice"]),
+            "uidNumber".to_string() => values(&["1001"]),
+            "gidNumber".to_string() => values(&["1002"]),
+            "givenName".to_string() => values(&["Alice"]),
+            "slurmQos".to_string() => values(&["basic", "interactive"]),
+            "sshPublicKey".to_string() => values(&[""]),
+            "createdAt".to_string() => values(&["2024-05-09T10:49:34.545686277+00:00"]),
+            "modifiedAt".to_string() => values(&["2024-06-01T08:00:00+00:00"]),
+            "modifiedBy".to_string() => values(&["cn=admin,dc=example,dc=org"]),
+            "employeeNumber".to_string() => values(&["4711"]),
+        });
+        let actual = LdapUser::from_search_entry(
+            given,
+            &LdapAttributes::default(),
+            &["employeeNumber".to_string(), "telephoneNumber".to_string()],
+        )
+        .expect("Should be a valid user");
+
+        assert_eq!("alice", actual.username);
+        assert_eq!(1001, actual.uid_number);
+        assert_eq!(Some(1002), actual.gid_number);
+        assert_eq!(Some("Alice".to_string()), actual.firstname);
+        assert_eq!(None, actual.lastname);
+        assert_eq!(values(&["basic", "interactive"]), actual.qos);
+        assert!(actual.publickeys.is_empty());
+        assert_eq!(
+            "2024-05-09T10:49:34.545686277+00:00",
+            actual.created_at.expect("Should have a date").to_rfc3339()
+        );
+        assert_eq!(
+            "2024-06-01T08:00:00+00:00",
+            actual.modified_at.expect("Should have a date").to_rfc3339()
+        );
+        assert_eq!(
+            Some("cn=admin,dc=example,dc=org".to_string()),
+            actual.modified_by
+        );
+        assert_eq!(
+            BTreeMap::from([("employeeNumber".to_string(), values(&["4711"]))]),
+            actual.extra_attributes
+        );
+    }
+
+    #[test]
+    fn invalid_entries_with_reason() {
+        let attributes = LdapAttributes::default();
+        let cases = [
+            (
+                hashmap! {"uidNumber".to_string() => values(&["1001"])},
+                "No uid",
+            ),
+            (
+                hashmap! {"uid".to_string() => values(&["alice"]), "uidNumber".to_string() => values(&["abc"])},
+                "uidNumber abc is not a number",
+            ),
+        ];
+        for (given, expected) in cases {
+            let actual = LdapUser::from_search_entry(entry(given), &attributes, &[])
+                .expect_err("Should be invalid");
+            assert_eq!("uid=alice,ou=people,dc=example,dc=org", actual.dn);
+            assert!(
+                actual.reason.starts_with(expected),
+                "{} does not start with {}",
+                actual.reason,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn ignore_dates_not_in_rfc_3339() {
+        let given = entry(hashmap! {
+            "uid".to_string() => values(&["alice"]),
+            "uidNumber".to_string() => values(&["1001"]),
+            "createdAt".to_string() => values(&["20240509104934Z"]),
+            "modifiedAt".to_string() => values(&["yesterday"]),
+        });
+        let actual = LdapUser::from_search_entry(given, &LdapAttributes::default(), &[])
+            .expect("Should be a valid user");
+
+        assert_eq!("alice", actual.username);
+        assert_eq!(None, actual.created_at);
+        assert_eq!(None, actual.modified_at);
+    }
+}
@@ -0,0 +1,251 @@
+//! Reading and writing of LDAP entries in the LDIF format (RFC 2849).
+//! Only content records are supported, no change records.
+
+use std::collections::BTreeMap;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::prelude::*;
+
+const VERSION_LINE: &str = "version: 1";
+/// Maximum length of a line before it is folded. Recommended by RFC 2849.
+const MAX_LINE_LENGTH: usize = 76;
+
+/// One LDAP entry with its DN and attributes.
+/// Attributes are sorted by name so the output is the same for the same entry.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LdifEntry {
+    pub dn: String,
+    pub attributes: BTreeMap<String, Vec<String>>,
+}
+
+impl LdifEntry {
+    pub fn new(dn: String, attributes: BTreeMap<String, Vec<String>>) -> Self {
+        Self { dn, attributes }
+    }
+
+    /// Returns the first value of the attribute `name` if present.
+    /// Attribute names are compared case-insensitively like in LDAP.
+    pub fn first_value(&self, name: &str) -> Option<&str> {
+        self.values(name).first().map(String::as_str)
+    }
+
+    /// Returns all values of the attribute `name`.
+    /// Attribute names are compared case-insensitively like in LDAP.
+    pub fn values(&self, name: &str) -> &[String] {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map_or(&[], |(_, values)| values.as_slice())
+    }
+}
+
+/// Renders all entries as one LDIF document.
+/// Values which are not safe strings are base64 encoded and long lines are folded.
+pub fn to_ldif(entries: &[LdifEntry]) -> String {
+    let mut output = String::from(VERSION_LINE);
+    output.push('\n');
+    for entry in entries {
+        output.push('\n');
+        push_line(&mut output, "dn", &entry.dn);
+        for (name, values) in entry.attributes.iter() {
+            for value in values {
+                push_line(&mut output, name, value);
+            }
+        }
+    }
+    output
+}
+
+/// Parses a LDIF document with content records.
+///
+/// # Errors
+///
+/// - If a record contains a change type.
+/// - If a line is neither a comment nor an attribute with a value.
+/// - If a record does not start with a DN.
+/// - If a base64 encoded value is not valid base64 or not valid UTF-8.
+pub fn parse_ldif(text: &str) -> AppResult<Vec<LdifEntry>> {
+    let mut entries = Vec::new();
+    for (index, mut record) in unfolded_records(text).into_iter().enumerate() {
+        // The optional version line might be directly followed by the first record.
+        if index == 0 {
+            let (name, value) = parse_line(&record[0])?;
+            if name.eq_ignore_ascii_case("version") {
+                if value != "1" {
+                    bail!("Unsupported LDIF version {}", value);
+                }
+                record.remove(0);
+                if record.is_empty() {
+                    continue;
+                }
+            }
+        }
+        entries.push(parse_record(record)?);
+    }
+    Ok(entries)
+}
+
+fn parse_record(lines: Vec<String>) -> AppResult<LdifEntry> {
+    let mut entry = LdifEntry::default();
+    for (index, line) in lines.into_iter().enumerate() {
+        let (name, value) = parse_line(&line)?;
+        if index == 0 {
+            if !name.eq_ignore_ascii_case("dn") {
+                bail!("LDIF record must start with a dn instead of {}", name);
+            }
+            entry.dn = value;
+        } else if name.eq_ignore_ascii_case("changetype") {
+            bail!(
+                "LDIF change records are not supported (changetype {} for {})",
+                value,
+                entry.dn
+            );
+        } else {
+            entry.attributes.entry(name).or_default().push(value);
+        }
+    }
+    Ok(entry)
+}
+
+/// Splits the text into records separated by empty lines.
+/// Comments are dropped and folded lines are joined. Every returned record has at least one line.
+fn unfolded_records(text: &str) -> Vec<Vec<String>> {
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    let mut in_comment = false;
+    for line in text.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() {
+            if !current.is_empty() {
+                records.push(std::mem::take(&mut current));
+            }
+            in_comment = false;
+        } else if let Some(continuation) = line.strip_prefix(' ') {
+            if !in_comment {
+                if let Some(last) = current.last_mut() {
+                    last.push_str(continuation);
+                }
+            }
+        } else if line.starts_with('#') {
+            in_comment = true;
+        } else {
+            in_comment = false;
+            current.push(line.to_string());
+        }
+    }
+    if !current.is_empty() {
+        records.push(current);
+    }
+    records
+}
+
+fn parse_line(line: &str) -> AppResult<(String, String)> {
+    let (name, rest) = line
+        .split_once(':')
+        .ok_or_else(|| anyhow!("LDIF line `{}` has no colon after the attribute name", line))?;
+    let value = if let Some(encoded) = rest.strip_prefix(':') {
+        let decoded = STANDARD
+            .decode(encoded.trim())
+            .with_context(|| format!("Value of {} is not valid base64", name))?;
+        String::from_utf8(decoded)
+            .with_context(|| format!("Value of {} is not valid UTF-8", name))?
+    } else if rest.starts_with('<') {
+        bail!("Values given by URL are not supported (attribute {})", name);
+    } else {
+        rest.trim_start_matches(' ').to_string()
+    };
+    Ok((name.trim().to_string(), value))
+}
+
+fn push_line(output: &mut String, name: &str, value: &str) {
+    let line = if is_safe_string(value) {
+        format!("{}: {}", name, value)
+    } else {
+        format!("{}:: {}", name, STANDARD.encode(value))
+    };
+    push_folded(output, &line);
+}
+
+/// Folds the line so no line is longer than [`MAX_LINE_LENGTH`].
+/// A continued line starts with a single space.
+fn push_folded(output: &mut String, line: &str) {
+    let mut chars = line.chars().peekable();
+    let mut width = MAX_LINE_LENGTH;
+    while chars.peek().is_some() {
+        let chunk: String = chars.by_ref().take(width).collect();
+        if width != MAX_LINE_LENGTH {
+            output.push(' ');
+        }
+        output.push_str(&chunk);
+        output.push('\n');
+        // Leave room for the leading space of continued lines.
+        width = MAX_LINE_LENGTH - 1;
+    }
+}
+
+/// A value is safe if it can be written without base64 encoding according to RFC 2849.
+fn is_safe_string(value: &str) -> bool {
+    let starts_safe = !value.starts_with([' ', ':', '<']);
+    let ends_safe = !value.ends_with(' ');
+    let chars_safe = value
+        .chars()
+        .all(|next| next.is_ascii() && !matches!(next, '\0' | '\n' | '\r'));
+    starts_safe && ends_safe && chars_safe
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use maplit::btreemap;
+
+    fn alice() -> LdifEntry {
+        LdifEntry::new(
+            "uid=alice,ou=people,dc=example,dc=com".to_string(),
+            btreemap! {
+                "uid".to_string() => vec!["alice".to_string()],
+                "uidNumber".to_string() => vec!["1001".to_string()],
+                "objectClass".to_string() => vec!["top".to_string(), "posixAccount".to_string()],
+                "sn".to_string() => vec!["Müller".to_string()],
+                "description".to_string() => vec![" leading space".to_string()],
+                "sshPublicKey".to_string() => vec![format!("ssh-ed25519 {} alice@example", "A".repeat(80))],
+            },
+        )
+    }
+
+    #[test]
+    fn render_with_base64_and_folding() {
+        insta::assert_snapshot!(to_ldif(&[alice()]));
+    }
+
+    #[test]
+    fn parse_what_was_rendered() {
+        let entries = vec![
+            alice(),
+            LdifEntry::new("uid=bob".to_string(), BTreeMap::new()),
+        ];
+        let actual = parse_ldif(&to_ldif(&entries)).expect("Should parse rendered LDIF");
+        assert_eq!(entries, actual);
+    }
+
+    #[test]
+    fn parse_comments_and_folded_lines() {
+        let text = "version: 1\n# a comment\n  which is folded\ndn: uid=alice,dc=exa\n mple\nuid: alice\ncn:: QWxpY2U=\n";
+        let actual = parse_ldif(text).expect("Should parse valid LDIF");
+        let expected = vec![LdifEntry::new(
+            "uid=alice,dc=example".to_string(),
+            btreemap! {
+                "uid".to_string() => vec!["alice".to_string()],
+                "cn".to_string() => vec!["Alice".to_string()],
+            },
+        )];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn error_for_change_records_and_missing_dn() {
+        assert!(parse_ldif("dn: uid=alice\nchangetype: delete\n").is_err());
+        assert!(parse_ldif("uid: alice\n").is_err());
+        assert!(parse_ldif("dn: uid=alice\nno colon\n").is_err());
+    }
+}
@@ -1,9 +1,7 @@
-use crate::ldap::{
-    ldap_search_result::LdapSearchResult, ldap_simple_credential::LdapSimpleCredential,
-};
+use crate::ldap::ldap_simple_credential::LdapSimpleCredential;
+use crate::util::TrimmedNonEmptyText;
 
 use super::*;
-use maplit::hashmap;
 
 struct ExpectedLdapPaths {
     pub ldap_bind: String,
@@ -108,43 +106,210 @@ fn should_give_correct_ldap_paths() {
     }
 }
 
+fn listed_users() -> Vec<LdapUser> {
+    let mr_x = LdapUser {
+        dn: "uid=mrx".to_string(),
+        username: "mrx".to_string(),
+        uid_number: 1001,
+        gid_number: Some(1002),
+        firstname: Some("Mr.".to_string()),
+        lastname: Some("X".to_string()),
+        mail: Some("mrx@example.org".to_string()),
+        default_qos: Some("basic".to_string()),
+        qos: vec!["basic".to_string(), "default".to_string()],
+        publickeys: Vec::new(),
+        created_at: chrono::DateTime::parse_from_rfc3339("2024-05-09T10:49:34+00:00").ok(),
+        modified_at: chrono::DateTime::parse_from_rfc3339("2024-06-01T08:00:00+00:00").ok(),
+        modified_by: Some("cn=admin,dc=example,dc=org".to_string()),
+        extra_attributes: Default::default(),
+    };
+    let example = LdapUser {
+        dn: "uid=example".to_string(),
+        username: "example".to_string(),
+        uid_number: 1002,
+        gid_number: None,
+        firstname: None,
+        lastname: None,
+        mail: None,
+        default_qos: None,
+        qos: Vec::new(),
+        publickeys: Vec::new(),
+        created_at: None,
+        modified_at: None,
+        modified_by: None,
+        extra_attributes: Default::default(),
+    };
+    vec![mr_x, example]
+}
+
 #[test]
 fn should_produce_simple_output() {
-    let given_entries = vec![
-        hashmap! {
-            "qos".to_string() => vec!["basic".to_string(), "default".to_string()],
-            "name".to_string() => vec!["Mr. X".to_string()],
-            "age".to_string() => vec!["2".to_string()]
-        },
-        hashmap! {
-            "name".to_string() => vec!["example".to_string()]
-        },
-        hashmap! {
-            "qos".to_string() => vec!["default".to_string()],
-            "name".to_string() => vec!["example_man".to_string()],
-        },
-    ];
-    let given_search_entries = vec!["qos", "name"];
-    let ldap_search_result = LdapSearchResult::new(given_search_entries, given_entries);
-    let actual = text_list_output::ldap_simple_output(&ldap_search_result);
-    insta::assert_display_snapshot!(actual);
+    let actual = text_list_output::ldap_simple_output(&listed_users(), &LdapAttributes::default());
+    insta::assert_snapshot!(actual);
 }
 
 #[test]
 fn should_produce_table_from_ldap_search() {
-    let given_entries = vec![
-        hashmap! {
-            "qos".to_string() => vec!["basic".to_string(), "default".to_string()],
-            "name".to_string() => vec!["Mr. X".to_string()],
-            "age".to_string() => vec!["2".to_string()]
-        },
-        hashmap! {
-            "name".to_string() => vec!["example".to_string()]
-        },
+    let actual =
+        text_list_output::ldap_users_to_pretty_table(&listed_users(), &LdapAttributes::default());
+    insta::assert_snapshot!(actual);
+}
+
+#[test]
+fn should_find_conflicts_of_ldif_import() {
+    fn user(username: &str, uid_number: &str) -> LdifEntry {
+        let mut attributes = std::collections::BTreeMap::new();
+        attributes.insert("objectClass".to_owned(), vec!["posixAccount".to_owned()]);
+        attributes.insert("uid".to_owned(), vec![username.to_owned()]);
+        attributes.insert("uidNumber".to_owned(), vec![uid_number.to_owned()]);
+        LdifEntry::new(format!("uid={}", username), attributes)
+    }
+    let config = MgmtConfig {
+        objectclass_common: vec!["posixAccount".to_owned()],
+        ldap_extra_attributes: vec!["telephoneNumber".to_owned()],
+        ..MgmtConfig::default()
+    };
+    let existing = vec![user("alice", "1000")];
+
+    let mut with_extra = user("erin", "1003");
+    with_extra
+        .attributes
+        .insert("telephoneNumber".to_owned(), vec!["123".to_owned()]);
+    let mut other_case = std::collections::BTreeMap::new();
+    other_case.insert("OBJECTCLASS".to_owned(), vec!["PosixAccount".to_owned()]);
+    other_case.insert("UID".to_owned(), vec!["frank".to_owned()]);
+    other_case.insert("uidnumber".to_owned(), vec!["1004".to_owned()]);
+    let valid = vec![
+        user("bob", "1001"),
+        user("carol", "1002"),
+        with_extra,
+        LdifEntry::new("uid=frank".to_owned(), other_case),
+    ];
+    assert_eq!(
+        Vec::<String>::new(),
+        find_import_conflicts(&valid, &existing, &config)
+    );
+
+    let mut unknown = user("gina", "1005");
+    unknown
+        .attributes
+        .insert("description".to_owned(), vec!["text".to_owned()]);
+    let mut no_object_class = user("hank", "1006");
+    no_object_class.attributes.remove("objectClass");
+    let conflicting = vec![
+        user("alice", "1001"),
+        user("bob", "1000"),
+        user("carol", "abc"),
+        user("dave", "1001"),
+        user("Alice", "1007"),
+        user("DAVE", "1008"),
+        LdifEntry::new("cn=no_user".to_owned(), Default::default()),
+        unknown,
+        no_object_class,
     ];
+    let actual = find_import_conflicts(&conflicting, &existing, &config);
+    assert_eq!(
+        vec![
+            "User alice already exists",
+            "uidNumber 1000 of user bob is already taken",
+            "uidNumber abc of user carol is not a number",
+            "uidNumber 1001 of user dave is already taken",
+            "User Alice already exists",
+            "User DAVE already exists",
+            "Entry cn=no_user lacks the object class posixAccount",
+            "Entry cn=no_user has no uid or no uidNumber",
+            "Entry uid=gina has the unknown attribute description",
+            "Entry uid=hank lacks the object class posixAccount",
+        ],
+        actual
+    );
+}
+
+#[test]
+fn should_validate_entries_to_import_like_new_users() {
+    fn exported(changes: &[(&str, &[&str])]) -> LdifEntry {
+        let mut attributes = std::collections::BTreeMap::new();
+        let values = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        for (name, value) in [
+            ("uid", &["alice"][..]),
+            ("uidNumber", &["1000"]),
+            ("gidNumber", &["1001"]),
+            ("givenName", &["Alice"]),
+            ("sn", &["Smith"]),
+            ("mail", &[""]),
+            ("slurmDefaultQos", &["advanced"]),
+            ("slurmQos", &["interactive", "advanced"]),
+            ("sshPublicKey", &[""]),
+            ("homeDirectory", &["/home/alice"]),
+            ("telephoneNumber", &["123"]),
+        ]
+        .iter()
+        .chain(changes)
+        {
+            attributes.insert(name.to_string(), values(value));
+        }
+        LdifEntry::new("uid=alice".to_owned(), attributes)
+    }
+    let config = MgmtConfig {
+        ldap_extra_attributes: vec!["telephoneNumber".to_owned()],
+        ..MgmtConfig::default()
+    };
+
+    let entity =
+        new_entity_of_entry(&exported(&[]), &config).expect("Exported entry should be valid");
+    assert_eq!("alice", entity.username.as_ref());
+    assert_eq!(Group::Staff, entity.group.id());
+    assert_eq!(None, entity.mail);
+    assert!(entity.publickey.is_empty());
+    assert_eq!("advanced", entity.default_qos.as_ref().as_str());
+    assert_eq!(
+        vec!["telephoneNumber=123".to_owned()],
+        entity
+            .extra_attributes
+            .iter()
+            .map(|attribute| format!("{}={}", attribute.name(), attribute.value()))
+            .collect::<Vec<_>>()
+    );
 
-    let given_search_entries = vec!["qos", "name"];
-    let ldap_search_result = LdapSearchResult::new(given_search_entries, given_entries);
-    let actual = text_list_output::ldap_search_to_pretty_table(&ldap_search_result);
-    insta::assert_display_snapshot!(actual);
+    let unknown_gid = new_entity_of_entry(&exported(&[("gidNumber", &["4242"])]), &config)
+        .err()
+        .expect("Unknown gidNumber should be rejected");
+    assert_eq!(
+        "gidNumber 4242 is not the one of any group",
+        unknown_gid.to_string()
+    );
+    for invalid in [
+        exported(&[("givenName", &[])]),
+        exported(&[("slurmQos", &["unknown"])]),
+        exported(&[("sshPublicKey", &["no key"])]),
+        exported(&[("uid", &[" "])]),
+    ] {
+        assert!(
+            new_entity_of_entry(&invalid, &config).is_err(),
+            "{:?} should be rejected",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn should_build_listing_filter_from_all_options() {
+    let config = MgmtConfig {
+        staff_gid: 1001,
+        ..MgmtConfig::default()
+    };
+    let filter = ListFilter {
+        group: Some(crate::Group::Staff),
+        qos: Some(TrimmedNonEmptyText::try_from("interactive").expect("Not empty")),
+        default_qos: Some(TrimmedNonEmptyText::try_from("basic").expect("Not empty")),
+        created_after: Some(Default::default()),
+        created_before: None,
+        missing_pubkey: true,
+        match_text: Some(TrimmedNonEmptyText::try_from("mü*").expect("Not empty")),
+    };
+    assert_eq!(
+        "(&(objectClass=*))",
+        listing_filter(&ListFilter::default(), &config).to_string()
+    );
+    insta::assert_snapshot!(listing_filter(&filter, &config).to_string());
 }
@@ -1,4 +1,6 @@
-use super::ldap_search_result::LdapSearchResult;
+use super::LdapUser;
+use crate::config::LdapAttributes;
+use crate::util::SshPublicKey;
 
 /// Returns rows. Every row consists comma separated cells. Every cell is key value pair with "="
 /// sign in the middle.
@@ -6,21 +8,14 @@ use super::ldap_search_result::LdapSearchResult;
 /// Example for cell: name=example
 ///
 /// If there is no value for the field, then there is nothing from right of the "=" sign
-pub fn ldap_simple_output(search_results: &LdapSearchResult) -> String {
-    let attrs = search_results.fields();
-    let search_by_in_title = search_results.headers();
-    attrs
+pub fn ldap_simple_output(users: &[LdapUser], attributes: &LdapAttributes) -> String {
+    let columns = LdapUser::listing_columns(attributes);
+    users
         .iter()
-        .map(|next_row| {
-            next_row
+        .map(|user| {
+            columns
                 .iter()
-                .enumerate()
-                .map(|(index, next_field)| {
-                    let title = *search_by_in_title
-                        .get(index)
-                        .expect("Index comes from iterator");
-                    format!("{}={}", title, next_field.join("|"))
-                })
+                .map(|(title, values_of)| format!("{}={}", title, values_of(user).join("|")))
                 .collect::<Vec<String>>()
                 .join(",")
         })
@@ -28,44 +23,57 @@ pub fn ldap_simple_output(search_results: &LdapSearchResult) -> String {
         .join("\n")
 }
 
-/// Returns a pretty ASCII table from the result of an LDAP search query.
+/// Returns a pretty ASCII table of users.
 /// First row is the title row which shows all used field name.
-/// Subsequent rows contain values to each field name for an LDAP entity.
+/// Subsequent rows contain values to each field name for an user.
 ///
 /// If there is no value for the field, then the cell of in the column is white space only
-pub fn ldap_search_to_pretty_table(search_result: &LdapSearchResult) -> String {
+pub fn ldap_users_to_pretty_table(users: &[LdapUser], attributes: &LdapAttributes) -> String {
     use prettytable::{Cell, Row, Table};
 
-    let headers = search_result.headers();
-    let mut table = table_with_title_bar(headers.as_slice());
-
-    for row_to_convert in search_result.fields().iter() {
-        // Construct with initial empty cells
-        let mut cells = vec![Cell::new(""); headers.len()];
-
-        for (field_index, cell_value) in row_to_convert.iter().enumerate() {
-            let to_mutate = cells
-                .get_mut(field_index)
-                .expect("Index comes from iterator");
-            *to_mutate = Cell::new(&cell_value.join(" | "));
-        }
+    let columns = LdapUser::listing_columns(attributes);
+    let mut table = Table::new();
+    table.set_titles(Row::new(
+        columns.iter().map(|(title, _)| Cell::new(title)).collect(),
+    ));
 
+    for user in users {
+        let cells = columns
+            .iter()
+            .map(|(_, values_of)| Cell::new(&values_of(user).join(" | ")))
+            .collect();
         table.add_row(Row::new(cells));
     }
 
-    return table.to_string();
-
-    fn table_with_title_bar(field_names: &[&str]) -> Table {
-        let mut table = Table::new();
-
-        // Title bar
-        let title_cells = field_names
-            .iter()
-            .map(|to_cell| Cell::new(to_cell))
-            .collect();
+    table.to_string()
+}
 
-        table.set_titles(Row::new(title_cells));
+/// Returns a pretty ASCII table with the fingerprint, type and comment of every SSH public key.
+/// Stored values which are not valid keys are shown with the reason instead of failing.
+pub fn ssh_keys_to_pretty_table(stored_keys: &[String]) -> String {
+    use prettytable::{Cell, Row, Table};
 
-        table
+    let mut table = Table::new();
+    table.set_titles(Row::new(vec![
+        Cell::new("Fingerprint"),
+        Cell::new("Type"),
+        Cell::new("Comment"),
+    ]));
+    for stored in stored_keys {
+        let cells = match stored.parse::<SshPublicKey>() {
+            Ok(key) => vec![
+                Cell::new(&key.fingerprint()),
+                Cell::new(key.key_type()),
+                Cell::new(key.comment().unwrap_or("")),
+            ],
+            Err(error) => vec![
+                Cell::new("Invalid key"),
+                Cell::new(""),
+                Cell::new(&error.to_string()),
+            ],
+        };
+        table.add_row(Row::new(cells));
     }
+
+    table.to_string()
 }
@@ -4,7 +4,10 @@ use crate::{
     cli::UserToAdd,
     config::MgmtConfig,
     prelude::AppResult,
-    util::{ResolvedGid, TrimmedNonEmptyText, ValidGroupOfQos, ValidQos},
+    util::{
+        ExtraLdapAttribute, ResolvedGid, SlurmLimit, SshPublicKey, TrimmedNonEmptyText,
+        UserAssociation, ValidGroupOfQos, ValidQos,
+    },
     Entity, Group,
 };
 
@@ -16,21 +19,32 @@ pub struct NewEntity {
     pub mail: Option<TrimmedNonEmptyText>,
     pub group: ResolvedGid,
     pub default_qos: ValidQos,
-    pub publickey: Option<TrimmedNonEmptyText>,
+    pub publickey: Vec<SshPublicKey>,
     pub qos: ValidGroupOfQos,
+    pub extra_attributes: Vec<ExtraLdapAttribute>,
+    /// Resource limits in Slurm, by default the ones of the group from the configuration
+    pub slurm_limits: Vec<SlurmLimit>,
+    /// Slurm associations in addition to the one with the account of the group
+    pub associations: Vec<UserAssociation>,
+    /// Default account in Slurm, the account of the group if none
+    pub default_account: Option<TrimmedNonEmptyText>,
+    /// Initial password in plain text
+    pub password: Option<String>,
 }
 
 impl NewEntity {
     /// # Errors
     ///
     /// - If first or last name is not provided.
+    /// - If the default Slurm limits of the group in the configuration are not valid.
+    /// - If the default account is neither the account of the group nor one of the associations.
     pub fn new(entity: Entity, config: &MgmtConfig) -> AppResult<Self> {
         let (firstname, lastname) = match (entity.firstname, entity.lastname) {
             (Some(first), Some(last)) => Ok((first, last)),
             _ => Err(anyhow::anyhow!("Last and first name need to be provided")),
         }?;
 
-        let (mail, publickey) = (entity.mail, entity.publickey);
+        let (mail, publickey) = (entity.mail, entity.publickey.unwrap_or_default());
 
         let group = entity.group.unwrap_or_else(|| {
             let group = Group::default();
@@ -43,8 +57,28 @@ impl NewEntity {
             .qos
             .map(Ok)
             .unwrap_or_else(|| ValidGroupOfQos::from_group(group.id(), config))?;
+        let slurm_limits = if entity.slurm_limits.is_empty() {
+            SlurmLimit::from_group(group.id(), config)?
+        } else {
+            entity.slurm_limits
+        };
 
-        if publickey.is_none() {
+        if let Some(default_account) = &entity.default_account {
+            let is_associated = default_account.as_ref() == &group.id().to_string()
+                || entity
+                    .associations
+                    .iter()
+                    .any(|association| association.account() == default_account.as_ref());
+            if !is_associated {
+                return Err(anyhow::anyhow!(
+                    "Default account {} is neither the account of the group {} nor of an association",
+                    default_account,
+                    group
+                ));
+            }
+        }
+
+        if publickey.is_empty() {
             warn!("No public key was supplied for new user. Remember to add it later via modification");
         }
 
@@ -57,16 +91,28 @@ impl NewEntity {
             mail,
             publickey,
             qos,
+            extra_attributes: entity.extra_attributes,
+            slurm_limits,
+            associations: entity.associations,
+            default_account: entity.default_account,
+            password: None,
         })
     }
 
     /// # Errors
     ///
     /// - If an user entity could not be created. See [`Entity::new`]
+    /// - If the associations are not valid. See [`Entity::with_associations`]
     pub fn new_user_addition_conf(to_add: UserToAdd, conf: &MgmtConfig) -> AppResult<Self> {
-        let (firstname, lastname, common_user_fields) = to_add.into();
+        let (firstname, lastname, common_user_fields, associations, default_account, password) =
+            to_add.into();
         let (firstname, lastname) = (Some(firstname), Some(lastname));
-        let entity = Entity::new(firstname, lastname, common_user_fields, conf)?;
-        Self::new(entity, conf)
+        let entity = Entity::new(firstname, lastname, common_user_fields, conf)?
+            .with_associations(associations, Vec::new(), default_account, conf)?;
+        let new_entity = Self::new(entity, conf)?;
+        Ok(Self {
+            password,
+            ..new_entity
+        })
     }
 }
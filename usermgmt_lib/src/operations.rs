@@ -1,15 +1,23 @@
-use log::info;
+use chrono::{Local, NaiveDate};
+use std::{cell::RefCell, fs, path::Path};
+
+use log::{error, info, warn};
 
 use crate::{
-    cli::{OnWhichSystem, UserToAdd},
+    cli::{AccountCommands, ListFilter, OnWhichSystem, QosCommands, UsageFormat, UserToAdd},
     config::MgmtConfig,
     dir,
-    ldap::{self, text_list_output, LdapCredential, LdapSession},
-    slurm,
+    ldap::{self, ldif, text_list_output, LdapCredential, LdapSession},
+    slurm::{self, ShowConditions, SlurmRestClient, UsagePeriod, UsageReport},
     ssh::{SshConnection, SshCredentials},
+    util::{ResultAccumulator, SshPublicKey, TrimmedNonEmptyText},
     AppResult, ChangesToUser, NewEntity,
 };
+use anyhow::{bail, Context};
 
+/// Parameter `on_added_to_ldap` is called as soon as the user is added to LDAP,
+/// e.g. to show a generated password even if adding the user to Slurm or its directories fails afterwards.
+///
 /// # Errors
 ///
 /// - If the attributes of the parameter `to_add` is not compatible with fields of parameter `config`. See [`NewEntity::new_user_addition_conf`].
@@ -20,6 +28,7 @@ pub fn add_user<T, C>(
     config: &MgmtConfig,
     ldap_credentials: T,
     ssh_credentials: C,
+    on_added_to_ldap: impl FnOnce(),
 ) -> AppResult
 where
     T: LdapCredential + Clone,
@@ -27,15 +36,30 @@ where
 {
     info!("Start adding user");
 
-    let entity = NewEntity::new_user_addition_conf(to_add, config)?;
+    let entity = NewEntity::new_user_addition_conf(to_add.clone(), config)?;
+    if entity.password.is_some() && !on_which_sys.ldap() {
+        warn!(
+            "A password was given but LDAP is not included. The password is not stored anywhere."
+        );
+    }
 
     perform_action_on_context(
         on_which_sys,
         config,
         ldap_credentials.clone(),
         &ssh_credentials,
-        |session| ldap::add_ldap_user(&entity, config, session),
-        |ssh_con| slurm::add_slurm_user(&entity, config, ssh_con),
+        then_on_success(
+            |session| ldap::add_ldap_user(&entity, config, session),
+            on_added_to_ldap,
+        ),
+        |cluster_config, ssh_con, rest| match cluster_config.slurm_cluster {
+            None => slurm::add_slurm_user(&entity, cluster_config, ssh_con, rest),
+            Some(_) => {
+                // Default QOS and their validation depend on the cluster.
+                let entity = NewEntity::new_user_addition_conf(to_add.clone(), cluster_config)?;
+                slurm::add_slurm_user(&entity, cluster_config, ssh_con, rest)
+            }
+        },
         |_| dir::add_user_directories(&entity, config, &ssh_credentials),
     )?;
 
@@ -44,11 +68,27 @@ where
     Ok(())
 }
 
+/// Returns parameter `action` which calls parameter `on_success` right after `action` succeeded.
+fn then_on_success<S>(
+    action: impl FnOnce(&mut S) -> AppResult,
+    on_success: impl FnOnce(),
+) -> impl FnOnce(&mut S) -> AppResult {
+    move |state| {
+        action(state)?;
+        on_success();
+        Ok(())
+    }
+}
+
+/// Refuses to delete a user with pending or running jobs in Slurm unless parameter `cancel_jobs` is true.
+///
 /// # Errors
 ///
+/// - If the user has jobs in Slurm. See [`slurm::ensure_no_active_jobs`].
 /// - When user deletion fails. See [`perform_action_on_context`].
 pub fn delete_user<T, C>(
     user: &str,
+    cancel_jobs: bool,
     on_which_sys: &OnWhichSystem,
     config: &MgmtConfig,
     ldap_credentials: T,
@@ -60,13 +100,27 @@ where
 {
     info!("Start deleting user {}", user);
 
+    if on_which_sys.slurm() {
+        // Checked before anything is deleted, so a refused deletion leaves the user untouched in LDAP.
+        let cluster_configs = config.slurm_cluster_configs(on_which_sys.clusters())?;
+        perform_on_slurm_clusters(
+            &cluster_configs,
+            &credentials,
+            |cluster_config, session, rest| {
+                slurm::ensure_no_active_jobs(user, cancel_jobs, cluster_config, session, rest)
+            },
+        )?;
+    }
+
     perform_action_on_context(
         on_which_sys,
         config,
         ldap_credentials.clone(),
         &credentials,
         |ldap_session| ldap::delete_ldap_user(user, ldap_session),
-        |ssh_connection| slurm::delete_slurm_user(user, config, ssh_connection),
+        |cluster_config, ssh_connection, rest| {
+            slurm::delete_slurm_user(user, cluster_config, ssh_connection, rest)
+        },
         |_| dir::delete_user_directories(user, config, &credentials),
     )?;
 
@@ -97,15 +151,177 @@ where
         &credential,
         false,
         |ldap_session| ldap::modify_ldap_user(&modifiable, ldap_session),
-        |ssh_connection| slurm::modify_slurm_user(&modifiable, config, ssh_connection),
+        |cluster_config, ssh_connection, rest| {
+            slurm::modify_slurm_user(&modifiable, cluster_config, ssh_connection, rest)
+        },
     )?;
 
     info!("Finished modifying user {}", modifiable.username);
     Ok(())
 }
 
+/// Replaces the password of a user in LDAP.
+/// See [`ldap::reset_ldap_password`] for the meaning of parameter `force_change`.
+///
+/// # Errors
+///
+/// - If getting of credentials for LDAP fails. See [`LdapSession::new`]
+/// - If replacing the password fails. See [`ldap::reset_ldap_password`].
+pub fn reset_password<T>(
+    user: &str,
+    password: &str,
+    force_change: bool,
+    config: &MgmtConfig,
+    ldap_credentials: T,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    info!("Start resetting password of user {}", user);
+
+    let mut ldap_session = LdapSession::new(config, ldap_credentials)?;
+    ldap_session.establish_connection()?;
+    ldap::reset_ldap_password(user, password, force_change, &mut ldap_session)?;
+
+    info!("Finished resetting password of user {}", user);
+    Ok(())
+}
+
+/// Adds SSH public keys to a user in LDAP.
+///
 /// # Errors
 ///
+/// - If getting of credentials for LDAP fails. See [`LdapSession::new`]
+/// - If adding the keys fails. See [`ldap::add_ldap_publickeys`].
+pub fn add_ssh_keys<T>(
+    user: &str,
+    keys: &[SshPublicKey],
+    config: &MgmtConfig,
+    ldap_credentials: T,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    let mut ldap_session = LdapSession::new(config, ldap_credentials)?;
+    ldap_session.establish_connection()?;
+    ldap::add_ldap_publickeys(user, keys, &mut ldap_session)
+}
+
+/// Removes the SSH public key with the SHA256 fingerprint `fingerprint` from a user in LDAP.
+///
+/// # Errors
+///
+/// - If getting of credentials for LDAP fails. See [`LdapSession::new`]
+/// - If removing the key fails. See [`ldap::remove_ldap_publickey`].
+pub fn remove_ssh_key<T>(
+    user: &str,
+    fingerprint: &str,
+    config: &MgmtConfig,
+    ldap_credentials: T,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    let mut ldap_session = LdapSession::new(config, ldap_credentials)?;
+    ldap_session.establish_connection()?;
+    ldap::remove_ldap_publickey(user, fingerprint, &mut ldap_session)
+}
+
+/// Prints a table with the fingerprints of all SSH public keys of a user in LDAP.
+///
+/// # Errors
+///
+/// - If getting of credentials for LDAP fails. See [`LdapSession::from_ldap_readonly_config`]
+/// - If fetching the keys fails. See [`ldap::find_publickeys_by_uid`].
+pub fn print_ssh_keys_to_stdout<T>(
+    user: &str,
+    config: &MgmtConfig,
+    ldap_credentials: T,
+) -> AppResult
+where
+    T: LdapCredential,
+{
+    let mut ldap_session = LdapSession::from_ldap_readonly_config(config, ldap_credentials)?;
+    ldap_session.establish_connection()?;
+    let keys = ldap::find_publickeys_by_uid(user, &mut ldap_session)?;
+    if keys.is_empty() {
+        println!("User {} has no SSH public keys", user);
+    } else {
+        println!("{}", text_list_output::ssh_keys_to_pretty_table(&keys));
+    }
+    Ok(())
+}
+
+/// Prints the configured object classes and attributes which are not known to the LDAP server.
+///
+/// # Errors
+///
+/// - If getting of credentials for LDAP fails. See [`LdapSession::from_ldap_readonly_config`]
+/// - If the schema could not be read. See [`ldap::check_ldap_schema`].
+/// - If any object class or attribute is not known to the LDAP server.
+pub fn check_ldap_schema<T>(config: &MgmtConfig, ldap_credentials: T) -> AppResult
+where
+    T: LdapCredential,
+{
+    let mut ldap_session = LdapSession::from_ldap_readonly_config(config, ldap_credentials)?;
+    ldap_session.establish_connection()?;
+    let missing = ldap::check_ldap_schema(config, &mut ldap_session)?;
+    println!("{}", missing);
+    if !missing.is_empty() {
+        bail!("LDAP schema of the server does not match the configuration");
+    }
+    Ok(())
+}
+
+/// Returns all users in LDAP with the attributes managed by this application as LDIF document.
+///
+/// # Errors
+///
+/// - If getting of credentials for LDAP fails. See [`LdapSession::from_ldap_readonly_config`]
+/// - If fetching the entries fails. See [`ldap::export_ldap_entries`].
+pub fn export_ldif<T>(config: &MgmtConfig, ldap_credentials: T) -> AppResult<String>
+where
+    T: LdapCredential,
+{
+    let mut ldap_session = LdapSession::from_ldap_readonly_config(config, ldap_credentials)?;
+    ldap_session.establish_connection()?;
+    let entries = ldap::export_ldap_entries(config, &mut ldap_session)?;
+    info!("Exported {} LDAP entries", entries.len());
+    Ok(ldif::to_ldif(&entries))
+}
+
+/// Adds all users within the LDIF file at `path` to LDAP.
+///
+/// # Errors
+///
+/// - If the file could not be read or is no valid LDIF. See [`ldif::parse_ldif`].
+/// - If getting of credentials for LDAP fails. See [`LdapSession::new`]
+/// - If importing the entries fails. See [`ldap::import_ldap_entries`].
+pub fn import_ldif<T>(path: &Path, config: &MgmtConfig, ldap_credentials: T) -> AppResult
+where
+    T: LdapCredential,
+{
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Unable to read LDIF file at {}", path.to_string_lossy()))?;
+    let entries = ldif::parse_ldif(&content)
+        .with_context(|| format!("Invalid LDIF file at {}", path.to_string_lossy()))?;
+
+    let mut ldap_session = LdapSession::new(config, ldap_credentials)?;
+    ldap_session.establish_connection()?;
+    ldap::import_ldap_entries(&entries, config, &mut ldap_session)?;
+    info!("Imported {} LDAP entries", entries.len());
+    Ok(())
+}
+
+/// Prints the users matching parameter `filter`.
+///
+/// Options of the filter which refer to attributes only stored in LDAP
+/// are applied to Slurm via the usernames found in LDAP.
+/// They are ignored for Slurm if LDAP is not included.
+///
+/// # Errors
+///
+/// - When the filter is not valid. See [`ListFilter::validate`].
 /// - When listing users fails. See [`perform_action_on_context`].
 /// - When the execution of the Slurm command fails. See [`slurm::list_users`].
 /// - When the execution of the LDAP command fails. See [`ldap::list_ldap_users`].
@@ -113,6 +329,7 @@ pub fn print_list_of_users_to_stdout<T, C>(
     config: &MgmtConfig,
     on_which_sys: &OnWhichSystem,
     simple_output_ldap: bool,
+    filter: &ListFilter,
     ldap_credentials: T,
     credentials: C,
 ) -> AppResult
@@ -120,6 +337,13 @@ where
     T: LdapCredential,
     C: SshCredentials,
 {
+    filter.validate(config)?;
+    if filter.has_ldap_only_options() && on_which_sys.slurm() && !on_which_sys.ldap() {
+        warn!("Filtering by default QOS, creation date, SSH public keys or text requires LDAP. These options are ignored for Slurm.");
+    }
+    // Usernames found in LDAP to restrict the listing of Slurm.
+    let ldap_usernames: RefCell<Option<Vec<String>>> = RefCell::new(None);
+
     perform_action_context_no_dirs(
         on_which_sys,
         config,
@@ -127,20 +351,43 @@ where
         &credentials,
         true,
         |ldap_session| {
-            let ldap_config = ldap_session.config();
-            let search_result_data = ldap::list_ldap_users(ldap_config)?;
+            let listed = ldap::list_ldap_users(config, filter, ldap_session)?;
+            for invalid in listed.invalid_entries.iter() {
+                warn!(
+                    "Skipping invalid LDAP entry {}: {}",
+                    invalid.dn, invalid.reason
+                );
+            }
 
+            let attributes = &config.ldap_attributes;
             let output = if simple_output_ldap {
-                text_list_output::ldap_simple_output(&search_result_data)
+                text_list_output::ldap_simple_output(&listed.users, attributes)
             } else {
-                text_list_output::ldap_search_to_pretty_table(&search_result_data)
+                text_list_output::ldap_users_to_pretty_table(&listed.users, attributes)
             };
             println!("{}", &output);
+            if filter.has_ldap_only_options() {
+                let usernames = listed.users.into_iter().map(|user| user.username).collect();
+                *ldap_usernames.borrow_mut() = Some(usernames);
+            }
             Ok(())
         },
-        |ssh_connection| {
-            let output = slurm::list_users(config, ssh_connection, false)?;
-            println!("{}", output);
+        |cluster_config, ssh_connection, rest| {
+            if let Some(cluster) = &cluster_config.slurm_cluster {
+                println!("Slurm cluster {}:", cluster);
+            }
+            let users = ldap_usernames.borrow().clone();
+            if users.as_ref().is_some_and(Vec::is_empty) {
+                println!("No users in Slurm match the filter because no users in LDAP match it");
+                return Ok(());
+            }
+            let conditions = ShowConditions {
+                users,
+                account: filter.group,
+                qos: filter.qos.as_ref().map(ToString::to_string),
+            };
+            let associations = slurm::list_users(cluster_config, ssh_connection, rest, conditions)?;
+            println!("{}", slurm::associations_to_pretty_table(&associations));
             Ok(())
         },
     )?;
@@ -148,16 +395,201 @@ where
     Ok(())
 }
 
+/// Adds, modifies, deletes or lists accounts in Slurm on every cluster selected by parameter `clusters`.
+/// See [`perform_on_slurm_clusters`]
+///
+/// # Errors
+///
+/// - If a cluster is selected which is not configured. See [`MgmtConfig::slurm_cluster_configs`]
+/// - If establishing the ssh connection fails
+/// - If the Slurm command fails. See [`slurm::add_slurm_account`], [`slurm::modify_slurm_account`],
+///   [`slurm::delete_slurm_account`] or [`slurm::list_slurm_accounts`].
+pub fn manage_slurm_account<C>(
+    action: AccountCommands,
+    clusters: &[TrimmedNonEmptyText],
+    config: &MgmtConfig,
+    ssh_credentials: C,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let cluster_configs = config.slurm_cluster_configs(clusters)?;
+    perform_on_slurm_clusters(
+        &cluster_configs,
+        &ssh_credentials,
+        |config, ssh_session, _| match &action {
+            AccountCommands::Add { name, properties } => slurm::add_slurm_account(
+                name.as_ref(),
+                properties.clone().into(),
+                config,
+                ssh_session,
+            ),
+            AccountCommands::Modify { name, properties } => slurm::modify_slurm_account(
+                name.as_ref(),
+                properties.clone().into(),
+                config,
+                ssh_session,
+            ),
+            AccountCommands::Delete { name } => {
+                slurm::delete_slurm_account(name.as_ref(), config, ssh_session)
+            }
+            AccountCommands::List => {
+                let accounts = slurm::list_slurm_accounts(config, ssh_session)?;
+                print_cluster_heading(config);
+                println!("{}", slurm::accounts_to_pretty_table(&accounts));
+                Ok(())
+            }
+        },
+    )
+}
+
+/// Prints the usage of a user in Slurm on every cluster selected by parameter `clusters`
+/// as tables or JSON depending on parameter `format`.
+/// The period ends today and starts 30 days earlier unless parameter `from` or `to` is given.
+///
+/// # Errors
+///
+/// - If parameter `from` is after parameter `to`
+/// - If a selected Slurm cluster is not configured. See [`MgmtConfig::slurm_cluster_configs`]
+/// - If the usage can not be queried on at least one cluster. See [`slurm::usage_of_user`]
+///   The usage of the other clusters is printed before. See [`perform_on_slurm_clusters`]
+pub fn print_usage_of_user_to_stdout<C>(
+    user: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    format: UsageFormat,
+    clusters: &[TrimmedNonEmptyText],
+    config: &MgmtConfig,
+    ssh_credentials: C,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let period = UsagePeriod::new(from, to, Local::now().date_naive())?;
+    let cluster_configs = config.slurm_cluster_configs(clusters)?;
+    let mut report = UsageReport::new(user, period);
+    let mut any_succeeded = false;
+    let queried = perform_on_slurm_clusters(
+        &cluster_configs,
+        &ssh_credentials,
+        |cluster_config, session, _| {
+            let usage = slurm::usage_of_user(user, period, cluster_config, session)?;
+            report.merge(usage);
+            any_succeeded = true;
+            Ok(())
+        },
+    );
+    // The usage of the clusters which could be queried is printed even if others failed.
+    if any_succeeded {
+        match format {
+            UsageFormat::Table => println!("{}", report.to_pretty_tables()),
+            UsageFormat::Json => println!("{}", report.to_json()?),
+        }
+    }
+    queried
+}
+
+/// Creates, modifies, deletes, lists or checks QOS in Slurm on every cluster. See [`perform_on_slurm_clusters`]
+///
+/// # Errors
+///
+/// - If establishing the ssh connection fails
+/// - If the Slurm command fails. See [`slurm::add_slurm_qos`], [`slurm::modify_slurm_qos`],
+///   [`slurm::delete_slurm_qos`] or [`slurm::list_slurm_qos`].
+/// - If a QOS named in parameter `config` does not exist in Slurm for the check.
+pub fn manage_slurm_qos<C>(
+    action: QosCommands,
+    config: &MgmtConfig,
+    ssh_credentials: C,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let cluster_configs = all_slurm_cluster_configs(config)?;
+    perform_on_slurm_clusters(
+        &cluster_configs,
+        &ssh_credentials,
+        |config, ssh_session, _| match &action {
+            QosCommands::Create { name, limits } => {
+                slurm::add_slurm_qos(name.as_ref(), limits.clone().into(), config, ssh_session)
+            }
+            QosCommands::Modify { name, limits } => {
+                slurm::modify_slurm_qos(name.as_ref(), limits.clone().into(), config, ssh_session)
+            }
+            QosCommands::Delete { name } => {
+                slurm::delete_slurm_qos(name.as_ref(), config, ssh_session)
+            }
+            QosCommands::List => {
+                let all_qos = slurm::list_slurm_qos(config, ssh_session)?;
+                print_cluster_heading(config);
+                println!("{}", slurm::qos_to_pretty_table(&all_qos));
+                Ok(())
+            }
+            QosCommands::Check => {
+                let comparison = slurm::check_slurm_qos(config, ssh_session)?;
+                print_cluster_heading(config);
+                print!("{}", comparison);
+                if !comparison.all_configured_exist() {
+                    bail!("QOS named in the configuration do not exist in Slurm");
+                }
+                Ok(())
+            }
+        },
+    )
+}
+
+/// Adds every account which is referenced in parameter `config` but missing in Slurm
+/// on every cluster. See [`perform_on_slurm_clusters`]
+///
+/// # Errors
+///
+/// - If establishing the ssh connection fails
+/// - If adding the accounts fails. See [`slurm::bootstrap_slurm_accounts`].
+pub fn bootstrap_slurm<C>(config: &MgmtConfig, ssh_credentials: C) -> AppResult
+where
+    C: SshCredentials,
+{
+    let cluster_configs = all_slurm_cluster_configs(config)?;
+    perform_on_slurm_clusters(
+        &cluster_configs,
+        &ssh_credentials,
+        |config, ssh_session, _| {
+            let added = slurm::bootstrap_slurm_accounts(config, ssh_session)?;
+            print_cluster_heading(config);
+            if added.is_empty() {
+                println!("All accounts of valid_slurm_groups already exist in Slurm");
+            } else {
+                println!("Added the accounts {} to Slurm", added.join(", "));
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Configurations of every cluster listed in `slurm_clusters` or of the head node if none are listed.
+/// See [`MgmtConfig::slurm_cluster_configs`]
+fn all_slurm_cluster_configs(config: &MgmtConfig) -> AppResult<Vec<MgmtConfig>> {
+    config.slurm_cluster_configs(&[] as &[&str])
+}
+
+/// Prints the name of the Slurm cluster of parameter `config` before its output, if the cluster is named.
+fn print_cluster_heading(config: &MgmtConfig) {
+    if let Some(cluster) = &config.slurm_cluster {
+        println!("Slurm cluster {}:", cluster);
+    }
+}
+
 /// Performs an action on all the three systems on the cluster.
 ///
 /// - LDAP
-/// - Slurm
+/// - Slurm, on every cluster selected by parameter `on_which_sys`. See [`perform_on_slurm_clusters`]
 /// - Directory management
 ///
 /// # Errors
 ///
 /// - If getting of credentials for LDAP fails. See [`LdapSession::new`]
 /// - If establishing the ssh connection fails
+/// - If a selected Slurm cluster is not configured. See [`MgmtConfig::slurm_cluster_configs`]
 /// - If one of three actions fails `on_ldap_action`, `on_slurm_action` or `on_dir_action`.
 pub fn perform_action_on_context<T, C>(
     on_which_sys: &OnWhichSystem,
@@ -165,7 +597,7 @@ pub fn perform_action_on_context<T, C>(
     ldap_credentials: T,
     ssh_credentials: &C,
     on_ldap_action: impl FnOnce(&mut LdapSession<T>) -> AppResult,
-    on_slurm_action: impl FnOnce(&SshConnection<C>) -> AppResult,
+    on_slurm_action: impl FnMut(&MgmtConfig, &SshConnection<C>, Option<&SlurmRestClient>) -> AppResult,
     mut on_dir_action: impl FnMut(&SshConnection<C>) -> AppResult,
 ) -> AppResult
 where
@@ -178,16 +610,15 @@ where
         on_ldap_action(&mut ldap_session)?;
     }
 
-    if on_which_sys.slurm() || on_which_sys.dirs() {
+    if on_which_sys.slurm() {
+        let cluster_configs = config.slurm_cluster_configs(on_which_sys.clusters())?;
+        perform_on_slurm_clusters(&cluster_configs, ssh_credentials, on_slurm_action)?;
+    }
+
+    if on_which_sys.dirs() {
         let ssh_session = SshConnection::from_head_node(config, ssh_credentials.clone());
         ssh_session.establish_connection()?;
-        if on_which_sys.slurm() {
-            on_slurm_action(&ssh_session)?;
-        }
-
-        if on_which_sys.dirs() {
-            on_dir_action(&ssh_session)?;
-        }
+        on_dir_action(&ssh_session)?;
     }
 
     Ok(())
@@ -201,31 +632,97 @@ fn perform_action_context_no_dirs<T, C>(
     ssh_credentials: &C,
     readonly: bool,
     on_ldap_action: impl FnOnce(&mut LdapSession<T>) -> AppResult,
-    on_slurm_action: impl FnOnce(&SshConnection<C>) -> AppResult,
+    on_slurm_action: impl FnMut(&MgmtConfig, &SshConnection<C>, Option<&SlurmRestClient>) -> AppResult,
 ) -> AppResult
 where
     T: LdapCredential,
     C: SshCredentials,
 {
-    let ssh_session = SshConnection::from_head_node(config, ssh_credentials.clone());
+    let cluster_configs = if on_which_sys.slurm() {
+        config.slurm_cluster_configs(on_which_sys.clusters())?
+    } else {
+        Vec::new()
+    };
     let mut ldap_session = if readonly {
         LdapSession::from_ldap_readonly_config(config, ldap_credentials)?
     } else {
         LdapSession::new(config, ldap_credentials)?
     };
 
-    if on_which_sys.slurm() {
-        ssh_session.establish_connection()?;
-    }
-
     if on_which_sys.ldap() {
         ldap_session.establish_connection()?;
         on_ldap_action(&mut ldap_session)?;
     }
 
-    if on_which_sys.slurm() {
-        on_slurm_action(&ssh_session)?;
+    perform_on_slurm_clusters(&cluster_configs, ssh_credentials, on_slurm_action)
+}
+
+/// Performs parameter `on_slurm_action` with the configuration, the ssh connection
+/// and the slurmrestd client of every cluster.
+/// The client is created once per cluster if the cluster uses slurmrestd.
+/// See [`SlurmRestClient::from_config_if_selected`]
+///
+/// The ssh connection to the head node of a cluster is established lazily by the first remote command,
+/// so clusters using slurmrestd need none and an unreachable head node only fails its own cluster.
+/// If the clusters are listed in `slurm_clusters`, the action is still performed on the remaining clusters
+/// after it failed on one of them. The outcome is reported per cluster.
+///
+/// # Errors
+///
+/// - If the action fails on at least one cluster.
+fn perform_on_slurm_clusters<C>(
+    cluster_configs: &[MgmtConfig],
+    ssh_credentials: &C,
+    mut on_slurm_action: impl FnMut(
+        &MgmtConfig,
+        &SshConnection<C>,
+        Option<&SlurmRestClient>,
+    ) -> AppResult,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let mut failed_clusters =
+        ResultAccumulator::new("Slurm action failed on at least one cluster".to_owned());
+    for cluster_config in cluster_configs {
+        let session = SshConnection::from_head_node(cluster_config, ssh_credentials.clone());
+        let result = SlurmRestClient::from_config_if_selected(cluster_config)
+            .and_then(|rest| on_slurm_action(cluster_config, &session, rest.as_ref()));
+        let Some(cluster) = &cluster_config.slurm_cluster else {
+            result?;
+            continue;
+        };
+        match result {
+            Ok(()) => info!("Succeeded on Slurm cluster {}", cluster),
+            Err(error) => {
+                error!("Failed on Slurm cluster {}: {:?}", cluster, error);
+                failed_clusters.add_err(format!("Slurm cluster {}: {:#}", cluster, error));
+            }
+        }
     }
+    failed_clusters.into()
+}
 
-    Ok(())
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn notify_about_ldap_step_even_if_later_step_fails() {
+        let mut notified = false;
+        let on_ldap_action = then_on_success(|_: &mut ()| Ok(()), || notified = true);
+        let result = on_ldap_action(&mut ()).and_then(|_| -> AppResult {
+            bail!("Adding user to Slurm failed");
+        });
+        assert!(result.is_err());
+        assert!(notified);
+
+        let mut notified = false;
+        let on_ldap_action = then_on_success(
+            |_: &mut ()| -> AppResult { bail!("Adding user to LDAP failed") },
+            || notified = true,
+        );
+        assert!(on_ldap_action(&mut ()).is_err());
+        assert!(!notified);
+    }
 }
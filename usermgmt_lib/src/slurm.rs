@@ -1,51 +1,178 @@
-use std::process::Command;
+use std::{process::Command, time::Duration};
 
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, ensure, Context};
 use log::{debug, info};
 
 mod command_builder;
-mod listed_user;
-use crate::{config::MgmtConfig, prelude::AppResult, ssh};
+mod sacctmgr_outcome;
+mod slurm_account;
+mod slurm_association;
+mod slurm_jobs;
+mod slurm_qos;
+mod slurm_rest;
+mod slurm_usage;
+mod user_associations;
+use crate::{
+    config::MgmtConfig,
+    prelude::AppResult,
+    util::{SlurmLimit, UserAssociation, ValidQos},
+};
 
+pub(crate) use self::command_builder::quote_for_shell;
 use self::command_builder::CommandBuilder;
 
 use crate::ssh::{SshConnection, SshCredentials};
 use crate::{ChangesToUser, NewEntity};
 
-pub use listed_user::ListedUser;
+pub use command_builder::{AccountProperties, QosProperties, ShowConditions};
+pub use sacctmgr_outcome::{SacctmgrOutcome, SacctmgrOutput};
+pub use slurm_account::{accounts_to_pretty_table, SlurmAccount};
+pub use slurm_association::{associations_to_pretty_table, SlurmAssociation};
+pub use slurm_qos::{qos_to_pretty_table, QosComparison, SlurmQos};
+pub use slurm_rest::SlurmRestClient;
+pub use slurm_usage::{usage_of_user, AccountedUsage, JobUsage, UsagePeriod, UsageReport};
+pub use user_associations::AssociationsOfUser;
 
 /// Creates a user in a slurm database on a remote machine over ssh
+/// or via slurmrestd if parameter `rest` is given. See [`SlurmRestClient::from_config_if_selected`]
 pub fn add_slurm_user<C>(
     entity: &NewEntity,
     config: &MgmtConfig,
     session: &SshConnection<C>,
+    rest: Option<&SlurmRestClient>,
 ) -> AppResult
 where
     C: SshCredentials,
 {
-    let action = CommandBuilder::new_add(
-        entity.username.to_string(),
-        entity.group.id(),
-        entity.default_qos.to_string(),
-        entity.qos.clone().into(),
-    );
+    // Outcome of adding the user and of setting the QOS and limits afterwards
+    let added = match rest {
+        Some(client) => client
+            .add_user(
+                entity.username.as_ref(),
+                &entity.group.id().to_string(),
+                entity.qos.clone().into(),
+                entity.default_qos.to_string(),
+                &entity.slurm_limits,
+            )
+            .map(|_| (SacctmgrOutcome::Created, SacctmgrOutcome::NoChange)),
+        None => {
+            let action = CommandBuilder::new_add(
+                entity.username.to_string(),
+                entity.group.id(),
+                entity.default_qos.to_string(),
+                entity.qos.clone().into(),
+                &entity.slurm_limits,
+            );
+            run_slurm_action_with_outcomes(action, config, session).map(|outputs| {
+                let mut outcomes = outputs.into_iter().map(|output| output.outcome);
+                let added = outcomes.next().unwrap_or(SacctmgrOutcome::NoChange);
+                let modified = outcomes.fold(SacctmgrOutcome::NoChange, SacctmgrOutcome::combine);
+                (added, modified)
+            })
+        }
+    };
 
-    run_slurm_action(action, config, session).with_context(|| {
+    let (added, modified) = added.with_context(|| {
         format!(
             "Failed to add user {} with account {} to Slurm",
             entity.username, entity.group
         )
     })?;
 
-    info!(
-        "Added user {} with account {}, QoS {:?} and default QoS {} to Slurm",
-        entity.username, entity.group, entity.qos, entity.default_qos
-    );
+    if added == SacctmgrOutcome::Created {
+        info!(
+            "Added user {} with account {}, QoS {:?} and default QoS {} to Slurm",
+            entity.username, entity.group, entity.qos, entity.default_qos
+        );
+    } else {
+        info!(
+            "User {} with account {} already existed in Slurm",
+            entity.username, entity.group
+        );
+        if modified == SacctmgrOutcome::Modified {
+            info!(
+                "Modified QoS {:?} and default QoS {} of existing user {} in Slurm",
+                entity.qos, entity.default_qos, entity.username
+            );
+        }
+    }
+    if !entity.slurm_limits.is_empty() {
+        info!(
+            "Set Slurm limits {} for user {}",
+            join_limits(&entity.slurm_limits),
+            entity.username
+        );
+    }
+
+    let username = entity.username.as_ref();
+    add_slurm_associations(username, &entity.associations, config, session, rest)?;
+    if let Some(default_account) = &entity.default_account {
+        set_slurm_default_account(username, default_account.as_ref(), config, session, rest)?;
+    }
 
     Ok(())
 }
 
-/// Deletes a user in a slurm database
+/// Ensures that the user has no pending or running jobs in Slurm before the user is deleted.
+/// Jobs are queried via squeue or via slurmrestd if parameter `rest` is given,
+/// see [`SlurmRestClient::from_config_if_selected`].
+///
+/// If parameter `cancel_jobs` is true, the jobs are cancelled instead
+/// and the queue is checked until they are gone or `cancel_jobs_timeout_secs` of parameter `config` passed.
+///
+/// # Errors
+///
+/// - If the user has jobs and parameter `cancel_jobs` is false.
+/// - If the jobs can not be queried or cancelled.
+/// - If cancelled jobs are still in the queue after the timeout.
+pub fn ensure_no_active_jobs<C>(
+    user: &str,
+    cancel_jobs: bool,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+    rest: Option<&SlurmRestClient>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let active_jobs = || {
+        match rest {
+            Some(client) => client.active_jobs_of_user(user),
+            None => slurm_jobs::active_jobs_of_user(user, config, session),
+        }
+        .with_context(|| format!("Failed to query the jobs of user {} in Slurm", user))
+    };
+
+    let jobs = active_jobs()?;
+    if jobs.is_empty() {
+        return Ok(());
+    }
+    ensure!(
+        cancel_jobs,
+        "User {} still has pending or running jobs in Slurm: {}. Cancel them first or use --cancel-jobs.",
+        user,
+        jobs.join(", ")
+    );
+
+    match rest {
+        Some(client) => client.cancel_jobs_of_user(user),
+        None => slurm_jobs::cancel_jobs_of_user(user, config, session),
+    }
+    .with_context(|| format!("Failed to cancel the jobs of user {} in Slurm", user))?;
+    info!(
+        "Cancelled jobs {} of user {} in Slurm",
+        jobs.join(", "),
+        user
+    );
+    slurm_jobs::wait_until_no_jobs(
+        user,
+        Duration::from_secs(config.cancel_jobs_timeout_secs.into()),
+        Duration::from_secs(slurm_jobs::SECS_BETWEEN_CHECKS),
+        active_jobs,
+    )
+}
+
+/// Deletes a user in a slurm database via sacctmgr or via slurmrestd if parameter `rest` is given
 ///
 /// # Errors
 ///
@@ -54,60 +181,489 @@ pub fn delete_slurm_user<C>(
     user: &str,
     config: &MgmtConfig,
     session: &SshConnection<C>,
+    rest: Option<&SlurmRestClient>,
 ) -> AppResult
 where
     C: SshCredentials,
 {
-    let action = CommandBuilder::new_delete(user.to_string());
-    run_slurm_action(action, config, session)
-        .with_context(|| format!("Failed to delete user {} from Slurm", user))?;
-    info!("Deleted user {} from Slurm", user);
+    let deleted = match rest {
+        Some(client) => client.delete_user(user).map(|_| SacctmgrOutcome::Modified),
+        None => {
+            let action = CommandBuilder::new_delete(user.to_string());
+            run_slurm_action_with_outcome(action, config, session).map(|output| output.outcome)
+        }
+    };
+    let outcome = deleted.with_context(|| format!("Failed to delete user {} from Slurm", user))?;
+    if outcome == SacctmgrOutcome::NoChange {
+        info!("User {} did not exist in Slurm", user);
+    } else {
+        info!("Deleted user {} from Slurm", user);
+    }
     Ok(())
 }
 
 /// Modifies a user in a slurm database via SSH session on a remote machine
-/// It currently only modifies the quality of services of a user !
+/// It only modifies the quality of services, the resource limits and the associations of a user !
+/// Uses slurmrestd instead if parameter `rest` is given.
 ///
 /// # Errors
 ///
+/// - If a QOS is not valid for the cluster of parameter `config`.
 /// - See [`run_slurm_action`]
+/// - See [`modify_slurm_associations`]
 pub fn modify_slurm_user<C>(
     modifiable: &ChangesToUser,
     config: &MgmtConfig,
     session: &SshConnection<C>,
+    rest: Option<&SlurmRestClient>,
 ) -> AppResult
 where
     C: SshCredentials,
 {
-    if let Some((qos, default_qos)) = modifiable.may_qos_and_default_qos() {
-        let action = CommandBuilder::new_modify_qos_default_qos(
-            modifiable.username.to_string(),
-            default_qos,
-            qos,
-        );
+    let qos_and_default_qos = modifiable.may_qos_and_default_qos();
+    if let Some((qos, _)) = &qos_and_default_qos {
+        // The QOS of a cluster in slurm_clusters can differ from the ones validated before.
+        for next in qos.iter() {
+            ValidQos::new(next.clone(), &config.valid_qos)?;
+        }
+    }
+    if qos_and_default_qos.is_some() || !modifiable.slurm_limits.is_empty() {
+        match rest {
+            Some(client) => client.modify_user(
+                modifiable.username.as_ref(),
+                qos_and_default_qos,
+                &modifiable.slurm_limits,
+            )?,
+            None => {
+                let action = CommandBuilder::new_modify_user(
+                    modifiable.username.to_string(),
+                    qos_and_default_qos,
+                    &modifiable.slurm_limits,
+                );
+                run_slurm_action(action, config, session)?;
+            }
+        }
 
-        run_slurm_action(action, config, session)?;
+        if !modifiable.slurm_limits.is_empty() {
+            info!(
+                "Set Slurm limits {} for user {}",
+                join_limits(&modifiable.slurm_limits),
+                modifiable.username
+            );
+        }
+    }
+
+    let changes_associations = !modifiable.associations.is_empty()
+        || !modifiable.removed_associations.is_empty()
+        || modifiable.default_account.is_some();
+    if changes_associations {
+        modify_slurm_associations(modifiable, config, session, rest)?;
     }
     Ok(())
 }
 
-/// Lists all users in slurm database
+/// Adds and removes associations of a user and sets the default account accordingly.
+/// Associations which are neither added nor removed stay in place.
+///
+/// Associations are added first and removed last,
+/// because Slurm does not remove the association with the default account of a user.
 ///
 /// # Errors
 ///
-/// See [`run_slurm_action`]
+/// - If listing the current associations of the user fails. See [`AssociationsOfUser::parse`]
+/// - If the associations can not be changed as requested. See [`AssociationsOfUser::next_default_account`]
+/// - See [`run_slurm_action`]
+pub fn modify_slurm_associations<C>(
+    modifiable: &ChangesToUser,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+    rest: Option<&SlurmRestClient>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let username = modifiable.username.as_ref();
+    let associations = on_cluster_of_config(&modifiable.associations, config);
+    let removed_associations = on_cluster_of_config(&modifiable.removed_associations, config);
+    let existing = match rest {
+        Some(client) => client.associations_of_user(username)?,
+        None => {
+            let action = CommandBuilder::new_show_user_associations(username.to_string());
+            AssociationsOfUser::parse(&run_slurm_action(action, config, session)?)?
+        }
+    };
+    let next_default_account = existing
+        .next_default_account(
+            &associations,
+            &removed_associations,
+            modifiable
+                .default_account
+                .as_ref()
+                .map(|account| account.as_ref().as_str()),
+        )
+        .with_context(|| format!("Failed to change Slurm associations of user {}", username))?;
+
+    add_slurm_associations(username, &associations, config, session, rest)?;
+    if let Some(default_account) = next_default_account {
+        set_slurm_default_account(username, &default_account, config, session, rest)?;
+    }
+    if !removed_associations.is_empty() {
+        let removed = match rest {
+            Some(client) => client.delete_associations(username, &removed_associations),
+            None => {
+                let action = CommandBuilder::new_delete_associations(
+                    username.to_string(),
+                    &removed_associations,
+                );
+                run_slurm_action(action, config, session).map(drop)
+            }
+        };
+        removed
+            .with_context(|| format!("Failed to remove Slurm associations of user {}", username))?;
+        for association in removed_associations.iter() {
+            info!(
+                "Removed Slurm association {} of user {}",
+                association, username
+            );
+        }
+    }
+    Ok(())
+}
+
+fn add_slurm_associations<C>(
+    username: &str,
+    associations: &[UserAssociation],
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+    rest: Option<&SlurmRestClient>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let associations = on_cluster_of_config(associations, config);
+    if associations.is_empty() {
+        return Ok(());
+    }
+    let added = match rest {
+        Some(client) => client.add_associations(username, &associations),
+        None => {
+            let action = CommandBuilder::new_add_associations(username.to_string(), &associations);
+            run_slurm_action(action, config, session).map(drop)
+        }
+    };
+    added.with_context(|| format!("Failed to add Slurm associations to user {}", username))?;
+    for association in associations.iter() {
+        info!(
+            "Added Slurm association {} to user {}",
+            association, username
+        );
+    }
+    Ok(())
+}
+
+/// Returns the associations of parameter `associations` which apply to the Slurm cluster of parameter `config`.
+/// These are all associations if the configuration is not for a cluster in `slurm_clusters`.
+/// Otherwise associations naming another cluster are left out.
+fn on_cluster_of_config(
+    associations: &[UserAssociation],
+    config: &MgmtConfig,
+) -> Vec<UserAssociation> {
+    associations
+        .iter()
+        .filter(
+            |association| match (&config.slurm_cluster, association.cluster()) {
+                (Some(cluster), Some(of_association)) => cluster == of_association,
+                _ => true,
+            },
+        )
+        .cloned()
+        .collect()
+}
+
+fn set_slurm_default_account<C>(
+    username: &str,
+    account: &str,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+    rest: Option<&SlurmRestClient>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let set = match rest {
+        Some(client) => client.set_default_account(username, account),
+        None => {
+            let action =
+                CommandBuilder::new_set_default_account(username.to_string(), account.to_string());
+            run_slurm_action(action, config, session).map(drop)
+        }
+    };
+    set.with_context(|| {
+        format!(
+            "Failed to set default account {} of user {} in Slurm",
+            account, username
+        )
+    })?;
+    info!(
+        "Set default account of user {} to {} in Slurm",
+        username, account
+    );
+    Ok(())
+}
+
+fn join_limits(limits: &[SlurmLimit]) -> String {
+    limits
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Lists the associations of all users in slurm database matching parameter `conditions`
+/// via sacctmgr or via slurmrestd if parameter `rest` is given
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+/// - If the output of sacctmgr is malformed. See [`SlurmAssociation::parse_many`]
 pub fn list_users<T>(
     config: &MgmtConfig,
     session: &SshConnection<T>,
-    parseable: bool,
-) -> AppResult<String>
+    rest: Option<&SlurmRestClient>,
+    conditions: ShowConditions,
+) -> AppResult<Vec<SlurmAssociation>>
 where
     T: SshCredentials,
 {
-    let action = CommandBuilder::new_show(parseable);
+    if let Some(client) = rest {
+        return client.list_users(conditions);
+    }
+    let action = CommandBuilder::new_show_where(conditions);
     let output = run_slurm_action(action, config, session)?;
 
-    Ok(output)
+    SlurmAssociation::parse_many(&output).context("Failed to parse the users listed in Slurm")
+}
+
+/// Adds an account with parameter `properties` to the slurm database
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+pub fn add_slurm_account<C>(
+    name: &str,
+    properties: AccountProperties,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let action = CommandBuilder::new_add_account(name.to_string(), properties);
+    let output = run_slurm_action_with_outcome(action, config, session)
+        .with_context(|| format!("Failed to add account {} to Slurm", name))?;
+    if output.outcome == SacctmgrOutcome::AlreadyExisted {
+        info!("Account {} already existed in Slurm", name);
+    } else {
+        info!("Added account {} to Slurm", name);
+    }
+    Ok(())
+}
+
+/// Sets the given parameter `properties` of an account in the slurm database
+///
+/// # Errors
+///
+/// - If no property is given to set.
+/// - See [`run_slurm_action`]
+pub fn modify_slurm_account<C>(
+    name: &str,
+    properties: AccountProperties,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    if properties.is_empty() {
+        return Err(anyhow!(
+            "No property of the Slurm account {} given to modify",
+            name
+        ));
+    }
+    let action = CommandBuilder::new_modify_account(name.to_string(), properties);
+    run_slurm_action(action, config, session)
+        .with_context(|| format!("Failed to modify account {} in Slurm", name))?;
+    info!("Modified account {} in Slurm", name);
+    Ok(())
+}
+
+/// Deletes an account in the slurm database
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+pub fn delete_slurm_account<C>(
+    name: &str,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let action = CommandBuilder::new_delete_account(name.to_string());
+    run_slurm_action(action, config, session)
+        .with_context(|| format!("Failed to delete account {} from Slurm", name))?;
+    info!("Deleted account {} from Slurm", name);
+    Ok(())
+}
+
+/// Lists all accounts in the slurm database
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+/// - If the output of sacctmgr could not be parsed. See [`SlurmAccount::parse_many`]
+pub fn list_slurm_accounts<C>(
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<Vec<SlurmAccount>>
+where
+    C: SshCredentials,
+{
+    let action = CommandBuilder::new_show_accounts();
+    let output = run_slurm_action(action, config, session)?;
+    SlurmAccount::parse_many(&output)
+}
+
+/// Adds every account of `valid_slurm_groups` from parameter `config` which does not exist yet.
+/// This way a fresh slurm database can be prepared for adding users.
+/// Returns the names of the added accounts.
+///
+/// # Errors
+///
+/// - If listing the existing accounts fails. See [`list_slurm_accounts`]
+/// - If adding an account fails. See [`add_slurm_account`]
+pub fn bootstrap_slurm_accounts<C>(
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<Vec<String>>
+where
+    C: SshCredentials,
+{
+    let existing = list_slurm_accounts(config, session)?;
+    let mut added = Vec::new();
+    for name in config.valid_slurm_groups.iter() {
+        if existing.iter().any(|account| &account.name == name) {
+            debug!("Slurm account {} already exists", name);
+            continue;
+        }
+        add_slurm_account(name, AccountProperties::default(), config, session)?;
+        added.push(name.clone());
+    }
+    Ok(added)
+}
+
+/// Creates a QOS with the limits of parameter `properties` in the slurm database
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+pub fn add_slurm_qos<C>(
+    name: &str,
+    properties: QosProperties,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let action = CommandBuilder::new_add_qos(name.to_string(), properties);
+    let output = run_slurm_action_with_outcome(action, config, session)
+        .with_context(|| format!("Failed to create QOS {} in Slurm", name))?;
+    if output.outcome == SacctmgrOutcome::AlreadyExisted {
+        info!("QOS {} already existed in Slurm", name);
+    } else {
+        info!("Created QOS {} in Slurm", name);
+    }
+    Ok(())
+}
+
+/// Sets the limits of parameter `properties` for a QOS in the slurm database
+///
+/// # Errors
+///
+/// - If no limit is given to set.
+/// - See [`run_slurm_action`]
+pub fn modify_slurm_qos<C>(
+    name: &str,
+    properties: QosProperties,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    if properties.is_empty() {
+        return Err(anyhow!(
+            "No limit of the Slurm QOS {} given to modify",
+            name
+        ));
+    }
+    let action = CommandBuilder::new_modify_qos(name.to_string(), properties);
+    run_slurm_action(action, config, session)
+        .with_context(|| format!("Failed to modify QOS {} in Slurm", name))?;
+    info!("Modified QOS {} in Slurm", name);
+    Ok(())
+}
+
+/// Deletes a QOS in the slurm database
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+pub fn delete_slurm_qos<C>(name: &str, config: &MgmtConfig, session: &SshConnection<C>) -> AppResult
+where
+    C: SshCredentials,
+{
+    let action = CommandBuilder::new_delete_qos(name.to_string());
+    run_slurm_action(action, config, session)
+        .with_context(|| format!("Failed to delete QOS {} from Slurm", name))?;
+    info!("Deleted QOS {} from Slurm", name);
+    Ok(())
+}
+
+/// Lists all QOS in the slurm database
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+/// - If the output of sacctmgr could not be parsed. See [`SlurmQos::parse_many`]
+pub fn list_slurm_qos<C>(
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<Vec<SlurmQos>>
+where
+    C: SshCredentials,
+{
+    let action = CommandBuilder::new_show_qos();
+    let output = run_slurm_action(action, config, session)?;
+    SlurmQos::parse_many(&output)
+}
+
+/// Compares the QOS named in parameter `config` with the ones existing in the slurm database
+///
+/// # Errors
+///
+/// - If listing the QOS fails. See [`list_slurm_qos`]
+pub fn check_slurm_qos<C>(
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<QosComparison>
+where
+    C: SshCredentials,
+{
+    let existing = list_slurm_qos(config, session)?;
+    Ok(QosComparison::new(config, &existing))
 }
 
 /// Runs the slurm command on a local machine or remotely somewhere else.
@@ -116,64 +672,131 @@ where
 /// # Errors
 ///
 /// - If running the command remotely fails. See [`run_remote_report_slurm_cmd`]
-/// - If running the command on the local machine. See [`run_remote_report_slurm_cmd`]
+/// - If running the command on the local machine. See [`run_local_and_report_slurm_cmd`]
 pub fn run_slurm_action<C>(
-    mut actions: CommandBuilder,
+    actions: CommandBuilder,
     config: &MgmtConfig,
     session: &SshConnection<C>,
 ) -> AppResult<String>
 where
     C: SshCredentials,
 {
-    let mut output = String::new();
+    run_slurm_action_with_outcome(actions, config, session).map(|output| output.stdout)
+}
+
+/// Same as [`run_slurm_action`], but returns how sacctmgr reported the outcome too.
+/// If several commands are run, the outcome with the most change is returned.
+/// See [`SacctmgrOutcome::combine`]
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+pub fn run_slurm_action_with_outcome<C>(
+    actions: CommandBuilder,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<SacctmgrOutput>
+where
+    C: SshCredentials,
+{
+    let combined = run_slurm_action_with_outcomes(actions, config, session)?
+        .into_iter()
+        .fold(SacctmgrOutput::default(), |mut combined, next| {
+            combined.outcome = combined.outcome.combine(next.outcome);
+            combined.stdout.push_str(&next.stdout);
+            combined
+        });
+    debug!("Outcome of Slurm command: {:?}", combined.outcome);
+    Ok(combined)
+}
+
+/// Same as [`run_slurm_action_with_outcome`], but returns the output of every command in the order of execution.
+///
+/// # Errors
+///
+/// - See [`run_slurm_action`]
+pub fn run_slurm_action_with_outcomes<C>(
+    mut actions: CommandBuilder,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<Vec<SacctmgrOutput>>
+where
+    C: SshCredentials,
+{
     actions = actions
         .immediate(true)
-        .sacctmgr_path(config.sacctmgr_path.clone());
+        .sacctmgr_path(config.sacctmgr_path.clone())
+        .cluster(config.slurm_cluster.clone());
     if config.run_slurm_remote {
-        for cmd in actions.remote_commands() {
-            debug!("Running remote Slurm command: {}", &cmd);
-            let next_output = run_remote_report_slurm_cmd(session, &cmd)?;
-            output.push_str(&next_output);
-        }
+        actions
+            .remote_commands()
+            .into_iter()
+            .map(|cmd| {
+                debug!("Running remote Slurm command: {}", &cmd);
+                run_remote_report_slurm_cmd(session, &cmd)
+            })
+            .collect()
     } else {
-        for cmd in actions.local_commands() {
-            let next_output = run_local_and_report_slurm_cmd(cmd)?;
-            output.push_str(&next_output);
-        }
+        actions
+            .local_commands()
+            .into_iter()
+            .map(run_local_and_report_slurm_cmd)
+            .collect()
     }
-    Ok(output)
 }
 
 /// # Errors
 ///
-/// - If execution of the command fails. See [`SshConnection::exec`].
-/// - If the exit code of executed command is an error code.
-pub fn run_remote_report_slurm_cmd<C>(session: &SshConnection<C>, cmd: &str) -> AppResult<String>
+/// - If execution of the command fails. See [`SshConnection::exec_with_stderr`].
+/// - If sacctmgr reports an error. See [`SacctmgrOutcome::classify`]
+pub fn run_remote_report_slurm_cmd<C>(
+    session: &SshConnection<C>,
+    cmd: &str,
+) -> AppResult<SacctmgrOutput>
 where
     C: SshCredentials,
 {
-    let (exit_code, output) = ssh::run_remote_command(session, cmd)
+    let (stdout, stderr, exit_code) = session
+        .exec_with_stderr(cmd)
         .with_context(|| format!("Error during remote Slurm command execution ({}).", cmd,))?;
 
-    if exit_code == 0 {
-        debug!("Successfully executed remote Slurm command:{}", cmd);
-        Ok(output)
-    } else {
-        Err(anyhow!(
-            "Error during remote Slurm command execution! Command '{}' returned exit code {}",
-            cmd,
-            exit_code
-        ))
-    }
+    let output = outcome_to_result(
+        cmd,
+        SacctmgrOutcome::classify(exit_code, &stdout, &stderr),
+        stdout,
+    )?;
+    debug!("Successfully executed remote Slurm command:{}", cmd);
+    Ok(output)
 }
 
 /// # Errors
 ///
 /// - If output of command could not be retrieved
-pub fn run_local_and_report_slurm_cmd(mut command: Command) -> AppResult<String> {
+/// - If sacctmgr reports an error. See [`SacctmgrOutcome::classify`]
+pub fn run_local_and_report_slurm_cmd(mut command: Command) -> AppResult<SacctmgrOutput> {
     let output = command.output().context(
         "Unable to execute sacctmgr command. Is the path specified in your config correct?",
     )?;
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // Exit code is none if terminated by a signal.
+    let exit_code = output.status.code().unwrap_or(-1);
+    let outcome = SacctmgrOutcome::classify(exit_code, &stdout, &stderr);
+    outcome_to_result(&format!("{:?}", command), outcome, stdout)
+}
+
+fn outcome_to_result(
+    cmd: &str,
+    outcome: SacctmgrOutcome,
+    stdout: String,
+) -> AppResult<SacctmgrOutput> {
+    match outcome {
+        SacctmgrOutcome::Error(message) => Err(anyhow!(
+            "Error during Slurm command execution! Command '{}' failed. {}",
+            cmd,
+            message
+        )),
+        outcome => Ok(SacctmgrOutput { outcome, stdout }),
+    }
 }
@@ -1,5 +1,6 @@
+use crate::util::{SlurmLimit, UserAssociation};
 use crate::Group;
-use std::collections::HashMap;
+use std::borrow::Cow;
 use std::iter;
 use std::process::Command;
 
@@ -11,55 +12,367 @@ const SUB_COMMAND_MODIFY: &str = "modify";
 const SUB_COMMAND_DELETE: &str = "delete";
 
 const SET: &str = "set";
+const WHERE: &str = "where";
 const ASSOCIATION: &str = "assoc";
 const USER: &str = "User";
 const ACCOUNT: &str = "Account";
 const DEFAULT_QOS: &str = "DefaultQOS";
 const QOS: &str = "QOS";
-const SLURM_PRASEABLE_ARG: &str = "--parsable";
+const USERS: &str = "Users";
+const ACCOUNTS: &str = "Accounts";
+const SLURM_PARSABLE2_ARG: &str = "--parsable2";
+const NO_HEADER_ARG: &str = "--noheader";
+const ACCOUNT_ENTITY: &str = "account";
+const WITH_ASSOCIATIONS: &str = "withassoc";
+const NAME: &str = "Name";
+const PARENT: &str = "Parent";
+const PARENT_NAME: &str = "ParentName";
+const ORGANIZATION: &str = "Organization";
+const DESCRIPTION: &str = "Description";
+const FAIRSHARE: &str = "Fairshare";
+const QOS_ENTITY: &str = "qos";
+const PRIORITY: &str = "Priority";
+const MAX_WALL: &str = "MaxWall";
+const MAX_TRES_PER_USER: &str = "MaxTRESPerUser";
+const PARTITION: &str = "Partition";
+const CLUSTER: &str = "Cluster";
+const DEFAULT_ACCOUNT: &str = "DefaultAccount";
+const CLUSTERS: &str = "Clusters";
 
 enum SlurmSubCommand {
-    Add { group: Group },
+    Add {
+        group: Group,
+    },
     Delete,
-    Modify(HashMap<&'static str, Vec<String>>),
-    Show { parseable: bool },
+    /// Settings of the association of a user as pairs of name and value
+    Modify(Vec<(&'static str, String)>),
+    Show {
+        conditions: Vec<String>,
+    },
+    AddAssociation(UserAssociation),
+    DeleteAssociation(UserAssociation),
+    ShowUserAssociations,
+    AddAccount {
+        name: String,
+        properties: AccountProperties,
+    },
+    ModifyAccount {
+        name: String,
+        properties: AccountProperties,
+    },
+    DeleteAccount {
+        name: String,
+    },
+    ShowAccounts,
+    AddQos {
+        name: String,
+        properties: QosProperties,
+    },
+    ModifyQos {
+        name: String,
+        properties: QosProperties,
+    },
+    DeleteQos {
+        name: String,
+    },
+    ShowQos,
 }
 
-fn from_username(value: SlurmSubCommand, username: String) -> Vec<String> {
+/// Properties of a Slurm account which are set when adding or modifying it.
+/// Properties without a value are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountProperties {
+    /// Parent account within the hierarchy of accounts. Slurm uses root if none is given.
+    pub parent: Option<String>,
+    pub organization: Option<String>,
+    pub description: Option<String>,
+    /// Share of the parent account used for the fair share scheduling
+    pub fairshare: Option<u32>,
+}
+
+impl AccountProperties {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    fn into_args(self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(parent) = self.parent {
+            args.push(format!("{}={}", PARENT, parent));
+        }
+        if let Some(organization) = self.organization {
+            args.push(format!("{}={}", ORGANIZATION, organization));
+        }
+        if let Some(description) = self.description {
+            args.push(format!("{}={}", DESCRIPTION, description));
+        }
+        if let Some(fairshare) = self.fairshare {
+            args.push(format!("{}={}", FAIRSHARE, fairshare));
+        }
+        args
+    }
+}
+
+/// Limits and properties of a QOS in Slurm which are set when creating or modifying it.
+/// Properties without a value are left out.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QosProperties {
+    /// Priority of jobs with this QOS
+    pub priority: Option<u32>,
+    /// Maximum wall clock time of a job e.g. 2-00:00:00 for 2 days
+    pub max_wall: Option<String>,
+    /// Maximum trackable resources of all running jobs of a user e.g. cpu=16,gres/gpu=2
+    pub max_tres_per_user: Option<String>,
+    pub description: Option<String>,
+}
+
+impl QosProperties {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    fn into_args(self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(priority) = self.priority {
+            args.push(format!("{}={}", PRIORITY, priority));
+        }
+        if let Some(max_wall) = self.max_wall {
+            args.push(format!("{}={}", MAX_WALL, max_wall));
+        }
+        if let Some(max_tres_per_user) = self.max_tres_per_user {
+            args.push(format!("{}={}", MAX_TRES_PER_USER, max_tres_per_user));
+        }
+        if let Some(description) = self.description {
+            args.push(format!("{}={}", DESCRIPTION, description));
+        }
+        args
+    }
+}
+
+/// Conditions for listing only some associations via a where clause of sacctmgr.
+/// Every given condition must match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShowConditions {
+    /// Only associations of these users
+    pub users: Option<Vec<String>>,
+    /// Only associations of this account aka group
+    pub account: Option<Group>,
+    /// Only associations with this QOS
+    pub qos: Option<String>,
+}
+
+impl ShowConditions {
+    fn into_args(self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(users) = self.users {
+            args.push(format!("{}={}", USERS, users.join(",")));
+        }
+        if let Some(account) = self.account {
+            args.push(format!("{}={}", ACCOUNTS, account));
+        }
+        if let Some(qos) = self.qos {
+            args.push(format!("{}={}", QOS, qos));
+        }
+        args
+    }
+}
+
+/// Account, partition and cluster of parameter `association` as arguments of sacctmgr.
+/// Only the given partition and cluster are included.
+/// Parameter `cluster` is used if the association does not name a cluster.
+fn association_args(association: &UserAssociation, cluster: Option<&str>) -> Vec<String> {
+    let mut args = vec![format!("{}={}", ACCOUNT, association.account())];
+    if let Some(partition) = association.partition() {
+        args.push(format!("{}={}", PARTITION, partition));
+    }
+    if let Some(cluster) = association.cluster().or(cluster) {
+        args.push(format!("{}={}", CLUSTER, cluster));
+    }
+    args
+}
+
+/// Where clause restricting a command to parameter `cluster` if given
+fn where_cluster(cluster: Option<&str>) -> Vec<String> {
+    cluster
+        .map(|cluster| vec![WHERE.into(), format!("{}={}", CLUSTER, cluster)])
+        .unwrap_or_default()
+}
+
+/// Parameter `cluster` restricts the commands on users and accounts to this cluster.
+fn from_username(value: SlurmSubCommand, username: String, cluster: Option<&str>) -> Vec<String> {
     match value {
         SlurmSubCommand::Add { group } => {
-            vec![
+            let mut command = vec![
                 SUB_COMMAND_ADD.into(),
                 USER.into(),
                 username,
                 format!("{}={}", ACCOUNT, group.to_string()),
-            ]
+            ];
+            if let Some(cluster) = cluster {
+                command.push(format!("{}={}", CLUSTER, cluster));
+            }
+            command
         }
-        SlurmSubCommand::Delete => vec![SUB_COMMAND_DELETE.into(), USER.into(), username],
-        SlurmSubCommand::Modify(map) => {
-            let mut to_set: Vec<String> = map
+        SlurmSubCommand::Delete => vec![SUB_COMMAND_DELETE.into(), USER.into(), username]
+            .into_iter()
+            .chain(where_cluster(cluster))
+            .collect(),
+        SlurmSubCommand::Modify(settings) => {
+            let mut to_set: Vec<String> = settings
                 .into_iter()
-                .map(|(key, values)| format!("{}={}", key, values.join(",")))
+                .map(|(key, value)| format!("{}={}", key, value))
                 .collect();
             to_set.sort();
-            vec![SUB_COMMAND_MODIFY.into(), USER.into(), username, SET.into()]
+            vec![SUB_COMMAND_MODIFY.into(), USER.into(), username]
                 .into_iter()
+                .chain(where_cluster(cluster))
+                .chain(iter::once(SET.into()))
                 .chain(to_set)
                 .collect()
         }
-        SlurmSubCommand::Show { parseable } => {
-            let mut command = if parseable {
-                vec![SLURM_PRASEABLE_ARG.to_owned()]
-            } else {
-                Vec::new()
-            };
-            command.extend_from_slice(&[
+        SlurmSubCommand::Show { mut conditions } => {
+            let mut command = vec![
+                SLURM_PARSABLE2_ARG.into(),
+                NO_HEADER_ARG.into(),
                 SUB_COMMAND_SHOW.into(),
                 ASSOCIATION.into(),
-                format!("format={}%30,{},{},{}%80", USER, ACCOUNT, DEFAULT_QOS, QOS),
-            ]);
+            ];
+            if let Some(cluster) = cluster {
+                conditions.push(format!("{}={}", CLUSTERS, cluster));
+            }
+            if !conditions.is_empty() {
+                command.push(WHERE.into());
+                command.extend(conditions);
+            }
+            let fields: Vec<&str> = [USER, ACCOUNT, PARTITION, CLUSTER, DEFAULT_QOS, QOS]
+                .into_iter()
+                .chain(SlurmLimit::names())
+                .collect();
+            command.push(format!("format={}", fields.join(",")));
+            command
+        }
+        SlurmSubCommand::AddAssociation(association) => {
+            let mut command = vec![SUB_COMMAND_ADD.into(), USER.into(), username];
+            command.extend(association_args(&association, cluster));
+            if !association.qos().is_empty() {
+                let qos: Vec<&str> = association
+                    .qos()
+                    .iter()
+                    .map(|qos| qos.as_ref().as_str())
+                    .collect();
+                command.push(format!("{}={}", QOS, qos.join(",")));
+            }
             command
         }
+        SlurmSubCommand::DeleteAssociation(association) => vec![
+            SUB_COMMAND_DELETE.into(),
+            USER.into(),
+            username,
+            WHERE.into(),
+        ]
+        .into_iter()
+        .chain(association_args(&association, cluster))
+        .collect(),
+        SlurmSubCommand::ShowUserAssociations => vec![
+            SLURM_PARSABLE2_ARG.into(),
+            NO_HEADER_ARG.into(),
+            SUB_COMMAND_SHOW.into(),
+            USER.into(),
+            username,
+            WITH_ASSOCIATIONS.into(),
+        ]
+        .into_iter()
+        .chain(where_cluster(cluster))
+        .chain(iter::once(format!(
+            "format={},{},{},{}",
+            DEFAULT_ACCOUNT, ACCOUNT, PARTITION, CLUSTER
+        )))
+        .collect(),
+        SlurmSubCommand::AddAccount { name, properties } => {
+            let mut command: Vec<String> =
+                vec![SUB_COMMAND_ADD.into(), ACCOUNT_ENTITY.into(), name]
+                    .into_iter()
+                    .chain(properties.into_args())
+                    .collect();
+            if let Some(cluster) = cluster {
+                command.push(format!("{}={}", CLUSTER, cluster));
+            }
+            command
+        }
+        SlurmSubCommand::ModifyAccount { name, properties } => {
+            let mut command = vec![
+                SUB_COMMAND_MODIFY.into(),
+                ACCOUNT_ENTITY.into(),
+                WHERE.into(),
+                format!("{}={}", NAME, name),
+            ];
+            if let Some(cluster) = cluster {
+                command.push(format!("{}={}", CLUSTER, cluster));
+            }
+            command.push(SET.into());
+            command.extend(properties.into_args());
+            command
+        }
+        SlurmSubCommand::DeleteAccount { name } => {
+            vec![SUB_COMMAND_DELETE.into(), ACCOUNT_ENTITY.into(), name]
+                .into_iter()
+                .chain(where_cluster(cluster))
+                .collect()
+        }
+        SlurmSubCommand::ShowAccounts => vec![
+            SLURM_PARSABLE2_ARG.into(),
+            NO_HEADER_ARG.into(),
+            SUB_COMMAND_SHOW.into(),
+            ACCOUNT_ENTITY.into(),
+            WITH_ASSOCIATIONS.into(),
+        ]
+        .into_iter()
+        .chain(where_cluster(cluster))
+        .chain(iter::once(format!(
+            "format={},{},{},{},{},{}",
+            ACCOUNT, DESCRIPTION, ORGANIZATION, PARENT_NAME, FAIRSHARE, USER
+        )))
+        .collect(),
+        SlurmSubCommand::AddQos { name, properties } => {
+            vec![SUB_COMMAND_ADD.into(), QOS_ENTITY.into(), name]
+                .into_iter()
+                .chain(properties.into_args())
+                .collect()
+        }
+        SlurmSubCommand::ModifyQos { name, properties } => vec![
+            SUB_COMMAND_MODIFY.into(),
+            QOS_ENTITY.into(),
+            WHERE.into(),
+            format!("{}={}", NAME, name),
+            SET.into(),
+        ]
+        .into_iter()
+        .chain(properties.into_args())
+        .collect(),
+        SlurmSubCommand::DeleteQos { name } => {
+            vec![SUB_COMMAND_DELETE.into(), QOS_ENTITY.into(), name]
+        }
+        SlurmSubCommand::ShowQos => vec![
+            SLURM_PARSABLE2_ARG.into(),
+            NO_HEADER_ARG.into(),
+            SUB_COMMAND_SHOW.into(),
+            QOS_ENTITY.into(),
+            format!(
+                "format={},{},{},{},{}",
+                NAME, PRIORITY, MAX_WALL, MAX_TRES_PER_USER, DESCRIPTION
+            ),
+        ],
+    }
+}
+
+/// Quotes parameter `arg` for a POSIX shell if it contains characters with a special meaning,
+/// e.g. white spaces within the description of an account.
+pub(crate) fn quote_for_shell(arg: &str) -> Cow<'_, str> {
+    let is_safe = |char: char| char.is_ascii_alphanumeric() || "_-=%,./:@+".contains(char);
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        Cow::Borrowed(arg)
+    } else {
+        Cow::Owned(format!("'{}'", arg.replace('\'', r"'\''")))
     }
 }
 
@@ -71,6 +384,7 @@ pub struct CommandBuilder {
     username: String,
     immediate: bool,
     sacctmgr_path: String,
+    cluster: Option<String>,
 }
 
 impl CommandBuilder {
@@ -78,33 +392,144 @@ impl CommandBuilder {
         Self::new_inner(username, vec![SlurmSubCommand::Delete])
     }
 
-    pub fn new_show(parseable: bool) -> Self {
+    pub fn new_show() -> Self {
+        Self::new_show_where(ShowConditions::default())
+    }
+
+    /// Lists only the associations matching parameter `conditions`.
+    /// The output is parsed via [`super::SlurmAssociation::parse_many`].
+    pub fn new_show_where(conditions: ShowConditions) -> Self {
+        Self::new_inner(
+            Default::default(),
+            vec![SlurmSubCommand::Show {
+                conditions: conditions.into_args(),
+            }],
+        )
+    }
+
+    /// Adds an association of user aka parameter `username` for every element in parameter `associations`.
+    /// The other associations of the user stay in place.
+    pub fn new_add_associations(username: String, associations: &[UserAssociation]) -> Self {
+        let sub_commands = associations
+            .iter()
+            .cloned()
+            .map(SlurmSubCommand::AddAssociation)
+            .collect();
+        Self::new_inner(username, sub_commands)
+    }
+
+    /// Deletes the associations of user aka parameter `username` matching the elements in parameter `associations`.
+    /// The other associations of the user stay in place.
+    pub fn new_delete_associations(username: String, associations: &[UserAssociation]) -> Self {
+        let sub_commands = associations
+            .iter()
+            .cloned()
+            .map(SlurmSubCommand::DeleteAssociation)
+            .collect();
+        Self::new_inner(username, sub_commands)
+    }
+
+    pub fn new_set_default_account(username: String, account: String) -> Self {
+        Self::new_inner(
+            username,
+            vec![SlurmSubCommand::Modify(vec![(DEFAULT_ACCOUNT, account)])],
+        )
+    }
+
+    /// Lists the associations of user aka parameter `username` in the format of `--parsable2` without header.
+    /// Columns: default account, account, partition and cluster.
+    pub fn new_show_user_associations(username: String) -> Self {
+        Self::new_inner(username, vec![SlurmSubCommand::ShowUserAssociations])
+    }
+
+    pub fn new_add_account(name: String, properties: AccountProperties) -> Self {
+        Self::new_inner(
+            Default::default(),
+            vec![SlurmSubCommand::AddAccount { name, properties }],
+        )
+    }
+
+    pub fn new_modify_account(name: String, properties: AccountProperties) -> Self {
+        Self::new_inner(
+            Default::default(),
+            vec![SlurmSubCommand::ModifyAccount { name, properties }],
+        )
+    }
+
+    pub fn new_delete_account(name: String) -> Self {
+        Self::new_inner(
+            Default::default(),
+            vec![SlurmSubCommand::DeleteAccount { name }],
+        )
+    }
+
+    /// Lists all accounts with their associations in the format of `--parsable2` without header.
+    /// Columns: account, description, organization, parent, fairshare and user.
+    pub fn new_show_accounts() -> Self {
+        Self::new_inner(Default::default(), vec![SlurmSubCommand::ShowAccounts])
+    }
+
+    pub fn new_add_qos(name: String, properties: QosProperties) -> Self {
         Self::new_inner(
             Default::default(),
-            vec![SlurmSubCommand::Show { parseable }],
+            vec![SlurmSubCommand::AddQos { name, properties }],
         )
     }
 
-    #[cfg(test)]
-    pub fn new_modify(username: String, modifier: HashMap<&'static str, Vec<String>>) -> Self {
-        Self::new_inner(username, vec![SlurmSubCommand::Modify(modifier)])
+    pub fn new_modify_qos(name: String, properties: QosProperties) -> Self {
+        Self::new_inner(
+            Default::default(),
+            vec![SlurmSubCommand::ModifyQos { name, properties }],
+        )
+    }
+
+    pub fn new_delete_qos(name: String) -> Self {
+        Self::new_inner(
+            Default::default(),
+            vec![SlurmSubCommand::DeleteQos { name }],
+        )
+    }
+
+    /// Lists all QOS in the format of `--parsable2` without header.
+    /// Columns: name, priority, max wall time, max TRES per user and description.
+    pub fn new_show_qos() -> Self {
+        Self::new_inner(Default::default(), vec![SlurmSubCommand::ShowQos])
     }
 
-    /// Adds a command to modify the default quality of service for user aka parameter `username`
-    pub fn new_modify_qos_default_qos(
+    /// Adds a command to modify the quality of services and/or the resource limits
+    /// for user aka parameter `username`.
+    /// The quality of services are left unchanged if parameter `qos_and_default_qos` is none.
+    pub fn new_modify_user(
         username: String,
-        default_qos: String,
-        qos: Vec<String>,
+        qos_and_default_qos: Option<(Vec<String>, String)>,
+        limits: &[SlurmLimit],
     ) -> Self {
-        let command = Self::create_modify_command(default_qos, qos);
-        Self::new_inner(username, vec![command])
+        let mut settings = match qos_and_default_qos {
+            Some((qos, default_qos)) => Self::qos_settings(default_qos, qos),
+            None => Vec::new(),
+        };
+        settings.extend(Self::limit_settings(limits));
+        Self::new_inner(username, vec![SlurmSubCommand::Modify(settings)])
     }
 
-    pub fn new_add(username: String, group: Group, default_qos: String, qos: Vec<String>) -> Self {
+    pub fn new_add(
+        username: String,
+        group: Group,
+        default_qos: String,
+        qos: Vec<String>,
+        limits: &[SlurmLimit],
+    ) -> Self {
         // Note: The order of execution is important here!
         // Slurm expects the user to have QOS, before it can set the default QOS
-        let mod_command = Self::create_modify_command(default_qos, qos);
-        Self::new_inner(username, vec![SlurmSubCommand::Add { group }, mod_command])
+        let mut settings = Self::qos_settings(default_qos, qos);
+        settings.extend(Self::limit_settings(limits));
+        Self::new_inner(
+            username,
+            vec![
+                SlurmSubCommand::Add { group },
+                SlurmSubCommand::Modify(settings),
+            ],
+        )
     }
 
     pub fn immediate(mut self, immediate: bool) -> Self {
@@ -117,20 +542,37 @@ impl CommandBuilder {
         self
     }
 
+    /// Restricts the commands on users, their associations and accounts to the Slurm cluster aka parameter `cluster`.
+    /// Commands on QOS are not restricted since a QOS is shared by all clusters of a Slurm database.
+    pub fn cluster(mut self, cluster: Option<String>) -> Self {
+        self.cluster = cluster;
+        self
+    }
+
     pub fn remote_commands(self) -> Vec<String> {
-        let args = Self::construct_args(self.username, self.immediate, self.sub_commands);
+        let args = Self::construct_args(
+            self.username,
+            self.immediate,
+            self.cluster.as_deref(),
+            self.sub_commands,
+        );
         args.into_iter()
             .map(|args| {
                 let mut command = Vec::with_capacity(args.len() + 1);
                 command.push(self.sacctmgr_path.to_owned());
-                command.extend(args);
+                command.extend(args.iter().map(|arg| quote_for_shell(arg).into_owned()));
                 command.join(" ")
             })
             .collect()
     }
 
     pub fn local_commands(self) -> Vec<Command> {
-        let args = Self::construct_args(self.username, self.immediate, self.sub_commands);
+        let args = Self::construct_args(
+            self.username,
+            self.immediate,
+            self.cluster.as_deref(),
+            self.sub_commands,
+        );
         args.into_iter()
             .map(|args| {
                 let mut command = Command::new(&self.sacctmgr_path);
@@ -146,23 +588,30 @@ impl CommandBuilder {
             username,
             immediate: false,
             sacctmgr_path: SACCTMG_NAME.to_owned(),
+            cluster: None,
         }
     }
 
-    fn create_modify_command(default_qos: String, qos: Vec<String>) -> SlurmSubCommand {
-        let map = HashMap::from_iter([(DEFAULT_QOS, vec![default_qos]), (QOS, qos)]);
-        SlurmSubCommand::Modify(map)
+    fn qos_settings(default_qos: String, qos: Vec<String>) -> Vec<(&'static str, String)> {
+        vec![(DEFAULT_QOS, default_qos), (QOS, qos.join(","))]
+    }
+
+    fn limit_settings(limits: &[SlurmLimit]) -> impl Iterator<Item = (&'static str, String)> + '_ {
+        limits
+            .iter()
+            .map(|limit| (limit.name(), limit.value().to_string()))
     }
 
     fn construct_args(
         username: String,
         immediate: bool,
+        cluster: Option<&str>,
         sub_commands: Vec<SlurmSubCommand>,
     ) -> Vec<Vec<String>> {
         sub_commands
             .into_iter()
             .map(|command| {
-                let args = from_username(command, username.to_owned());
+                let args = from_username(command, username.to_owned(), cluster);
                 if immediate {
                     args.into_iter()
                         .chain(iter::once(IMMEDIATE.to_owned()))
@@ -185,6 +634,7 @@ mod testing {
             Group::Staff,
             "student".to_owned(),
             vec!["student".into(), "worker".into()],
+            &[],
         );
 
         let actual = input.remote_commands();
@@ -197,6 +647,7 @@ mod testing {
             Group::Staff,
             "student".to_owned(),
             vec!["student".into(), "worker".into()],
+            &[],
         )
         .immediate(true);
 
@@ -222,26 +673,212 @@ mod testing {
 
     #[test]
     fn list_user() {
-        let input = CommandBuilder::new_show(false).sacctmgr_path("some_path/sacctmgr".to_owned());
+        let input = CommandBuilder::new_show().sacctmgr_path("some_path/sacctmgr".to_owned());
         let actual = input.remote_commands();
         insta::assert_debug_snapshot!(actual);
     }
 
     #[test]
-    fn list_user_parserable() {
-        let input = CommandBuilder::new_show(true).sacctmgr_path("some_path/sacctmgr".to_owned());
+    fn list_user_where() {
+        let conditions = ShowConditions {
+            users: Some(vec!["alice".to_string(), "bob".to_string()]),
+            account: Some(Group::Staff),
+            qos: Some("basic".to_string()),
+        };
+        let input = CommandBuilder::new_show_where(conditions)
+            .sacctmgr_path("some_path/sacctmgr".to_owned());
         let actual = input.remote_commands();
         insta::assert_debug_snapshot!(actual);
     }
 
+    #[test]
+    fn manage_accounts() {
+        let properties = AccountProperties {
+            parent: Some("root".to_string()),
+            organization: Some("th".to_string()),
+            description: Some("Staff of the university".to_string()),
+            fairshare: Some(10),
+        };
+        let actual: Vec<String> = [
+            CommandBuilder::new_add_account("staff".to_string(), properties),
+            CommandBuilder::new_modify_account(
+                "student".to_string(),
+                AccountProperties {
+                    description: Some("Student's account".to_string()),
+                    ..Default::default()
+                },
+            ),
+            CommandBuilder::new_delete_account("staff".to_string()),
+            CommandBuilder::new_show_accounts(),
+        ]
+        .into_iter()
+        .flat_map(|builder| {
+            builder
+                .sacctmgr_path("some_path/sacctmgr".to_owned())
+                .remote_commands()
+        })
+        .collect();
+        insta::assert_debug_snapshot!(actual);
+    }
+
+    #[test]
+    fn manage_qos() {
+        let properties = QosProperties {
+            priority: Some(100),
+            max_wall: Some("2-00:00:00".to_string()),
+            max_tres_per_user: Some("cpu=16,gres/gpu=2".to_string()),
+            description: None,
+        };
+        let actual: Vec<String> = [
+            CommandBuilder::new_add_qos("interactive".to_string(), properties),
+            CommandBuilder::new_modify_qos(
+                "basic".to_string(),
+                QosProperties {
+                    priority: Some(10),
+                    ..Default::default()
+                },
+            ),
+            CommandBuilder::new_delete_qos("basic".to_string()),
+            CommandBuilder::new_show_qos(),
+        ]
+        .into_iter()
+        .flat_map(|builder| {
+            builder
+                .sacctmgr_path("some_path/sacctmgr".to_owned())
+                .remote_commands()
+        })
+        .collect();
+        insta::assert_debug_snapshot!(actual);
+    }
+
+    #[test]
+    fn manage_associations_of_user() {
+        let associations: Vec<UserAssociation> = [
+            "account=project-a,partition=gpu,cluster=alpha,qos=basic,qos=interactive",
+            "project-b",
+        ]
+        .into_iter()
+        .map(|association| association.parse().expect("Should be a valid association"))
+        .collect();
+        let actual: Vec<String> = [
+            CommandBuilder::new_add_associations("somebody".to_owned(), &associations),
+            CommandBuilder::new_set_default_account("somebody".to_owned(), "project-b".to_owned()),
+            CommandBuilder::new_delete_associations("somebody".to_owned(), &associations),
+            CommandBuilder::new_show_user_associations("somebody".to_owned()),
+        ]
+        .into_iter()
+        .flat_map(|builder| {
+            builder
+                .sacctmgr_path("some_path/sacctmgr".to_owned())
+                .remote_commands()
+        })
+        .collect();
+        insta::assert_debug_snapshot!(actual);
+    }
+
+    #[test]
+    fn restrict_commands_on_users_to_cluster() {
+        let associations: Vec<UserAssociation> = ["project-a", "account=project-b,cluster=beta"]
+            .into_iter()
+            .map(|association| association.parse().expect("Should be a valid association"))
+            .collect();
+        let actual: Vec<String> = [
+            CommandBuilder::new_add(
+                "somebody".to_owned(),
+                Group::Staff,
+                "basic".to_owned(),
+                vec!["basic".into()],
+                &[],
+            ),
+            CommandBuilder::new_delete("somebody".to_owned()),
+            CommandBuilder::new_show_where(ShowConditions {
+                users: Some(vec!["somebody".to_string()]),
+                ..Default::default()
+            }),
+            CommandBuilder::new_add_associations("somebody".to_owned(), &associations),
+            CommandBuilder::new_delete_associations("somebody".to_owned(), &associations),
+            CommandBuilder::new_show_user_associations("somebody".to_owned()),
+        ]
+        .into_iter()
+        .flat_map(|builder| {
+            builder
+                .sacctmgr_path("some_path/sacctmgr".to_owned())
+                .cluster(Some("alpha".to_owned()))
+                .remote_commands()
+        })
+        .collect();
+        insta::assert_debug_snapshot!(actual);
+    }
+
+    #[test]
+    fn restrict_commands_on_accounts_to_cluster() {
+        let actual: Vec<String> = [
+            CommandBuilder::new_add_account(
+                "project-a".to_owned(),
+                AccountProperties {
+                    parent: Some("root".to_string()),
+                    ..Default::default()
+                },
+            ),
+            CommandBuilder::new_modify_account(
+                "project-a".to_owned(),
+                AccountProperties {
+                    fairshare: Some(10),
+                    ..Default::default()
+                },
+            ),
+            CommandBuilder::new_delete_account("project-a".to_owned()),
+            CommandBuilder::new_show_accounts(),
+            CommandBuilder::new_delete_qos("basic".to_owned()),
+        ]
+        .into_iter()
+        .flat_map(|builder| {
+            builder
+                .sacctmgr_path("some_path/sacctmgr".to_owned())
+                .cluster(Some("alpha".to_owned()))
+                .remote_commands()
+        })
+        .collect();
+        insta::assert_debug_snapshot!(actual);
+    }
+
     #[test]
     fn modify_user() {
-        let map: HashMap<&'static str, _> = HashMap::from_iter([
-            (QOS, vec!["basic".to_string(), "interactive".to_string()]),
-            (DEFAULT_QOS, vec!["basic".to_string()]),
-        ]);
-        let input = CommandBuilder::new_modify("somebody".to_owned(), map);
+        let input = CommandBuilder::new_modify_user(
+            "somebody".to_owned(),
+            Some((
+                vec!["basic".to_string(), "interactive".to_string()],
+                "basic".to_string(),
+            )),
+            &[],
+        );
         let actual = input.remote_commands();
         insta::assert_debug_snapshot!(actual);
     }
+
+    #[test]
+    fn add_and_modify_user_with_limits() {
+        let limits: Vec<SlurmLimit> = ["GrpTRES=cpu=64,gres/gpu=2", "MaxJobs=10"]
+            .into_iter()
+            .map(|limit| limit.parse().expect("Should be a valid limit"))
+            .collect();
+        let actual: Vec<String> = [
+            CommandBuilder::new_add(
+                "somebody".to_owned(),
+                Group::Student,
+                "basic".to_owned(),
+                vec!["basic".into()],
+                &limits,
+            ),
+            CommandBuilder::new_modify_user("somebody".to_owned(), None, &limits),
+        ]
+        .into_iter()
+        .flat_map(|builder| {
+            builder
+                .sacctmgr_path("some_path/sacctmgr".to_owned())
+                .remote_commands()
+        })
+        .collect();
+        insta::assert_debug_snapshot!(actual);
+    }
 }
@@ -0,0 +1,216 @@
+/// Messages of sacctmgr, in lower case, if an entity to add exists already
+const ALREADY_EXISTED: [&str; 2] = ["already exists", "nothing new added"];
+/// Messages of sacctmgr, in lower case, if nothing matched the entities to modify or delete
+const NO_CHANGE: [&str; 2] = ["nothing modified", "nothing deleted"];
+const CREATED: [&str; 1] = ["adding"];
+const MODIFIED: [&str; 3] = ["modified", "deleting", "setting"];
+/// Errors on stderr, in lower case, which sacctmgr reports despite exit code 0
+const ERROR_ON_STDERR: &str = "error";
+
+/// Outcome of a sacctmgr command interpreted from its exit code and output.
+///
+/// Sacctmgr returns exit code 0 for "Nothing new added" or "Nothing modified",
+/// but exit code 1 for "already exists". Both are no error if the desired state is reached already.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SacctmgrOutcome {
+    Created,
+    AlreadyExisted,
+    Modified,
+    /// Nothing was changed e.g. for listing or because no entity matched
+    NoChange,
+    /// Message of the failed command
+    Error(String),
+}
+
+impl SacctmgrOutcome {
+    /// A failed command, i.e. an exit code other than 0 or an error on stderr, is only no error
+    /// if every problem on stderr is about an existing or unmatched entity.
+    /// Without anything on stderr, the messages on stdout decide.
+    /// Output without any known message is no change, e.g. listed entities.
+    pub fn classify(exit_code: i32, stdout: &str, stderr: &str) -> Self {
+        let output = format!("{}\n{}", stdout, stderr).to_lowercase();
+        let mentions = |messages: &[&str]| messages.iter().any(|message| output.contains(message));
+        if exit_code != 0 || stderr.to_lowercase().contains(ERROR_ON_STDERR) {
+            Self::classify_failure(exit_code, stdout, stderr)
+        } else if mentions(&ALREADY_EXISTED) {
+            Self::AlreadyExisted
+        } else if mentions(&NO_CHANGE) {
+            Self::NoChange
+        } else if mentions(&CREATED) {
+            Self::Created
+        } else if mentions(&MODIFIED) {
+            Self::Modified
+        } else {
+            Self::NoChange
+        }
+    }
+
+    fn classify_failure(exit_code: i32, stdout: &str, stderr: &str) -> Self {
+        let problems: Vec<String> = if stderr.trim().is_empty() {
+            stdout.lines()
+        } else {
+            stderr.lines()
+        }
+        .map(|line| line.trim().to_lowercase())
+        .filter(|line| !line.is_empty())
+        .collect();
+        let is_one_of = |problem: &String, messages: &[&str]| {
+            messages.iter().any(|message| problem.contains(message))
+        };
+        let only_known = !problems.is_empty()
+            && problems.iter().all(|problem| {
+                is_one_of(problem, &ALREADY_EXISTED) || is_one_of(problem, &NO_CHANGE)
+            });
+        if only_known {
+            if problems
+                .iter()
+                .any(|problem| is_one_of(problem, &ALREADY_EXISTED))
+            {
+                Self::AlreadyExisted
+            } else {
+                Self::NoChange
+            }
+        } else {
+            let message = if stderr.trim().is_empty() {
+                stdout.trim()
+            } else {
+                stderr.trim()
+            };
+            Self::Error(format!("Exit code {}: {}", exit_code, message))
+        }
+    }
+
+    /// Outcome of several commands run for one action.
+    /// An error or else the outcome with the most change wins.
+    pub fn combine(self, other: Self) -> Self {
+        if other.rank() > self.rank() {
+            other
+        } else {
+            self
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::NoChange => 0,
+            Self::AlreadyExisted => 1,
+            Self::Modified => 2,
+            Self::Created => 3,
+            Self::Error(_) => 4,
+        }
+    }
+}
+
+/// Output of sacctmgr commands which succeeded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SacctmgrOutput {
+    pub outcome: SacctmgrOutcome,
+    pub stdout: String,
+}
+
+impl Default for SacctmgrOutput {
+    fn default() -> Self {
+        Self {
+            outcome: SacctmgrOutcome::NoChange,
+            stdout: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn classify(exit_code: i32, stdout: &str, stderr: &str) -> SacctmgrOutcome {
+        SacctmgrOutcome::classify(exit_code, stdout, stderr)
+    }
+
+    #[test]
+    fn classify_output_of_sacctmgr() {
+        assert_eq!(
+            SacctmgrOutcome::Created,
+            classify(
+                0,
+                " Adding User(s)\n  alice\n Associations =\n  U = alice    A = staff    C = alpha\n",
+                ""
+            )
+        );
+        assert_eq!(
+            SacctmgrOutcome::AlreadyExisted,
+            classify(0, " Nothing new added.\n", "")
+        );
+        assert_eq!(
+            SacctmgrOutcome::AlreadyExisted,
+            classify(1, "", " Account staff already exists\n")
+        );
+        assert_eq!(
+            SacctmgrOutcome::Modified,
+            classify(
+                0,
+                " Modified user associations...\n  C = alpha A = staff U = alice\n",
+                ""
+            )
+        );
+        assert_eq!(
+            SacctmgrOutcome::Modified,
+            classify(0, " Deleting users...\n  alice\n", "")
+        );
+        assert_eq!(
+            SacctmgrOutcome::NoChange,
+            classify(0, " Nothing modified\n", "")
+        );
+        assert_eq!(
+            SacctmgrOutcome::NoChange,
+            classify(1, " Nothing deleted\n", "")
+        );
+        assert_eq!(SacctmgrOutcome::NoChange, classify(0, "alice|staff|\n", ""));
+    }
+
+    #[test]
+    fn classify_errors_of_sacctmgr() {
+        assert_eq!(
+            SacctmgrOutcome::Error("Exit code 1: Unknown option: foo".to_string()),
+            classify(1, "", " Unknown option: foo\n")
+        );
+        assert_eq!(
+            SacctmgrOutcome::Error("Exit code 1: Problem adding users".to_string()),
+            classify(1, " Problem adding users\n", "")
+        );
+        assert_eq!(
+            SacctmgrOutcome::Error(
+                "Exit code 0: sacctmgr: error: Problem talking to the database".to_string()
+            ),
+            classify(0, "", "sacctmgr: error: Problem talking to the database\n")
+        );
+        assert_eq!(
+            SacctmgrOutcome::Error("Exit code 1: This account 'ghost' doesn't exist.".to_string()),
+            classify(
+                1,
+                " Nothing new added.\n",
+                " This account 'ghost' doesn't exist.\n"
+            )
+        );
+        assert_eq!(
+            SacctmgrOutcome::Error(
+                "Exit code 1: Account staff already exists\n Problem adding users".to_string()
+            ),
+            classify(
+                1,
+                "",
+                " Account staff already exists\n Problem adding users\n"
+            )
+        );
+    }
+
+    #[test]
+    fn combine_outcomes_of_commands() {
+        assert_eq!(
+            SacctmgrOutcome::Created,
+            SacctmgrOutcome::AlreadyExisted.combine(SacctmgrOutcome::Created)
+        );
+        assert_eq!(
+            SacctmgrOutcome::AlreadyExisted,
+            SacctmgrOutcome::AlreadyExisted.combine(SacctmgrOutcome::NoChange)
+        );
+    }
+}
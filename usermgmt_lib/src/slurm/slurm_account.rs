@@ -0,0 +1,107 @@
+use prettytable::{Cell, Row, Table};
+
+use crate::prelude::AppResult;
+
+const NUMBER_OF_COLUMNS: usize = 6;
+
+/// Account in Slurm with the properties of its own association.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlurmAccount {
+    pub name: String,
+    pub description: String,
+    pub organization: String,
+    /// Empty for the root account
+    pub parent: String,
+    pub fairshare: String,
+}
+
+impl SlurmAccount {
+    /// Parses the output of sacctmgr listing accounts with their associations.
+    /// See [`super::command_builder::CommandBuilder::new_show_accounts`].
+    ///
+    /// Only the associations of accounts are taken, the ones of users are skipped.
+    /// An account is listed once even if it has associations on several clusters.
+    ///
+    /// # Errors
+    ///
+    /// - If a line does not have the expected number of columns.
+    pub fn parse_many(output: &str) -> AppResult<Vec<Self>> {
+        let mut accounts: Vec<Self> = Vec::new();
+        for line in output.lines().filter(|line| !line.trim().is_empty()) {
+            let cells: Vec<&str> = line.split('|').collect();
+            let [name, description, organization, parent, fairshare, user] = cells[..] else {
+                anyhow::bail!(
+                    "Expected {} columns separated by | in the list of Slurm accounts, but got line: {}",
+                    NUMBER_OF_COLUMNS,
+                    line
+                );
+            };
+            let is_association_of_user = !user.trim().is_empty();
+            if is_association_of_user || accounts.iter().any(|account| account.name == name) {
+                continue;
+            }
+            accounts.push(Self {
+                name: name.to_string(),
+                description: description.to_string(),
+                organization: organization.to_string(),
+                parent: parent.to_string(),
+                fairshare: fairshare.to_string(),
+            });
+        }
+        Ok(accounts)
+    }
+}
+
+pub fn accounts_to_pretty_table(accounts: &[SlurmAccount]) -> String {
+    let mut table = Table::new();
+    table.set_titles(Row::new(
+        [
+            "Account",
+            "Parent",
+            "Organization",
+            "Description",
+            "Fairshare",
+        ]
+        .into_iter()
+        .map(Cell::new)
+        .collect(),
+    ));
+    for account in accounts {
+        let cells = [
+            &account.name,
+            &account.parent,
+            &account.organization,
+            &account.description,
+            &account.fairshare,
+        ]
+        .into_iter()
+        .map(|value| Cell::new(value))
+        .collect();
+        table.add_row(Row::new(cells));
+    }
+    table.to_string()
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_accounts_without_users() {
+        let output = "\
+root|default root account|root|||
+root|default root account|root||1|root
+staff|Staff of the university|th|root|10|
+staff|Staff of the university|th|root|1|alice
+student|student|student|root|1|
+";
+        let actual = SlurmAccount::parse_many(output).expect("Should be valid output");
+        insta::assert_debug_snapshot!(actual);
+        insta::assert_snapshot!(accounts_to_pretty_table(&actual));
+    }
+
+    #[test]
+    fn error_for_line_with_wrong_number_of_columns() {
+        assert!(SlurmAccount::parse_many("root|default root account|root").is_err());
+    }
+}
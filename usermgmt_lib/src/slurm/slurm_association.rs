@@ -0,0 +1,172 @@
+use anyhow::bail;
+use prettytable::{Cell, Row, Table};
+
+use crate::{prelude::AppResult, util::SlurmLimit};
+
+/// Columns before the ones of the limits: user, account, partition, cluster, default QOS and QOS
+const NUMBER_OF_COLUMNS_WITHOUT_LIMITS: usize = 6;
+const SEPARATOR_OF_CELLS: char = '|';
+const SEPARATOR_OF_QOS: char = ',';
+
+/// Association of a user in Slurm as listed by sacctmgr.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlurmAssociation {
+    pub user: String,
+    pub account: String,
+    pub partition: Option<String>,
+    pub cluster: Option<String>,
+    pub default_qos: Option<String>,
+    pub qos: Vec<String>,
+    /// Limits which are set as pairs of name and value.
+    /// In the order of [`SlurmLimit::names`].
+    pub limits: Vec<(&'static str, String)>,
+}
+
+impl SlurmAssociation {
+    /// Parses the output of sacctmgr listing associations.
+    /// See [`super::command_builder::CommandBuilder::new_show_where`].
+    ///
+    /// Associations of accounts, which have no user, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// - If a line does not have the expected number of columns.
+    /// - If a line of a user has no account.
+    pub fn parse_many(output: &str) -> AppResult<Vec<Self>> {
+        let number_of_columns = NUMBER_OF_COLUMNS_WITHOUT_LIMITS + SlurmLimit::names().count();
+        let mut associations = Vec::new();
+        for (index, line) in output
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+        {
+            let cells: Vec<&str> = line.split(SEPARATOR_OF_CELLS).map(str::trim).collect();
+            if cells.len() != number_of_columns {
+                bail!(
+                    "Expected {} columns separated by {} in line {} of the Slurm associations, but got {}: {}",
+                    number_of_columns,
+                    SEPARATOR_OF_CELLS,
+                    index + 1,
+                    cells.len(),
+                    line
+                );
+            }
+            let (columns, limits) = cells.split_at(NUMBER_OF_COLUMNS_WITHOUT_LIMITS);
+            let [user, account, partition, cluster, default_qos, qos] = columns[..] else {
+                unreachable!("Number of columns is checked above");
+            };
+            if user.is_empty() {
+                continue;
+            }
+            if account.is_empty() {
+                bail!(
+                    "Association of user {} in line {} has no account: {}",
+                    user,
+                    index + 1,
+                    line
+                );
+            }
+
+            let optional = |value: &str| (!value.is_empty()).then(|| value.to_string());
+            associations.push(Self {
+                user: user.to_string(),
+                account: account.to_string(),
+                partition: optional(partition),
+                cluster: optional(cluster),
+                default_qos: optional(default_qos),
+                qos: qos
+                    .split(SEPARATOR_OF_QOS)
+                    .filter(|qos| !qos.is_empty())
+                    .map(String::from)
+                    .collect(),
+                limits: SlurmLimit::names()
+                    .zip(limits)
+                    .filter(|(_, value)| !value.is_empty())
+                    .map(|(name, value)| (name, value.to_string()))
+                    .collect(),
+            });
+        }
+        Ok(associations)
+    }
+
+    /// Titles of the columns returned by [`SlurmAssociation::cells`]
+    pub fn headers() -> [&'static str; 7] {
+        [
+            "User",
+            "Account",
+            "Partition",
+            "Cluster",
+            "Default QOS",
+            "QOS",
+            "Limits",
+        ]
+    }
+
+    /// Values of this association as shown in a table.
+    /// Missing values are empty.
+    pub fn cells(&self) -> [String; 7] {
+        let limits: Vec<String> = self
+            .limits
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        [
+            self.user.clone(),
+            self.account.clone(),
+            self.partition.clone().unwrap_or_default(),
+            self.cluster.clone().unwrap_or_default(),
+            self.default_qos.clone().unwrap_or_default(),
+            self.qos.join(","),
+            limits.join(" "),
+        ]
+    }
+}
+
+pub fn associations_to_pretty_table(associations: &[SlurmAssociation]) -> String {
+    let mut table = Table::new();
+    table.set_titles(Row::new(
+        SlurmAssociation::headers()
+            .into_iter()
+            .map(Cell::new)
+            .collect(),
+    ));
+    for association in associations {
+        let cells = association
+            .cells()
+            .iter()
+            .map(|value| Cell::new(value))
+            .collect();
+        table.add_row(Row::new(cells));
+    }
+    table.to_string()
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_associations() {
+        let output = "\
+|root||alpha||normal||||||||
+root|root||alpha||normal||||||||
+alice|staff||alpha|basic|basic,interactive|cpu=64,gres/gpu=2||||10|||
+alice|project-a|gpu|alpha||basic||||||||2-00:00:00
+";
+        let actual = SlurmAssociation::parse_many(output).expect("Should be valid output");
+        insta::assert_debug_snapshot!(actual);
+        insta::assert_snapshot!(associations_to_pretty_table(&actual));
+    }
+
+    #[test]
+    fn error_for_malformed_lines() {
+        // Trailing separator of --parsable instead of --parsable2
+        assert!(SlurmAssociation::parse_many("alice|staff||alpha||basic|||||||||").is_err());
+        assert!(SlurmAssociation::parse_many("alice|staff|basic").is_err());
+        assert!(SlurmAssociation::parse_many("alice|||alpha||basic||||||||").is_err());
+        assert_eq!(
+            Vec::<SlurmAssociation>::new(),
+            SlurmAssociation::parse_many("\n").expect("Should be valid output")
+        );
+    }
+}
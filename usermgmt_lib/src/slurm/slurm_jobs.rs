@@ -0,0 +1,231 @@
+use std::{
+    path::Path,
+    process::Command,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::ensure;
+use log::{debug, info};
+
+use crate::{
+    config::MgmtConfig,
+    prelude::*,
+    ssh::{SshConnection, SshCredentials},
+};
+
+use super::command_builder::quote_for_shell;
+
+const SQUEUE: &str = "squeue";
+const SCANCEL: &str = "scancel";
+/// Line which squeue prints before the jobs of every cluster if clusters are given
+const PREFIX_OF_CLUSTER_LINE: &str = "CLUSTER:";
+/// Separator between the job id and the user within a line of squeue
+const SEPARATOR_OF_JOB_AND_USER: char = '|';
+/// Time between two checks of the queue while waiting for cancelled jobs
+pub const SECS_BETWEEN_CHECKS: u64 = 2;
+
+/// Ids of the pending, running or otherwise unfinished jobs of a user as listed by squeue.
+///
+/// The jobs of all users are listed and filtered by the user afterwards
+/// since squeue fails for a user which can not be resolved on the head node, e.g. one without an LDAP entry.
+/// Such a user has no jobs instead.
+///
+/// # Errors
+///
+/// - If squeue can not be executed or fails.
+pub fn active_jobs_of_user<C>(
+    username: &str,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<Vec<String>>
+where
+    C: SshCredentials,
+{
+    let mut args = vec![
+        "--noheader".to_string(),
+        format!("--format=%i{}%u", SEPARATOR_OF_JOB_AND_USER),
+    ];
+    args.extend(clusters_arg(config));
+    let output = run_slurm_tool(SQUEUE, args, config, session)?;
+    Ok(parse_job_ids_of_user(&output, username))
+}
+
+/// Cancels every job of a user via scancel.
+///
+/// # Errors
+///
+/// - If scancel can not be executed or fails.
+pub fn cancel_jobs_of_user<C>(
+    username: &str,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult
+where
+    C: SshCredentials,
+{
+    let mut args = vec![format!("--user={}", username)];
+    args.extend(clusters_arg(config));
+    run_slurm_tool(SCANCEL, args, config, session).map(drop)
+}
+
+/// Calls parameter `active_jobs` repeatedly until it returns no jobs anymore.
+///
+/// # Errors
+///
+/// - If parameter `active_jobs` fails.
+/// - If jobs are still returned after parameter `timeout`.
+pub fn wait_until_no_jobs(
+    username: &str,
+    timeout: Duration,
+    between_checks: Duration,
+    mut active_jobs: impl FnMut() -> AppResult<Vec<String>>,
+) -> AppResult {
+    let started = Instant::now();
+    loop {
+        let jobs = active_jobs()?;
+        if jobs.is_empty() {
+            return Ok(());
+        }
+        ensure!(
+            started.elapsed() < timeout,
+            "Jobs {} of user {} are still in the Slurm queue after waiting {} seconds for their cancellation",
+            jobs.join(", "),
+            username,
+            timeout.as_secs()
+        );
+        info!(
+            "Waiting for {} cancelled jobs of user {} to leave the Slurm queue",
+            jobs.len(),
+            username
+        );
+        thread::sleep(between_checks);
+    }
+}
+
+/// Option which restricts squeue, scancel or sacct to the Slurm cluster of parameter `config` if any
+pub(super) fn clusters_arg(config: &MgmtConfig) -> Option<String> {
+    config
+        .slurm_cluster
+        .as_ref()
+        .map(|cluster| format!("--clusters={}", cluster))
+}
+
+/// Runs a Slurm tool other than sacctmgr like [`super::run_slurm_action`] runs sacctmgr.
+/// The tools are expected in the same directory as sacctmgr.
+///
+/// # Errors
+///
+/// - If the tool can not be executed or returns an exit code other than 0.
+pub(super) fn run_slurm_tool<C>(
+    tool: &str,
+    args: Vec<String>,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<String>
+where
+    C: SshCredentials,
+{
+    let path = path_of_tool(&config.sacctmgr_path, tool);
+    let (stdout, stderr, exit_code) = if config.run_slurm_remote {
+        let mut command = vec![path.clone()];
+        command.extend(args.iter().map(|arg| quote_for_shell(arg).into_owned()));
+        let command = command.join(" ");
+        debug!("Running remote Slurm command: {}", command);
+        session.exec_with_stderr(&command).with_context(|| {
+            format!("Error during remote Slurm command execution ({}).", command)
+        })?
+    } else {
+        let output = Command::new(&path)
+            .args(&args)
+            .output()
+            .with_context(|| format!("Unable to execute {}. Is it next to sacctmgr?", path))?;
+        (
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+            output.status.code().unwrap_or(-1),
+        )
+    };
+    ensure!(
+        exit_code == 0,
+        "Slurm command {} failed with exit code {}: {}",
+        path,
+        exit_code,
+        stderr.trim()
+    );
+    Ok(stdout)
+}
+
+/// Path of parameter `tool` within the directory of parameter `sacctmgr_path`.
+/// Only the name of the tool if sacctmgr is given without a directory.
+fn path_of_tool(sacctmgr_path: &str, tool: &str) -> String {
+    match Path::new(sacctmgr_path).parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => {
+            directory.join(tool).to_string_lossy().to_string()
+        }
+        _ => tool.to_string(),
+    }
+}
+
+/// Ids of the jobs of parameter `username` within lines of job id and user like `4711|alice`
+fn parse_job_ids_of_user(output: &str, username: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(PREFIX_OF_CLUSTER_LINE))
+        .filter_map(|line| line.split_once(SEPARATOR_OF_JOB_AND_USER))
+        .filter(|(_, user)| user.trim() == username)
+        .map(|(job_id, _)| job_id.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_ids_of_jobs() {
+        assert_eq!(
+            vec!["4711", "4712_[1-3]"],
+            parse_job_ids_of_user(
+                "CLUSTER: alpha\n4711|alice\n4710|bob\n  4712_[1-3]|alice\n\n",
+                "alice"
+            )
+        );
+        assert_eq!(Vec::<String>::new(), parse_job_ids_of_user("", "alice"));
+    }
+
+    #[test]
+    fn no_jobs_of_user_unknown_to_slurm() {
+        assert_eq!(
+            Vec::<String>::new(),
+            parse_job_ids_of_user("4710|bob\n4711|alicia\n", "alice")
+        );
+    }
+
+    #[test]
+    fn path_of_tools_next_to_sacctmgr() {
+        assert_eq!(
+            "/usr/local/bin/squeue",
+            path_of_tool("/usr/local/bin/sacctmgr", SQUEUE)
+        );
+        assert_eq!("scancel", path_of_tool("sacctmgr", SCANCEL));
+    }
+
+    #[test]
+    fn wait_until_jobs_left_queue() {
+        let mut remaining = vec![vec![], vec!["4711".to_string()], vec!["4711".to_string()]];
+        wait_until_no_jobs("alice", Duration::from_secs(60), Duration::ZERO, || {
+            Ok(remaining
+                .pop()
+                .expect("Should not check after the queue is empty"))
+        })
+        .expect("Should finish once the queue is empty");
+        assert!(remaining.is_empty());
+
+        let timed_out = wait_until_no_jobs("alice", Duration::ZERO, Duration::ZERO, || {
+            Ok(vec!["4711".to_string()])
+        });
+        assert!(timed_out.is_err());
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+
+use prettytable::{Cell, Row, Table};
+
+use crate::{prelude::AppResult, MgmtConfig};
+
+const NUMBER_OF_COLUMNS: usize = 5;
+
+/// QOS in Slurm with its limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlurmQos {
+    pub name: String,
+    pub priority: String,
+    pub max_wall: String,
+    pub max_tres_per_user: String,
+    pub description: String,
+}
+
+impl SlurmQos {
+    /// Parses the output of sacctmgr listing all QOS.
+    /// See [`super::command_builder::CommandBuilder::new_show_qos`].
+    ///
+    /// # Errors
+    ///
+    /// - If a line does not have the expected number of columns.
+    pub fn parse_many(output: &str) -> AppResult<Vec<Self>> {
+        output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let cells: Vec<&str> = line.split('|').collect();
+                let [name, priority, max_wall, max_tres_per_user, description] = cells[..] else {
+                    anyhow::bail!(
+                        "Expected {} columns separated by | in the list of Slurm QOS, but got line: {}",
+                        NUMBER_OF_COLUMNS,
+                        line
+                    );
+                };
+                Ok(Self {
+                    name: name.to_string(),
+                    priority: priority.to_string(),
+                    max_wall: max_wall.to_string(),
+                    max_tres_per_user: max_tres_per_user.to_string(),
+                    description: description.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+pub fn qos_to_pretty_table(all_qos: &[SlurmQos]) -> String {
+    let mut table = Table::new();
+    table.set_titles(Row::new(
+        [
+            "QOS",
+            "Priority",
+            "MaxWall",
+            "MaxTRESPerUser",
+            "Description",
+        ]
+        .into_iter()
+        .map(Cell::new)
+        .collect(),
+    ));
+    for qos in all_qos {
+        let cells = [
+            &qos.name,
+            &qos.priority,
+            &qos.max_wall,
+            &qos.max_tres_per_user,
+            &qos.description,
+        ]
+        .into_iter()
+        .map(|value| Cell::new(value))
+        .collect();
+        table.add_row(Row::new(cells));
+    }
+    table.to_string()
+}
+
+/// Differences between the QOS named in the configuration and the ones existing in Slurm
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct QosComparison {
+    /// QOS named in the configuration which do not exist in Slurm,
+    /// together with the names of the fields in which they are named.
+    pub missing_in_slurm: Vec<(String, Vec<&'static str>)>,
+    /// QOS existing in Slurm which are not listed in `valid_qos`
+    pub missing_in_config: Vec<String>,
+}
+
+impl QosComparison {
+    pub fn new(config: &MgmtConfig, existing: &[SlurmQos]) -> Self {
+        let named_in_config = [
+            ("valid_qos", config.valid_qos.clone()),
+            ("student_qos", config.student_qos.clone()),
+            ("staff_qos", config.staff_qos.clone()),
+            (
+                "student_default_qos",
+                vec![config.student_default_qos.clone()],
+            ),
+            ("staff_default_qos", vec![config.staff_default_qos.clone()]),
+        ];
+        let exists = |name: &str| existing.iter().any(|qos| qos.name == name);
+
+        let mut missing_in_slurm: Vec<(String, Vec<&'static str>)> = Vec::new();
+        for (field, names) in named_in_config {
+            for name in names.into_iter().filter(|name| !exists(name)) {
+                match missing_in_slurm
+                    .iter_mut()
+                    .find(|(missing, _)| *missing == name)
+                {
+                    Some((_, fields)) => fields.push(field),
+                    None => missing_in_slurm.push((name, vec![field])),
+                }
+            }
+        }
+        let missing_in_config = existing
+            .iter()
+            .filter(|qos| !config.valid_qos.contains(&qos.name))
+            .map(|qos| qos.name.clone())
+            .collect();
+        Self {
+            missing_in_slurm,
+            missing_in_config,
+        }
+    }
+
+    /// Returns true if every QOS named in the configuration exists in Slurm
+    pub fn all_configured_exist(&self) -> bool {
+        self.missing_in_slurm.is_empty()
+    }
+}
+
+impl fmt::Display for QosComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.all_configured_exist() {
+            writeln!(f, "All QOS named in the configuration exist in Slurm.")?;
+        } else {
+            writeln!(
+                f,
+                "QOS named in the configuration which do not exist in Slurm:"
+            )?;
+            for (name, fields) in self.missing_in_slurm.iter() {
+                writeln!(f, "  - {} (in {})", name, fields.join(", "))?;
+            }
+        }
+        if !self.missing_in_config.is_empty() {
+            writeln!(f, "QOS in Slurm which are not listed in valid_qos:")?;
+            for name in self.missing_in_config.iter() {
+                writeln!(f, "  - {}", name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    const OUTPUT: &str = "\
+normal|0|||
+basic|10|1-00:00:00|cpu=8|Default for students
+interactive|100|08:00:00|cpu=4,gres/gpu=1|
+";
+
+    #[test]
+    fn parse_qos() {
+        let actual = SlurmQos::parse_many(OUTPUT).expect("Should be valid output");
+        insta::assert_debug_snapshot!(actual);
+        insta::assert_snapshot!(qos_to_pretty_table(&actual));
+        assert!(SlurmQos::parse_many("normal|0").is_err());
+    }
+
+    #[test]
+    fn compare_configured_with_existing_qos() {
+        let existing = SlurmQos::parse_many(OUTPUT).expect("Should be valid output");
+        let config = MgmtConfig {
+            valid_qos: vec![
+                "interactive".to_string(),
+                "basic".to_string(),
+                "advanced".to_string(),
+            ],
+            student_qos: vec!["interactive".to_string(), "basic".to_string()],
+            staff_qos: vec!["interactive".to_string(), "advanced".to_string()],
+            student_default_qos: "basic".to_string(),
+            staff_default_qos: "advanced".to_string(),
+            ..Default::default()
+        };
+        let actual = QosComparison::new(&config, &existing);
+        assert_eq!(
+            QosComparison {
+                missing_in_slurm: vec![(
+                    "advanced".to_string(),
+                    vec!["valid_qos", "staff_qos", "staff_default_qos"]
+                )],
+                missing_in_config: vec!["normal".to_string()],
+            },
+            actual
+        );
+        insta::assert_snapshot!(actual.to_string());
+    }
+}
@@ -0,0 +1,781 @@
+use std::{fs, process::Command, sync::Arc, time::Duration};
+
+use anyhow::ensure;
+use log::debug;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    config::{MgmtConfig, SlurmBackend},
+    prelude::*,
+    util::{SlurmLimit, UserAssociation},
+};
+
+use super::{AssociationsOfUser, ShowConditions, SlurmAssociation};
+
+mod rest_limits;
+
+const HEADER_OF_TOKEN: &str = "X-SLURM-USER-TOKEN";
+/// Prefix of the token in the output of `scontrol token`
+const PREFIX_OF_TOKEN: &str = "SLURM_JWT=";
+const TIMEOUT_SECS: u64 = 30;
+/// API of slurmrestd for the accounting database
+const SLURMDB: &str = "slurmdb";
+/// API of slurmrestd for the jobs
+const SLURM: &str = "slurm";
+const USERS: &str = "users";
+const ASSOCIATIONS: &str = "associations";
+const JOBS: &str = "jobs";
+/// States of jobs which are listed by squeue by default
+const ACTIVE_JOB_STATES: [&str; 6] = [
+    "PENDING",
+    "RUNNING",
+    "SUSPENDED",
+    "COMPLETING",
+    "CONFIGURING",
+    "REQUEUED",
+];
+
+/// Client of the slurmdb endpoints of slurmrestd.
+/// Performs the same operations on users as sacctmgr if `slurm_backend` is rest in the configuration.
+/// Jobs of users are queried and cancelled via the slurm endpoints like squeue and scancel would do.
+pub struct SlurmRestClient {
+    agent: ureq::Agent,
+    /// URL of slurmrestd e.g. http://head.node.de:6820
+    base_url: String,
+    api_version: String,
+    token: String,
+    /// Cluster to which the operations on users are restricted
+    cluster: Option<String>,
+}
+
+/// Association as returned by slurmrestd. Fields not needed here are ignored.
+#[derive(Debug, Default, Deserialize)]
+struct RestAssociation {
+    #[serde(default)]
+    user: String,
+    #[serde(default)]
+    account: String,
+    partition: Option<String>,
+    cluster: Option<String>,
+    #[serde(default)]
+    qos: Vec<String>,
+    #[serde(default)]
+    default: DefaultsOfAssociation,
+    #[serde(default)]
+    is_default: bool,
+    #[serde(default)]
+    max: Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DefaultsOfAssociation {
+    qos: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedAssociations {
+    #[serde(default)]
+    associations: Vec<RestAssociation>,
+}
+
+/// Job as returned by slurmrestd. Fields not needed here are ignored.
+#[derive(Debug, Deserialize)]
+struct RestJob {
+    job_id: u64,
+    #[serde(default)]
+    user_name: String,
+    /// List of states since v0.0.40, a single state before
+    #[serde(default)]
+    job_state: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedJobs {
+    #[serde(default)]
+    jobs: Vec<RestJob>,
+}
+
+impl RestJob {
+    fn is_active(&self) -> bool {
+        let is_active_state = |state: &Value| {
+            state
+                .as_str()
+                .is_some_and(|state| ACTIVE_JOB_STATES.contains(&state))
+        };
+        match &self.job_state {
+            Value::Array(states) => states.iter().any(is_active_state),
+            state => is_active_state(state),
+        }
+    }
+}
+
+impl RestAssociation {
+    /// Fields which identify this association for slurmrestd
+    fn key(&self) -> Map<String, Value> {
+        association_key(
+            &self.user,
+            &self.account,
+            non_empty(&self.partition),
+            non_empty(&self.cluster),
+        )
+    }
+
+    fn into_slurm_association(self) -> SlurmAssociation {
+        SlurmAssociation {
+            partition: non_empty(&self.partition).map(String::from),
+            cluster: non_empty(&self.cluster).map(String::from),
+            default_qos: non_empty(&self.default.qos).map(String::from),
+            limits: rest_limits::from_json(&self.max),
+            user: self.user,
+            account: self.account,
+            qos: self.qos,
+        }
+    }
+}
+
+impl SlurmRestClient {
+    /// Returns a client if `slurm_backend` of parameter `config` is rest, otherwise none.
+    ///
+    /// # Errors
+    ///
+    /// - See [`SlurmRestClient::from_config`]
+    pub fn from_config_if_selected(config: &MgmtConfig) -> AppResult<Option<Self>> {
+        match config.slurm_backend {
+            SlurmBackend::Sacctmgr => Ok(None),
+            SlurmBackend::Rest => Self::from_config(config).map(Some),
+        }
+    }
+
+    /// Uses the field `slurm_rest_url`, `slurm_rest_api_version` and the token of parameter `config`.
+    /// Operations on users are restricted to the Slurm cluster of the configuration if any.
+    ///
+    /// # Errors
+    ///
+    /// - If `slurm_rest_url` is not configured.
+    /// - If the token can not be obtained. See [`read_token`]
+    /// - If TLS can not be initialized.
+    pub fn from_config(config: &MgmtConfig) -> AppResult<Self> {
+        let url = config.slurm_rest_url.as_deref().ok_or_else(|| {
+            anyhow!("Field slurm_rest_url must be configured for the Slurm backend rest")
+        })?;
+        let tls =
+            native_tls::TlsConnector::new().context("Failed to initialize TLS for slurmrestd")?;
+        let agent = ureq::AgentBuilder::new()
+            .tls_connector(Arc::new(tls))
+            .timeout(Duration::from_secs(TIMEOUT_SECS))
+            .build();
+        Ok(Self::new(
+            agent,
+            url,
+            &config.slurm_rest_api_version,
+            read_token(config)?,
+            config.slurm_cluster.clone(),
+        ))
+    }
+
+    fn new(
+        agent: ureq::Agent,
+        url: &str,
+        api_version: &str,
+        token: String,
+        cluster: Option<String>,
+    ) -> Self {
+        Self {
+            agent,
+            base_url: url.trim_end_matches('/').to_string(),
+            api_version: api_version.to_string(),
+            token,
+            cluster,
+        }
+    }
+
+    /// Adds the user with an association with parameter `account` as default account.
+    pub fn add_user(
+        &self,
+        username: &str,
+        account: &str,
+        qos: Vec<String>,
+        default_qos: String,
+        limits: &[SlurmLimit],
+    ) -> AppResult {
+        self.post(
+            USERS,
+            json!({ USERS: [{ "name": username, "default": { "account": account } }] }),
+        )?;
+        let mut association = association_key(username, account, None, self.cluster.as_deref());
+        association.insert("qos".to_string(), json!(qos));
+        association.insert("default".to_string(), json!({ "qos": default_qos }));
+        if !limits.is_empty() {
+            association.insert("max".to_string(), rest_limits::to_json(limits)?);
+        }
+        self.post(ASSOCIATIONS, json!({ ASSOCIATIONS: [association] }))
+    }
+
+    /// Sets the given QOS, default QOS and limits on every association of the user.
+    ///
+    /// # Errors
+    ///
+    /// - If the user has no association.
+    pub fn modify_user(
+        &self,
+        username: &str,
+        qos_and_default_qos: Option<(Vec<String>, String)>,
+        limits: &[SlurmLimit],
+    ) -> AppResult {
+        let max = rest_limits::to_json(limits)?;
+        let associations: Vec<Map<String, Value>> = self
+            .associations_where(&[("user", username.to_string())])?
+            .iter()
+            .map(|existing| {
+                let mut association = existing.key();
+                if let Some((qos, default_qos)) = &qos_and_default_qos {
+                    association.insert("qos".to_string(), json!(qos));
+                    association.insert("default".to_string(), json!({ "qos": default_qos }));
+                }
+                if !limits.is_empty() {
+                    association.insert("max".to_string(), max.clone());
+                }
+                association
+            })
+            .collect();
+        ensure!(
+            !associations.is_empty(),
+            "User {} has no association in Slurm",
+            username
+        );
+        self.post(ASSOCIATIONS, json!({ ASSOCIATIONS: associations }))
+    }
+
+    /// Deletes the user or only its associations on the cluster if the client is restricted to one.
+    pub fn delete_user(&self, username: &str) -> AppResult {
+        match &self.cluster {
+            Some(cluster) => self.delete(
+                SLURMDB,
+                ASSOCIATIONS,
+                &[("user", username.to_string()), ("cluster", cluster.clone())],
+            ),
+            None => self.delete(SLURMDB, &format!("user/{}", username), &[]),
+        }
+    }
+
+    /// Lists the associations of all users matching parameter `conditions`.
+    pub fn list_users(&self, conditions: ShowConditions) -> AppResult<Vec<SlurmAssociation>> {
+        let mut query = Vec::new();
+        if let Some(users) = conditions.users {
+            query.push(("user", users.join(",")));
+        }
+        if let Some(account) = conditions.account {
+            query.push(("account", account.to_string()));
+        }
+        if let Some(qos) = conditions.qos {
+            query.push(("qos", qos));
+        }
+        Ok(self
+            .associations_where(&query)?
+            .into_iter()
+            .map(RestAssociation::into_slurm_association)
+            .collect())
+    }
+
+    /// Same as [`AssociationsOfUser::parse`] for the output of sacctmgr
+    pub fn associations_of_user(&self, username: &str) -> AppResult<AssociationsOfUser> {
+        let mut of_user = AssociationsOfUser::default();
+        for association in self.associations_where(&[("user", username.to_string())])? {
+            if association.is_default {
+                of_user.default_account = Some(association.account.clone());
+            }
+            of_user.associations.push(UserAssociation::new(
+                association.account.try_into()?,
+                non_empty(&association.partition).and_then(|value| value.try_into().ok()),
+                non_empty(&association.cluster).and_then(|value| value.try_into().ok()),
+            ));
+        }
+        Ok(of_user)
+    }
+
+    /// Associations without a cluster are added on the cluster of this client if any.
+    pub fn add_associations(&self, username: &str, associations: &[UserAssociation]) -> AppResult {
+        let associations: Vec<Map<String, Value>> = associations
+            .iter()
+            .map(|association| {
+                let mut added = association_key(
+                    username,
+                    association.account(),
+                    association.partition(),
+                    association.cluster().or(self.cluster.as_deref()),
+                );
+                if !association.qos().is_empty() {
+                    let qos: Vec<&str> = association
+                        .qos()
+                        .iter()
+                        .map(|qos| qos.as_ref().as_str())
+                        .collect();
+                    added.insert("qos".to_string(), json!(qos));
+                }
+                added
+            })
+            .collect();
+        self.post(ASSOCIATIONS, json!({ ASSOCIATIONS: associations }))
+    }
+
+    /// Deletes every association of the user matching one of parameter `associations`.
+    pub fn delete_associations(
+        &self,
+        username: &str,
+        associations: &[UserAssociation],
+    ) -> AppResult {
+        for association in associations {
+            let mut query = vec![
+                ("user", username.to_string()),
+                ("account", association.account().to_string()),
+            ];
+            if let Some(partition) = association.partition() {
+                query.push(("partition", partition.to_string()));
+            }
+            if let Some(cluster) = association.cluster().or(self.cluster.as_deref()) {
+                query.push(("cluster", cluster.to_string()));
+            }
+            self.delete(SLURMDB, ASSOCIATIONS, &query)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_default_account(&self, username: &str, account: &str) -> AppResult {
+        self.post(
+            USERS,
+            json!({ USERS: [{ "name": username, "default": { "account": account } }] }),
+        )
+    }
+
+    /// Ids of the jobs of the user which are pending, running or otherwise not finished yet.
+    ///
+    /// # Errors
+    ///
+    /// - If the response of slurmrestd is not a list of jobs.
+    pub fn active_jobs_of_user(&self, username: &str) -> AppResult<Vec<String>> {
+        let request = self.request("GET", SLURM, JOBS, &[]);
+        let listed: ListedJobs = serde_json::from_value(self.send(request, None)?)
+            .context("Unexpected list of jobs from slurmrestd")?;
+        Ok(listed
+            .jobs
+            .into_iter()
+            .filter(|job| job.user_name == username && job.is_active())
+            .map(|job| job.job_id.to_string())
+            .collect())
+    }
+
+    /// Cancels every active job of the user. See [`SlurmRestClient::active_jobs_of_user`]
+    pub fn cancel_jobs_of_user(&self, username: &str) -> AppResult {
+        for job_id in self.active_jobs_of_user(username)? {
+            self.delete(SLURM, &format!("job/{}", job_id), &[])?;
+        }
+        Ok(())
+    }
+
+    /// Associations of users matching parameter `query` on the cluster of this client.
+    /// Associations of accounts without a user are left out.
+    fn associations_where(&self, query: &[(&str, String)]) -> AppResult<Vec<RestAssociation>> {
+        let mut query = query.to_vec();
+        if let Some(cluster) = &self.cluster {
+            query.push(("cluster", cluster.clone()));
+        }
+        let request = self.request("GET", SLURMDB, ASSOCIATIONS, &query);
+        let listed: ListedAssociations = serde_json::from_value(self.send(request, None)?)
+            .context("Unexpected list of associations from slurmrestd")?;
+        Ok(listed
+            .associations
+            .into_iter()
+            .filter(|association| !association.user.is_empty())
+            .collect())
+    }
+
+    fn post(&self, path: &str, body: Value) -> AppResult {
+        let request = self.request("POST", SLURMDB, path, &[]);
+        self.send(request, Some(body)).map(drop)
+    }
+
+    fn delete(&self, api: &str, path: &str, query: &[(&str, String)]) -> AppResult {
+        let request = self.request("DELETE", api, path, query);
+        self.send(request, None).map(drop)
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        api: &str,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> ureq::Request {
+        let url = format!("{}/{}/{}/{}", self.base_url, api, self.api_version, path);
+        query.iter().fold(
+            self.agent
+                .request(method, &url)
+                .set(HEADER_OF_TOKEN, &self.token),
+            |request, (key, value)| request.query(key, value),
+        )
+    }
+
+    /// # Errors
+    ///
+    /// - If slurmrestd can not be reached.
+    /// - If slurmrestd responds with an error status or reports errors in the response.
+    fn send(&self, request: ureq::Request, body: Option<Value>) -> AppResult<Value> {
+        let description = format!("{} {}", request.method(), request.url());
+        debug!("Sending request to slurmrestd: {}", description);
+        let response = match body {
+            Some(body) => request.send_json(body),
+            None => request.call(),
+        };
+        let response = match response {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                let errors = response
+                    .into_json::<Value>()
+                    .map(|body| errors_of(&body))
+                    .unwrap_or_default();
+                bail!(
+                    "Slurmrestd responded with status {} to {}: {}",
+                    status,
+                    description,
+                    errors.join("; ")
+                );
+            }
+            Err(error) => {
+                return Err(error).with_context(|| {
+                    format!("Failed to send request {} to slurmrestd", description)
+                })
+            }
+        };
+        let body: Value = response
+            .into_json()
+            .with_context(|| format!("Response of slurmrestd to {} is not JSON", description))?;
+        let errors = errors_of(&body);
+        ensure!(
+            errors.is_empty(),
+            "Slurmrestd reported errors for {}: {}",
+            description,
+            errors.join("; ")
+        );
+        Ok(body)
+    }
+}
+
+/// Reads the JWT for slurmrestd from `slurm_rest_token_file` or else from the output of `slurm_rest_token_command`.
+/// The prefix SLURM_JWT= as printed by `scontrol token` is removed.
+///
+/// # Errors
+///
+/// - If neither a file nor a command for the token is configured.
+/// - If the file can not be read or the command fails.
+/// - If the token is empty.
+fn read_token(config: &MgmtConfig) -> AppResult<String> {
+    let output = match (
+        &config.slurm_rest_token_file,
+        &config.slurm_rest_token_command,
+    ) {
+        (Some(path), _) => fs::read_to_string(path)
+            .with_context(|| format!("Failed to read the token for slurmrestd from {}", path))?,
+        (None, Some(command)) => {
+            let output = Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .output()
+                .with_context(|| format!("Failed to run {} for the token of slurmrestd", command))?;
+            ensure!(
+                output.status.success(),
+                "Command {} for the token of slurmrestd failed with {}: {}",
+                command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            String::from_utf8(output.stdout).context("Token for slurmrestd is not valid UTF-8")?
+        }
+        (None, None) => bail!(
+            "Either slurm_rest_token_file or slurm_rest_token_command must be configured for the Slurm backend rest"
+        ),
+    };
+    let token = output.trim();
+    let token = token.strip_prefix(PREFIX_OF_TOKEN).unwrap_or(token).trim();
+    ensure!(!token.is_empty(), "Token for slurmrestd is empty");
+    Ok(token.to_string())
+}
+
+fn association_key(
+    username: &str,
+    account: &str,
+    partition: Option<&str>,
+    cluster: Option<&str>,
+) -> Map<String, Value> {
+    let mut key = Map::new();
+    key.insert("user".to_string(), json!(username));
+    key.insert("account".to_string(), json!(account));
+    if let Some(partition) = partition {
+        key.insert("partition".to_string(), json!(partition));
+    }
+    if let Some(cluster) = cluster {
+        key.insert("cluster".to_string(), json!(cluster));
+    }
+    key
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().filter(|value| !value.is_empty())
+}
+
+/// Descriptions of the errors within the field errors of a response of slurmrestd
+fn errors_of(body: &Value) -> Vec<String> {
+    body.get("errors")
+        .and_then(Value::as_array)
+        .map(|errors| {
+            errors
+                .iter()
+                .map(|error| {
+                    ["description", "error"]
+                        .into_iter()
+                        .filter_map(|field| error.get(field)?.as_str())
+                        .find(|text| !text.is_empty())
+                        .map_or_else(|| error.to_string(), String::from)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod testing {
+    use std::thread;
+
+    use tiny_http::{Response, Server};
+
+    use super::*;
+
+    /// Responses of a mock of slurmrestd as pairs of status and body
+    const NO_ERRORS: (u16, &str) = (200, r#"{ "errors": [] }"#);
+    const ASSOCIATIONS_OF_ALICE: &str = r#"{
+        "associations": [
+            {
+                "user": "alice", "account": "staff", "cluster": "alpha", "partition": "",
+                "qos": ["basic", "interactive"], "default": { "qos": "basic" }, "is_default": true,
+                "max": { "jobs": { "per": { "count": { "set": true, "infinite": false, "number": 10 } } } }
+            },
+            {
+                "user": "alice", "account": "project-a", "cluster": "alpha", "partition": "gpu",
+                "qos": ["basic"], "default": { "qos": "" }, "is_default": false
+            },
+            { "user": "", "account": "project-a", "cluster": "alpha" }
+        ],
+        "errors": []
+    }"#;
+
+    /// Starts a mock of slurmrestd which answers the requests in order with parameter `responses`.
+    /// Returns a client for the mock and a handle to the received requests
+    /// formatted as method, URL, token and body.
+    fn mock_slurmrestd(
+        responses: Vec<(u16, &'static str)>,
+    ) -> (SlurmRestClient, thread::JoinHandle<Vec<String>>) {
+        let server = Server::http("127.0.0.1:0").expect("Should be able to start a mock server");
+        let address = server
+            .server_addr()
+            .to_ip()
+            .expect("Mock server should listen on an IP address");
+        let received = thread::spawn(move || {
+            responses
+                .into_iter()
+                .map(|(status, body)| {
+                    let mut request = server.recv().expect("Should receive a request");
+                    let token = request
+                        .headers()
+                        .iter()
+                        .find(|header| header.field.equiv(HEADER_OF_TOKEN))
+                        .map(|header| header.value.to_string())
+                        .unwrap_or_default();
+                    let mut content = String::new();
+                    request
+                        .as_reader()
+                        .read_to_string(&mut content)
+                        .expect("Should be able to read the body of a request");
+                    let received = format!(
+                        "{} {} token={} {}",
+                        request.method(),
+                        request.url(),
+                        token,
+                        content
+                    );
+                    request
+                        .respond(Response::from_string(body).with_status_code(status))
+                        .expect("Should be able to respond");
+                    received.trim_end().to_string()
+                })
+                .collect()
+        });
+        let client = SlurmRestClient::new(
+            ureq::AgentBuilder::new().build(),
+            &format!("http://{}/", address),
+            "v0.0.40",
+            "secret".to_string(),
+            Some("alpha".to_string()),
+        );
+        (client, received)
+    }
+
+    fn received_requests(received: thread::JoinHandle<Vec<String>>) -> Vec<String> {
+        received.join().expect("Mock server should not panic")
+    }
+
+    fn association(association: &str) -> UserAssociation {
+        association.parse().expect("Should be a valid association")
+    }
+
+    #[test]
+    fn add_and_delete_user() {
+        let (client, received) = mock_slurmrestd(vec![NO_ERRORS; 5]);
+        let limits = vec!["MaxJobs=10".parse().expect("Should be a valid limit")];
+        client
+            .add_user(
+                "alice",
+                "staff",
+                vec!["basic".to_string(), "interactive".to_string()],
+                "basic".to_string(),
+                &limits,
+            )
+            .expect("Should add the user");
+        client
+            .add_associations("alice", &[association("account=project-a,partition=gpu")])
+            .expect("Should add the associations");
+        client
+            .set_default_account("alice", "project-a")
+            .expect("Should set the default account");
+        client.delete_user("alice").expect("Should delete the user");
+        insta::assert_debug_snapshot!(received_requests(received));
+    }
+
+    #[test]
+    fn list_users() {
+        let (client, received) = mock_slurmrestd(vec![(200, ASSOCIATIONS_OF_ALICE)]);
+        let listed = client
+            .list_users(ShowConditions {
+                users: Some(vec!["alice".to_string()]),
+                ..Default::default()
+            })
+            .expect("Should list the users");
+        insta::assert_debug_snapshot!((received_requests(received), listed));
+    }
+
+    #[test]
+    fn modify_every_association_of_user() {
+        let (client, received) = mock_slurmrestd(vec![(200, ASSOCIATIONS_OF_ALICE), NO_ERRORS]);
+        let limits = vec!["MaxWall=08:00:00".parse().expect("Should be a valid limit")];
+        client
+            .modify_user(
+                "alice",
+                Some((vec!["basic".to_string()], "basic".to_string())),
+                &limits,
+            )
+            .expect("Should modify the user");
+        insta::assert_debug_snapshot!(received_requests(received));
+    }
+
+    #[test]
+    fn associations_of_user_with_default_account() {
+        let (client, received) = mock_slurmrestd(vec![(200, ASSOCIATIONS_OF_ALICE)]);
+        let actual = client
+            .associations_of_user("alice")
+            .expect("Should return the associations");
+        received_requests(received);
+        assert_eq!(Some("staff".to_string()), actual.default_account);
+        assert_eq!(
+            vec![
+                association("account=staff,cluster=alpha"),
+                association("account=project-a,partition=gpu,cluster=alpha"),
+            ],
+            actual.associations
+        );
+    }
+
+    #[test]
+    fn cancel_active_jobs_of_user() {
+        const JOBS: &str = r#"{
+            "jobs": [
+                { "job_id": 4711, "user_name": "alice", "job_state": ["RUNNING"] },
+                { "job_id": 4712, "user_name": "alice", "job_state": "PENDING" },
+                { "job_id": 4713, "user_name": "alice", "job_state": ["COMPLETED"] },
+                { "job_id": 4714, "user_name": "bob", "job_state": ["RUNNING"] }
+            ],
+            "errors": []
+        }"#;
+        let (client, received) = mock_slurmrestd(vec![(200, JOBS), NO_ERRORS, NO_ERRORS]);
+        client
+            .cancel_jobs_of_user("alice")
+            .expect("Should cancel the jobs");
+        assert_eq!(
+            vec![
+                "GET /slurm/v0.0.40/jobs token=secret",
+                "DELETE /slurm/v0.0.40/job/4711 token=secret",
+                "DELETE /slurm/v0.0.40/job/4712 token=secret",
+            ],
+            received_requests(received)
+        );
+    }
+
+    #[test]
+    fn error_for_failed_requests() {
+        let (client, received) = mock_slurmrestd(vec![
+            (
+                200,
+                r#"{ "errors": [{ "description": "Nothing found", "error": "" }] }"#,
+            ),
+            (500, r#"{ "errors": [{ "error": "Internal error" }] }"#),
+        ]);
+        let reported = client
+            .set_default_account("alice", "staff")
+            .expect_err("Should fail because of the reported errors");
+        let status = client
+            .delete_user("alice")
+            .expect_err("Should fail because of the status");
+        received_requests(received);
+        assert!(reported.to_string().ends_with("Nothing found"));
+        assert!(status
+            .to_string()
+            .starts_with("Slurmrestd responded with status 500"));
+        assert!(status.to_string().ends_with("Internal error"));
+    }
+
+    #[test]
+    fn read_token_from_file_or_command() {
+        // Unique per run, so concurrent test runs do not share the file.
+        let unique = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("Should be after the Unix epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!(
+            "usermgmt_test_slurm_rest_token_{}_{}",
+            std::process::id(),
+            unique
+        ));
+        fs::write(&path, "SLURM_JWT=from-file\n").expect("Should be able to write the token file");
+        let config = MgmtConfig {
+            slurm_rest_token_file: Some(path.to_string_lossy().to_string()),
+            slurm_rest_token_command: Some("echo from-command".to_string()),
+            ..Default::default()
+        };
+        let from_file = read_token(&config);
+        fs::remove_file(&path).expect("Should be able to remove the token file");
+        assert_eq!("from-file", from_file.expect("Should read the token"));
+
+        let config = MgmtConfig {
+            slurm_rest_token_command: Some("echo SLURM_JWT=from-command".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            "from-command",
+            read_token(&config).expect("Should run the command")
+        );
+        assert!(read_token(&MgmtConfig::default()).is_err());
+        let config = MgmtConfig {
+            slurm_rest_token_command: Some("exit 1".to_string()),
+            ..Default::default()
+        };
+        assert!(read_token(&config).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use serde_json::{json, Value};
+
+use crate::{prelude::*, util::SlurmLimit};
+
+/// Value which removes a limit, like for sacctmgr
+const NO_LIMIT: &str = "-1";
+const MINUTES_PER_HOUR: u64 = 60;
+const MINUTES_PER_DAY: u64 = 24 * MINUTES_PER_HOUR;
+const MEMORY: &str = "mem";
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    /// Object with the fields set, infinite and number
+    Number,
+    /// Same as number, but the number is a time in minutes
+    Minutes,
+    /// List of trackable resources with the fields type, name and count
+    Tres,
+}
+
+/// Location of every limit of [`SlurmLimit::names`] within the field max of an association of slurmrestd
+const LOCATIONS: [(&str, &[&str], Format); 8] = [
+    ("GrpTRES", &["tres", "total"], Format::Tres),
+    ("GrpTRESMins", &["tres", "group", "minutes"], Format::Tres),
+    ("GrpJobs", &["jobs", "active"], Format::Number),
+    ("GrpSubmitJobs", &["jobs", "total"], Format::Number),
+    ("MaxJobs", &["jobs", "per", "count"], Format::Number),
+    (
+        "MaxSubmitJobs",
+        &["jobs", "per", "submitted"],
+        Format::Number,
+    ),
+    ("MaxTRES", &["tres", "per", "job"], Format::Tres),
+    ("MaxWall", &["jobs", "per", "wall_clock"], Format::Minutes),
+];
+
+/// Returns the field max of an association of slurmrestd which sets parameter `limits`.
+///
+/// # Errors
+///
+/// - If a value of a limit can not be converted for slurmrestd, e.g. a time with an invalid number.
+/// - If a TRES limit is removed as a whole via -1. Slurmrestd needs -1 per resource like cpu=-1.
+pub fn to_json(limits: &[SlurmLimit]) -> AppResult<Value> {
+    let mut max = json!({});
+    for limit in limits {
+        let (_, path, format) = location_of(limit.name());
+        let value = limit.value();
+        let converted = match format {
+            Format::Number if value == NO_LIMIT => no_limit(),
+            Format::Number => number(
+                value
+                    .parse()
+                    .with_context(|| format!("Slurm limit {} is not a number", limit))?,
+            ),
+            Format::Minutes if value == NO_LIMIT => no_limit(),
+            Format::Minutes => number(minutes_of(value)?),
+            Format::Tres if value == NO_LIMIT => bail!(
+                "Slurm limit {} can not be removed as a whole via slurmrestd. Remove every resource instead e.g. {}=cpu=-1",
+                limit,
+                limit.name()
+            ),
+            Format::Tres => tres_to_json(value)?,
+        };
+        insert(&mut max, path, converted);
+    }
+    Ok(max)
+}
+
+/// Returns the limits which are set within parameter `max`, the field max of an association of slurmrestd.
+/// Values are formatted like the ones of sacctmgr.
+pub fn from_json(max: &Value) -> Vec<(&'static str, String)> {
+    LOCATIONS
+        .iter()
+        .filter_map(|(name, path, format)| {
+            let value = path.iter().try_fold(max, |value, key| value.get(key))?;
+            let formatted = match format {
+                Format::Number => set_number(value)?.to_string(),
+                Format::Minutes => format_minutes(set_number(value)?),
+                Format::Tres => tres_from_json(value)?,
+            };
+            Some((*name, formatted))
+        })
+        .collect()
+}
+
+fn location_of(name: &str) -> (&'static str, &'static [&'static str], Format) {
+    *LOCATIONS
+        .iter()
+        .find(|(known, _, _)| *known == name)
+        .expect("Every Slurm limit has a location in the JSON of slurmrestd")
+}
+
+fn insert(target: &mut Value, path: &[&str], value: Value) {
+    match path {
+        [] => *target = value,
+        [key, rest @ ..] => insert(&mut target[*key], rest, value),
+    }
+}
+
+fn number(value: u64) -> Value {
+    json!({ "set": true, "infinite": false, "number": value })
+}
+
+fn no_limit() -> Value {
+    json!({ "set": true, "infinite": true, "number": 0 })
+}
+
+/// Returns the number of parameter `value` if it is set and not infinite
+fn set_number(value: &Value) -> Option<u64> {
+    if value.get("set")?.as_bool()? && !value.get("infinite")?.as_bool()? {
+        value.get("number")?.as_u64()
+    } else {
+        None
+    }
+}
+
+/// Converts trackable resources like cpu=64,gres/gpu=2,mem=64G into the format of slurmrestd
+fn tres_to_json(tres: &str) -> AppResult<Value> {
+    let resources = tres
+        .split(',')
+        .map(|resource| {
+            let (kind, count) = resource
+                .split_once('=')
+                .ok_or_else(|| anyhow!("{} is not a trackable resource of Slurm", resource))?;
+            let (kind, name) = kind.split_once('/').unwrap_or((kind, ""));
+            Ok(json!({ "type": kind, "name": name, "count": count_of(count)? }))
+        })
+        .collect::<AppResult<Vec<Value>>>()?;
+    Ok(Value::Array(resources))
+}
+
+fn tres_from_json(value: &Value) -> Option<String> {
+    let resources: Vec<String> = value
+        .as_array()?
+        .iter()
+        .filter_map(|resource| {
+            let kind = resource.get("type")?.as_str()?;
+            let name = resource.get("name").and_then(Value::as_str).unwrap_or("");
+            let count = resource.get("count")?.as_i64()?;
+            let unit = if kind == MEMORY { "M" } else { "" };
+            Some(if name.is_empty() {
+                format!("{}={}{}", kind, count, unit)
+            } else {
+                format!("{}/{}={}{}", kind, name, count, unit)
+            })
+        })
+        .collect();
+    (!resources.is_empty()).then(|| resources.join(","))
+}
+
+/// Count of a trackable resource as expected by slurmrestd.
+/// Sizes with a unit like 64G are converted into megabytes, the unit of memory in Slurm.
+fn count_of(count: &str) -> AppResult<i64> {
+    if count == NO_LIMIT {
+        return Ok(-1);
+    }
+    let digits = count.trim_end_matches(|char: char| char.is_ascii_alphabetic());
+    let number: i64 = digits
+        .parse()
+        .with_context(|| format!("{} is not a valid count of a trackable resource", count))?;
+    let factor = match count[digits.len()..].to_ascii_uppercase().as_str() {
+        "" | "M" => 1,
+        "G" => 1024,
+        "T" => 1024 * 1024,
+        "P" => 1024 * 1024 * 1024,
+        unit => bail!(
+            "Unit {} of {} is not supported. Supported units are M, G, T and P",
+            unit,
+            count
+        ),
+    };
+    Ok(number * factor)
+}
+
+/// Minutes of a time limit of Slurm like 90, 90:30, 08:00:00, 2-00 or 2-00:00:00.
+/// Seconds are rounded up to the next minute.
+fn minutes_of(time: &str) -> AppResult<u64> {
+    let invalid = || {
+        anyhow!(
+            "{} is not a valid time limit of Slurm e.g. 90, 08:00:00 or 2-00:00:00",
+            time
+        )
+    };
+    let (days, rest) = match time.split_once('-') {
+        Some((days, rest)) => (Some(days.parse::<u64>().map_err(|_| invalid())?), rest),
+        None => (None, time),
+    };
+    let parts = rest
+        .split(':')
+        .map(str::parse::<u64>)
+        .collect::<Result<Vec<u64>, _>>()
+        .map_err(|_| invalid())?;
+    let (hours, minutes, seconds) = match (days, parts.as_slice()) {
+        (None, &[minutes]) => (0, minutes, 0),
+        (None, &[minutes, seconds]) => (0, minutes, seconds),
+        (Some(_), &[hours]) => (hours, 0, 0),
+        (Some(_), &[hours, minutes]) => (hours, minutes, 0),
+        (_, &[hours, minutes, seconds]) => (hours, minutes, seconds),
+        _ => return Err(invalid()),
+    };
+    Ok(days.unwrap_or(0) * MINUTES_PER_DAY
+        + hours * MINUTES_PER_HOUR
+        + minutes
+        + seconds / 60
+        + u64::from(seconds % 60 > 0))
+}
+
+fn format_minutes(minutes: u64) -> String {
+    let days = minutes / MINUTES_PER_DAY;
+    let hours = minutes % MINUTES_PER_DAY / MINUTES_PER_HOUR;
+    let minutes = minutes % MINUTES_PER_HOUR;
+    if days > 0 {
+        format!("{}-{:02}:{:02}:00", days, hours, minutes)
+    } else {
+        format!("{:02}:{:02}:00", hours, minutes)
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn limits(limits: &[&str]) -> Vec<SlurmLimit> {
+        limits
+            .iter()
+            .map(|limit| limit.parse().expect("Should be a valid limit"))
+            .collect()
+    }
+
+    #[test]
+    fn every_limit_has_a_location() {
+        assert_eq!(
+            SlurmLimit::names().collect::<Vec<_>>(),
+            LOCATIONS
+                .iter()
+                .map(|(name, _, _)| *name)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn convert_limits_to_json_and_back() {
+        let max = to_json(&limits(&[
+            "GrpTRES=cpu=64,gres/gpu=2,mem=64G",
+            "MaxJobs=10",
+            "GrpJobs=-1",
+            "MaxWall=2-00:00:00",
+        ]))
+        .expect("Should be convertible limits");
+        insta::assert_yaml_snapshot!(max);
+        assert_eq!(
+            vec![
+                ("GrpTRES", "cpu=64,gres/gpu=2,mem=65536M".to_string()),
+                ("MaxJobs", "10".to_string()),
+                ("MaxWall", "2-00:00:00".to_string()),
+            ],
+            from_json(&max)
+        );
+    }
+
+    #[test]
+    fn minutes_of_time_limits() {
+        assert_eq!(90, minutes_of("90").expect("Should be valid"));
+        assert_eq!(91, minutes_of("90:30").expect("Should be valid"));
+        assert_eq!(480, minutes_of("08:00:00").expect("Should be valid"));
+        assert_eq!(2 * 24 * 60, minutes_of("2-00").expect("Should be valid"));
+        assert_eq!(
+            2 * 24 * 60 + 90,
+            minutes_of("2-01:30").expect("Should be valid")
+        );
+        assert!(minutes_of("1:2:3:4").is_err());
+        assert!(minutes_of("2-").is_err());
+        assert_eq!("01:30:00", format_minutes(90));
+    }
+
+    #[test]
+    fn error_for_limits_not_convertible() {
+        assert!(to_json(&limits(&["GrpTRES=-1"])).is_err());
+        assert!(to_json(&limits(&["GrpTRES=mem=1K"])).is_err());
+        assert!(to_json(&limits(&["MaxWall=1-2-3"])).is_err());
+    }
+}
@@ -0,0 +1,392 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, ensure};
+use chrono::{Days, NaiveDate};
+use prettytable::{Cell, Row, Table};
+use serde::Serialize;
+
+use crate::{
+    config::MgmtConfig,
+    prelude::*,
+    ssh::{SshConnection, SshCredentials},
+};
+
+use super::slurm_jobs::{clusters_arg, run_slurm_tool};
+
+const SACCT: &str = "sacct";
+const SREPORT: &str = "sreport";
+const SEPARATOR_OF_CELLS: char = '|';
+const SEPARATOR_OF_TRES: char = ',';
+const CPU: &str = "cpu";
+const GPU: &str = "gres/gpu";
+/// Period of the report without --from
+const DEFAULT_DAYS_OF_PERIOD: u64 = 30;
+const FORMAT_OF_DATE: &str = "%Y-%m-%d";
+const SECS_PER_HOUR: f64 = 3600.0;
+const MINUTES_PER_HOUR: f64 = 60.0;
+
+/// Days from parameter `from` until parameter `to` including both days
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsagePeriod {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+impl UsagePeriod {
+    /// Parameter `to` defaults to parameter `today`
+    /// and parameter `from` to 30 days before `to`.
+    ///
+    /// # Errors
+    ///
+    /// - If `from` is after `to`.
+    pub fn new(
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        today: NaiveDate,
+    ) -> AppResult<Self> {
+        let to = to.unwrap_or(today);
+        let from = match from {
+            Some(from) => from,
+            None => to
+                .checked_sub_days(Days::new(DEFAULT_DAYS_OF_PERIOD))
+                .ok_or_else(|| anyhow!("No start of the usage report before {}", to))?,
+        };
+        ensure!(
+            from <= to,
+            "Start {} of the usage report is after its end {}",
+            from,
+            to
+        );
+        Ok(Self { from, to })
+    }
+
+    fn start(&self) -> String {
+        self.from.format(FORMAT_OF_DATE).to_string()
+    }
+
+    /// Slurm expects the end as the first moment which is not included anymore
+    fn end_exclusive(&self) -> String {
+        self.to
+            .checked_add_days(Days::new(1))
+            .unwrap_or(self.to)
+            .format(FORMAT_OF_DATE)
+            .to_string()
+    }
+}
+
+/// Usage of the jobs of a user with the same cluster, account and QOS as listed by sacct
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobUsage {
+    pub cluster: String,
+    pub account: String,
+    pub qos: String,
+    pub jobs: u64,
+    pub cpu_hours: f64,
+    pub gpu_hours: f64,
+}
+
+/// Usage of a user within an account as accounted by Slurm and reported by sreport
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountedUsage {
+    pub cluster: String,
+    pub account: String,
+    pub cpu_hours: f64,
+    pub gpu_hours: f64,
+}
+
+/// Usage of a user in Slurm within a period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageReport {
+    pub user: String,
+    pub from: String,
+    /// Last day of the report, inclusive
+    pub to: String,
+    /// Jobs which ran within the period. Their hours include the time outside of the period.
+    pub jobs: Vec<JobUsage>,
+    /// Usage accounted by Slurm for the period only
+    pub accounted: Vec<AccountedUsage>,
+}
+
+impl UsageReport {
+    pub fn new(user: &str, period: UsagePeriod) -> Self {
+        Self {
+            user: user.to_string(),
+            from: period.start(),
+            to: period.to.format(FORMAT_OF_DATE).to_string(),
+            jobs: Vec::new(),
+            accounted: Vec::new(),
+        }
+    }
+
+    /// Adds the usage of another cluster
+    pub fn merge(&mut self, other: Self) {
+        self.jobs.extend(other.jobs);
+        self.accounted.extend(other.accounted);
+    }
+
+    pub fn to_pretty_tables(&self) -> String {
+        let mut jobs = Table::new();
+        jobs.set_titles(titles(&[
+            "Cluster",
+            "Account",
+            "QOS",
+            "Jobs",
+            "CPU hours",
+            "GPU hours",
+        ]));
+        for usage in &self.jobs {
+            jobs.add_row(Row::new(vec![
+                Cell::new(&usage.cluster),
+                Cell::new(&usage.account),
+                Cell::new(&usage.qos),
+                Cell::new(&usage.jobs.to_string()),
+                Cell::new(&format!("{:.2}", usage.cpu_hours)),
+                Cell::new(&format!("{:.2}", usage.gpu_hours)),
+            ]));
+        }
+        let mut accounted = Table::new();
+        accounted.set_titles(titles(&["Cluster", "Account", "CPU hours", "GPU hours"]));
+        for usage in &self.accounted {
+            accounted.add_row(Row::new(vec![
+                Cell::new(&usage.cluster),
+                Cell::new(&usage.account),
+                Cell::new(&format!("{:.2}", usage.cpu_hours)),
+                Cell::new(&format!("{:.2}", usage.gpu_hours)),
+            ]));
+        }
+        format!(
+            "Jobs of user {} from {} to {} (sacct):\n{}\nUsage accounted by Slurm (sreport):\n{}",
+            self.user, self.from, self.to, jobs, accounted
+        )
+    }
+
+    /// # Errors
+    ///
+    /// - If the report can not be serialized.
+    pub fn to_json(&self) -> AppResult<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize the usage report as JSON")
+    }
+}
+
+/// Queries the usage of a user via sacct and sreport on the cluster of parameter `config`.
+///
+/// # Errors
+///
+/// - If sacct or sreport fails. See [`run_slurm_tool`]
+/// - If the output of sacct or sreport is malformed.
+pub fn usage_of_user<C>(
+    user: &str,
+    period: UsagePeriod,
+    config: &MgmtConfig,
+    session: &SshConnection<C>,
+) -> AppResult<UsageReport>
+where
+    C: SshCredentials,
+{
+    let mut sacct_args = vec![
+        format!("--user={}", user),
+        "--allocations".to_string(),
+        "--parsable2".to_string(),
+        "--noheader".to_string(),
+        format!("--starttime={}", period.start()),
+        format!("--endtime={}", period.end_exclusive()),
+        "--format=Cluster,Account,QOS,ElapsedRaw,AllocTRES".to_string(),
+    ];
+    sacct_args.extend(clusters_arg(config));
+    let sacct_output = run_slurm_tool(SACCT, sacct_args, config, session)?;
+
+    let mut sreport_args = vec![
+        "--parsable2".to_string(),
+        "--noheader".to_string(),
+        format!("--tres={},{}", CPU, GPU),
+        "-t".to_string(),
+        "Minutes".to_string(),
+        "cluster".to_string(),
+        "AccountUtilizationByUser".to_string(),
+        format!("Users={}", user),
+        format!("Start={}", period.start()),
+        format!("End={}", period.end_exclusive()),
+    ];
+    if let Some(cluster) = &config.slurm_cluster {
+        sreport_args.push(format!("Clusters={}", cluster));
+    }
+    let sreport_output = run_slurm_tool(SREPORT, sreport_args, config, session)?;
+
+    let mut report = UsageReport::new(user, period);
+    report.jobs = parse_sacct(&sacct_output).context("Failed to parse the jobs listed by sacct")?;
+    report.accounted = parse_sreport(&sreport_output, user)
+        .context("Failed to parse the usage reported by sreport")?;
+    Ok(report)
+}
+
+/// Sums up the jobs of the output of sacct per cluster, account and QOS.
+/// Expects the columns cluster, account, QOS, elapsed seconds and allocated TRES.
+fn parse_sacct(output: &str) -> AppResult<Vec<JobUsage>> {
+    let mut usages: BTreeMap<(&str, &str, &str), (u64, u64, u64)> = BTreeMap::new();
+    for line in lines_of(output) {
+        let [cluster, account, qos, elapsed, tres] = cells(line)[..] else {
+            bail!(
+                "Expected 5 columns in the output of sacct, but got line: {}",
+                line
+            );
+        };
+        let elapsed: u64 = elapsed
+            .parse()
+            .with_context(|| format!("Elapsed seconds {} of a job are not a number", elapsed))?;
+        let (cpus, gpus) = cpus_and_gpus(tres)?;
+        let usage = usages.entry((cluster, account, qos)).or_default();
+        usage.0 += 1;
+        usage.1 += cpus * elapsed;
+        usage.2 += gpus * elapsed;
+    }
+    Ok(usages
+        .into_iter()
+        .map(
+            |((cluster, account, qos), (jobs, cpu_secs, gpu_secs))| JobUsage {
+                cluster: cluster.to_string(),
+                account: account.to_string(),
+                qos: qos.to_string(),
+                jobs,
+                cpu_hours: rounded(cpu_secs as f64 / SECS_PER_HOUR),
+                gpu_hours: rounded(gpu_secs as f64 / SECS_PER_HOUR),
+            },
+        )
+        .collect())
+}
+
+/// Usage per cluster and account from the output of sreport in minutes.
+/// Expects the columns cluster, account, login, proper name, TRES name and used minutes.
+/// Lines of accounts without parameter `user` as login are skipped.
+fn parse_sreport(output: &str, user: &str) -> AppResult<Vec<AccountedUsage>> {
+    let mut usages: BTreeMap<(&str, &str), (f64, f64)> = BTreeMap::new();
+    for line in lines_of(output) {
+        let [cluster, account, login, _, tres, used] = cells(line)[..] else {
+            bail!(
+                "Expected 6 columns in the output of sreport, but got line: {}",
+                line
+            );
+        };
+        if login != user {
+            continue;
+        }
+        let used: f64 = used
+            .parse()
+            .with_context(|| format!("Used minutes {} of {} are not a number", used, tres))?;
+        let usage = usages.entry((cluster, account)).or_default();
+        match tres {
+            CPU => usage.0 += used,
+            GPU => usage.1 += used,
+            _ => {}
+        }
+    }
+    Ok(usages
+        .into_iter()
+        .map(
+            |((cluster, account), (cpu_minutes, gpu_minutes))| AccountedUsage {
+                cluster: cluster.to_string(),
+                account: account.to_string(),
+                cpu_hours: rounded(cpu_minutes / MINUTES_PER_HOUR),
+                gpu_hours: rounded(gpu_minutes / MINUTES_PER_HOUR),
+            },
+        )
+        .collect())
+}
+
+/// Number of CPUs and GPUs within allocated trackable resources like cpu=4,gres/gpu=1,mem=16G.
+/// Resources of a job which did not start are empty.
+fn cpus_and_gpus(tres: &str) -> AppResult<(u64, u64)> {
+    let (mut cpus, mut gpus) = (0, 0);
+    for resource in tres
+        .split(SEPARATOR_OF_TRES)
+        .filter(|resource| !resource.is_empty())
+    {
+        let Some((name, count)) = resource.split_once('=') else {
+            bail!("{} is not an allocated trackable resource", resource);
+        };
+        let count = || {
+            count
+                .parse::<u64>()
+                .with_context(|| format!("Count of {} is not a number", resource))
+        };
+        match name {
+            CPU => cpus = count()?,
+            GPU => gpus = count()?,
+            _ => {}
+        }
+    }
+    Ok((cpus, gpus))
+}
+
+fn lines_of(output: &str) -> impl Iterator<Item = &str> {
+    output.lines().filter(|line| !line.trim().is_empty())
+}
+
+fn cells(line: &str) -> Vec<&str> {
+    line.split(SEPARATOR_OF_CELLS).map(str::trim).collect()
+}
+
+fn rounded(hours: f64) -> f64 {
+    (hours * 100.0).round() / 100.0
+}
+
+fn titles(names: &[&str]) -> Row {
+    Row::new(names.iter().map(|name| Cell::new(name)).collect())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    const OUTPUT_OF_SACCT: &str = "\
+alpha|staff|basic|3600|billing=4,cpu=4,mem=16G,node=1
+alpha|staff|basic|1800|billing=8,cpu=8,gres/gpu=2,gres/gpu:a100=2,mem=32G,node=1
+alpha|staff|gpubasic|7200|cpu=2,gres/gpu=1,mem=8G,node=1
+alpha|project-a|basic|0|
+";
+    const OUTPUT_OF_SREPORT: &str = "\
+alpha|staff|alice|Alice Example|cpu|480
+alpha|staff|alice|Alice Example|gres/gpu|150
+alpha|staff|||cpu|9000
+alpha|project-a|alice|Alice Example|cpu|0
+";
+
+    fn date(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, FORMAT_OF_DATE).expect("Should be a valid date")
+    }
+
+    #[test]
+    fn report_usage_of_user() {
+        let period = UsagePeriod::new(Some(date("2024-01-01")), None, date("2024-01-31"))
+            .expect("Should be a valid period");
+        let mut report = UsageReport::new("alice", period);
+        report.jobs = parse_sacct(OUTPUT_OF_SACCT).expect("Should be valid output of sacct");
+        report.accounted =
+            parse_sreport(OUTPUT_OF_SREPORT, "alice").expect("Should be valid output of sreport");
+        insta::assert_snapshot!(report.to_pretty_tables());
+        insta::assert_snapshot!(report.to_json().expect("Should be serializable"));
+    }
+
+    #[test]
+    fn period_of_usage_report() {
+        let period = UsagePeriod::new(None, None, date("2024-03-01")).expect("Should be valid");
+        assert_eq!(
+            ("2024-01-31".to_string(), "2024-03-02".to_string()),
+            (period.start(), period.end_exclusive())
+        );
+        assert!(UsagePeriod::new(
+            Some(date("2024-02-01")),
+            Some(date("2024-01-01")),
+            date("2024-03-01")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn error_for_malformed_output() {
+        assert!(parse_sacct("alpha|staff|basic|3600").is_err());
+        assert!(parse_sacct("alpha|staff|basic|soon|cpu=1").is_err());
+        assert!(parse_sacct("alpha|staff|basic|60|cpu=many").is_err());
+        assert!(parse_sreport("alpha|staff|alice|cpu|480", "alice").is_err());
+        assert!(parse_sreport("alpha|staff|alice||cpu|lots", "alice").is_err());
+    }
+}
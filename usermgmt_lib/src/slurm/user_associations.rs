@@ -0,0 +1,190 @@
+use anyhow::{bail, ensure};
+
+use crate::{
+    prelude::AppResult,
+    util::{TrimmedNonEmptyText, UserAssociation},
+};
+
+const NUMBER_OF_COLUMNS: usize = 4;
+
+/// Associations of a user in Slurm together with the default account of the user
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AssociationsOfUser {
+    /// None if the user does not exist in Slurm
+    pub default_account: Option<String>,
+    pub associations: Vec<UserAssociation>,
+}
+
+impl AssociationsOfUser {
+    /// Parses the output of sacctmgr listing the associations of a user.
+    /// See [`super::command_builder::CommandBuilder::new_show_user_associations`].
+    ///
+    /// # Errors
+    ///
+    /// - If a line does not have the expected number of columns.
+    /// - If a line has no account.
+    pub fn parse(output: &str) -> AppResult<Self> {
+        let mut parsed = Self::default();
+        for line in output.lines().filter(|line| !line.trim().is_empty()) {
+            let cells: Vec<&str> = line.split('|').collect();
+            let [default_account, account, partition, cluster] = cells[..] else {
+                bail!(
+                    "Expected {} columns separated by | in the list of Slurm associations, but got line: {}",
+                    NUMBER_OF_COLUMNS,
+                    line
+                );
+            };
+            let optional = |value: &str| TrimmedNonEmptyText::try_from(value).ok();
+            parsed.default_account = optional(default_account).map(String::from);
+            parsed.associations.push(UserAssociation::new(
+                account.try_into()?,
+                optional(partition),
+                optional(cluster),
+            ));
+        }
+        Ok(parsed)
+    }
+
+    /// Returns the account which has to be set as default account after the associations of parameter
+    /// `to_add` were added and the ones matching parameter `to_remove` were removed.
+    /// Returns none if the current default account stays in place.
+    ///
+    /// Parameter `requested` is the account explicitly requested as default.
+    /// Without it, the current default account is kept as long as an association with it remains.
+    /// Otherwise the first remaining account becomes the default.
+    ///
+    /// # Errors
+    ///
+    /// - If an association to remove matches none of the existing associations.
+    /// - If no association would remain.
+    /// - If parameter `requested` is not the account of any remaining association.
+    pub fn next_default_account(
+        &self,
+        to_add: &[UserAssociation],
+        to_remove: &[UserAssociation],
+        requested: Option<&str>,
+    ) -> AppResult<Option<String>> {
+        for removed in to_remove {
+            ensure!(
+                self.associations
+                    .iter()
+                    .any(|existing| removed.matches(existing)),
+                "User has no Slurm association {} to remove",
+                removed
+            );
+        }
+        let remaining: Vec<&str> = self
+            .associations
+            .iter()
+            .filter(|existing| !to_remove.iter().any(|removed| removed.matches(existing)))
+            .chain(to_add.iter())
+            .map(UserAssociation::account)
+            .collect();
+        ensure!(
+            !remaining.is_empty(),
+            "No Slurm association of the user would remain. Delete the user instead."
+        );
+
+        let current = self.default_account.as_deref();
+        let next = match requested {
+            Some(requested) => {
+                ensure!(
+                    remaining.contains(&requested),
+                    "Default account {} is not the account of any Slurm association of the user",
+                    requested
+                );
+                requested
+            }
+            None => match current {
+                Some(current) if remaining.contains(&current) => current,
+                _ => remaining[0],
+            },
+        };
+        Ok((Some(next) != current).then(|| next.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    const OUTPUT: &str = "\
+staff|staff||alpha
+staff|project-a|gpu|alpha
+staff|project-a|cpu|alpha
+";
+
+    fn parse(association: &str) -> UserAssociation {
+        association.parse().expect("Should be a valid association")
+    }
+
+    #[test]
+    fn parse_associations_of_user() {
+        let actual = AssociationsOfUser::parse(OUTPUT).expect("Should be valid output");
+        assert_eq!(
+            AssociationsOfUser {
+                default_account: Some("staff".to_string()),
+                associations: vec![
+                    parse("account=staff,cluster=alpha"),
+                    parse("account=project-a,partition=gpu,cluster=alpha"),
+                    parse("account=project-a,partition=cpu,cluster=alpha"),
+                ]
+            },
+            actual
+        );
+        assert_eq!(
+            AssociationsOfUser::default(),
+            AssociationsOfUser::parse("").expect("Should be valid output")
+        );
+        assert!(AssociationsOfUser::parse("staff|staff").is_err());
+    }
+
+    #[test]
+    fn keep_or_change_default_account() {
+        let existing = AssociationsOfUser::parse(OUTPUT).expect("Should be valid output");
+        let next =
+            |to_add: &[UserAssociation], to_remove: &[UserAssociation], requested: Option<&str>| {
+                existing.next_default_account(to_add, to_remove, requested)
+            };
+
+        assert_eq!(
+            None,
+            next(&[parse("project-b")], &[], None).expect("Should be valid changes")
+        );
+        assert_eq!(
+            None,
+            next(&[], &[parse("account=project-a,partition=gpu")], None)
+                .expect("Should be valid changes")
+        );
+        assert_eq!(
+            Some("project-b".to_string()),
+            next(&[parse("project-b")], &[], Some("project-b")).expect("Should be valid changes")
+        );
+        assert_eq!(
+            None,
+            next(&[], &[], Some("staff")).expect("Should be valid changes")
+        );
+        // Default account is removed, so the first remaining account takes over.
+        assert_eq!(
+            Some("project-a".to_string()),
+            next(&[], &[parse("staff")], None).expect("Should be valid changes")
+        );
+    }
+
+    #[test]
+    fn error_for_invalid_changes_of_associations() {
+        let existing = AssociationsOfUser::parse(OUTPUT).expect("Should be valid output");
+        assert!(existing
+            .next_default_account(&[], &[parse("project-b")], None)
+            .is_err());
+        assert!(existing
+            .next_default_account(&[], &[parse("staff"), parse("project-a")], None)
+            .is_err());
+        assert!(existing
+            .next_default_account(&[], &[parse("staff")], Some("staff"))
+            .is_err());
+        assert!(existing
+            .next_default_account(&[], &[], Some("project-b"))
+            .is_err());
+    }
+}
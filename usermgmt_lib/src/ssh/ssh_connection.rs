@@ -1,13 +1,14 @@
 use crate::prelude::*;
 
-use std::io::Read;
+use std::io::{ErrorKind, Read};
 use std::net::{SocketAddr, TcpStream};
+use std::thread;
 use std::time::Duration;
 
 use once_cell::unsync::OnceCell;
 
 use log::{info, warn};
-use ssh2::Session;
+use ssh2::{Channel, Session};
 
 use crate::config::MgmtConfig;
 use crate::prelude::AppResult;
@@ -59,6 +60,17 @@ where
     /// - If output or exit code of executed remote command could not be retrieved.
     ///
     pub fn exec(&self, cmd: &str) -> AppResult<(String, i32)> {
+        self.exec_with_stderr(cmd)
+            .map(|(output, _, exit_status)| (output, exit_status))
+    }
+
+    /// Same as [`SshConnection::exec`], but returns the output on stderr too.
+    /// Returns stdout, stderr and the exit code in this order.
+    ///
+    /// # Error
+    ///
+    /// - See [`SshConnection::exec`]
+    pub fn exec_with_stderr(&self, cmd: &str) -> AppResult<(String, String, i32)> {
         let session = self
             .session
             .get_or_try_init(|| -> AppResult<Session> { self.establish_connection() })?;
@@ -71,15 +83,17 @@ where
             .exec(cmd)
             .context("Execution of command on remote machine over SSH has failed.")?;
 
-        let mut output = String::new();
-        channel
-            .read_to_string(&mut output)
-            .context("Could not read output of executed command over SSH channel")?;
+        // Both streams are read alternately. Reading one to its end first blocks forever
+        // once the command fills the window of the SSH channel with output on the other one.
+        session.set_blocking(false);
+        let outputs = read_stdout_and_stderr(&mut channel);
+        session.set_blocking(true);
+        let (output, error_output) = outputs?;
         let exit_status = channel
             .exit_status()
             .context("Could not retrieve exit code of executed command over SSH")?;
 
-        Ok((output, exit_status))
+        Ok((output, error_output, exit_status))
     }
 
     pub fn establish_connection(&self) -> AppResult<Session> {
@@ -255,3 +269,46 @@ fn try_authenticate_via_ssh_agent(
     agent.userauth(username, &chosen_key)?;
     Ok(())
 }
+
+/// Reads stdout and stderr of parameter `channel` until the end of both.
+/// The session of the channel must be in non-blocking mode.
+///
+/// # Errors
+///
+/// - If reading from one of the streams fails
+fn read_stdout_and_stderr(channel: &mut Channel) -> AppResult<(String, String)> {
+    /// Appends the available bytes of parameter `stream` to parameter `target`.
+    /// Returns true if bytes were available.
+    fn read_available(stream: &mut impl Read, target: &mut Vec<u8>) -> std::io::Result<bool> {
+        let mut buffer = [0; 4096];
+        match stream.read(&mut buffer) {
+            Ok(read) => {
+                target.extend_from_slice(&buffer[..read]);
+                Ok(read > 0)
+            }
+            Err(error) if error.kind() == ErrorKind::WouldBlock => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    let (mut output, mut error_output) = (Vec::new(), Vec::new());
+    loop {
+        let read_output = read_available(channel, &mut output)
+            .context("Could not read output of executed command over SSH channel")?;
+        let read_error_output = read_available(&mut channel.stderr(), &mut error_output)
+            .context("Could not read error output of executed command over SSH channel")?;
+        if read_output || read_error_output {
+            continue;
+        }
+        if channel.eof() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(
+            constants::SSH_POLL_INTERVAL_MILL_SECS,
+        ));
+    }
+    Ok((
+        String::from_utf8_lossy(&output).to_string(),
+        String::from_utf8_lossy(&error_output).to_string(),
+    ))
+}
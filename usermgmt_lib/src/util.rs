@@ -1,11 +1,21 @@
+mod extra_ldap_attribute;
 mod gid;
+mod password_generator;
 mod result_accumulator;
+mod slurm_limit;
+mod ssh_public_key;
 mod trimmed_non_empty_text;
+mod user_association;
 mod valid_qos;
 
+pub use extra_ldap_attribute::{ensure_allowed_extra_attribute, ExtraLdapAttribute};
 pub use gid::ResolvedGid;
+pub use password_generator::generate_password;
 pub use result_accumulator::ResultAccumulator;
+pub use slurm_limit::{ensure_unique_names, SlurmLimit};
+pub use ssh_public_key::{ensure_not_duplicate, SshPublicKey};
 pub use trimmed_non_empty_text::TrimmedNonEmptyText;
+pub use user_association::UserAssociation;
 pub use valid_qos::{ValidGroupOfQos, ValidQos};
 
 use crate::prelude::AppResult;
@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use derive_more::Display;
+
+use crate::prelude::*;
+
+use super::TrimmedNonEmptyText;
+
+const NAME_VALUE_SEPARATOR: char = '=';
+
+/// Site-specific LDAP attribute of an user which is not covered by the other fields,
+/// for example `employeeNumber` or `telephoneNumber`.
+/// Parsed from text in the form of `name=value`.
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
+#[display(fmt = "{}={}", name, value)]
+pub struct ExtraLdapAttribute {
+    name: TrimmedNonEmptyText,
+    value: TrimmedNonEmptyText,
+}
+
+impl ExtraLdapAttribute {
+    pub fn new(name: TrimmedNonEmptyText, value: TrimmedNonEmptyText) -> Self {
+        Self { name, value }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    pub fn value(&self) -> &str {
+        self.value.as_ref()
+    }
+}
+
+impl FromStr for ExtraLdapAttribute {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s.split_once(NAME_VALUE_SEPARATOR).ok_or_else(|| {
+            anyhow!(
+                "Extra LDAP attribute `{}` must be given in the form of name{}value",
+                s,
+                NAME_VALUE_SEPARATOR
+            )
+        })?;
+        let name = TrimmedNonEmptyText::try_from(name)
+            .with_context(|| format!("Name of extra LDAP attribute `{}` is not valid", s))?;
+        let value = TrimmedNonEmptyText::try_from(value)
+            .with_context(|| format!("Value of extra LDAP attribute `{}` is not valid", s))?;
+        Ok(Self::new(name, value))
+    }
+}
+
+/// # Errors
+///
+/// - If parameter `name` is not listed within the allowed attribute names aka parameter `allowed`
+pub fn ensure_allowed_extra_attribute(name: &str, allowed: &[impl AsRef<str>]) -> AppResult {
+    if allowed.iter().any(|next| next.as_ref() == name) {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "LDAP attribute {} is not listed in the allowed extra attributes (ldap_extra_attributes)",
+            name
+        ))
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_name_and_value() {
+        let actual = ExtraLdapAttribute::from_str(" employeeNumber = 4711=1 ")
+            .expect("Should parse valid name and value");
+        assert_eq!("employeeNumber", actual.name());
+        assert_eq!("4711=1", actual.value());
+    }
+
+    #[test]
+    fn error_for_missing_separator_name_or_value() {
+        assert!(ExtraLdapAttribute::from_str("employeeNumber").is_err());
+        assert!(ExtraLdapAttribute::from_str(" =4711").is_err());
+        assert!(ExtraLdapAttribute::from_str("employeeNumber= ").is_err());
+    }
+
+    #[test]
+    fn error_for_not_allowed_name() {
+        let allowed = ["employeeNumber", "telephoneNumber"];
+        assert!(ensure_allowed_extra_attribute("telephoneNumber", &allowed).is_ok());
+        assert!(ensure_allowed_extra_attribute("userPassword", &allowed).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use rand::seq::SliceRandom;
+
+use crate::{config::MgmtConfig, prelude::*};
+
+/// Generates a random password with the length and characters given by the
+/// fields `generated_password_length` and `generated_password_charset` of the configuration.
+///
+/// # Errors
+///
+/// - If the configured length is zero.
+/// - If the configured character set is empty.
+pub fn generate_password(config: &MgmtConfig) -> AppResult<String> {
+    let charset: Vec<char> = config.generated_password_charset.chars().collect();
+    let length = config.generated_password_length;
+    if length == 0 {
+        bail!("Length of generated passwords (generated_password_length) must not be zero");
+    }
+    if charset.is_empty() {
+        bail!("Characters for generated passwords (generated_password_charset) must not be empty");
+    }
+
+    let mut rng = rand::thread_rng();
+    let password = (0..length)
+        .map(|_| {
+            *charset
+                .choose(&mut rng)
+                .expect("Previous check made sure that the charset is not empty")
+        })
+        .collect();
+    Ok(password)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn generate_with_configured_length_and_charset() {
+        let config = MgmtConfig {
+            generated_password_length: 32,
+            generated_password_charset: "ab".to_string(),
+            ..Default::default()
+        };
+        let actual = generate_password(&config).expect("Should generate with valid config");
+        assert_eq!(32, actual.chars().count());
+        assert!(actual.chars().all(|next| next == 'a' || next == 'b'));
+    }
+
+    #[test]
+    fn error_for_zero_length_or_empty_charset() {
+        let zero_length = MgmtConfig {
+            generated_password_length: 0,
+            ..Default::default()
+        };
+        let empty_charset = MgmtConfig {
+            generated_password_charset: String::new(),
+            ..Default::default()
+        };
+        assert!(generate_password(&zero_length).is_err());
+        assert!(generate_password(&empty_charset).is_err());
+    }
+}
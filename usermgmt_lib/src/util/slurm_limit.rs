@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use derive_more::Display;
+
+use crate::{cli, config::MgmtConfig, prelude::*, Group};
+
+use super::TrimmedNonEmptyText;
+
+const NAME_VALUE_SEPARATOR: char = '=';
+/// Value which removes a limit from an association in Slurm
+const NO_LIMIT: &str = "-1";
+
+#[derive(Debug, Clone, Copy)]
+enum LimitKind {
+    /// Number of jobs e.g. 10
+    Count,
+    /// Trackable resources e.g. cpu=64,gres/gpu=2
+    Tres,
+    /// Time limit e.g. 2-00:00:00
+    Time,
+}
+
+/// Limits of an association in Slurm which can be set for a user.
+/// Names as expected by sacctmgr for setting and showing them.
+const LIMITS: [(&str, LimitKind); 8] = [
+    ("GrpTRES", LimitKind::Tres),
+    ("GrpTRESMins", LimitKind::Tres),
+    ("GrpJobs", LimitKind::Count),
+    ("GrpSubmitJobs", LimitKind::Count),
+    ("MaxJobs", LimitKind::Count),
+    ("MaxSubmitJobs", LimitKind::Count),
+    ("MaxTRES", LimitKind::Tres),
+    ("MaxWall", LimitKind::Time),
+];
+
+/// Resource limit of the association of a user in Slurm, for example `GrpTRES=cpu=64` or `MaxJobs=10`.
+/// Parsed from text in the form of `name=value`. The name is case insensitive.
+/// The value -1 removes the limit.
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
+#[display(fmt = "{}={}", name, value)]
+pub struct SlurmLimit {
+    name: &'static str,
+    value: TrimmedNonEmptyText,
+}
+
+impl SlurmLimit {
+    /// Names of all limits which can be set
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        LIMITS.iter().map(|(name, _)| *name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn value(&self) -> &str {
+        self.value.as_ref()
+    }
+
+    /// Returns the default limits for users of parameter `group`.
+    /// Faculty users get the same limits as staff users.
+    ///
+    /// # Errors
+    ///
+    /// - If a limit in `student_slurm_limits` or `staff_slurm_limits` of the configuration is not valid.
+    pub fn from_group(group: Group, config: &MgmtConfig) -> AppResult<Vec<Self>> {
+        let (field, limits) = match group {
+            Group::Student => ("student_slurm_limits", &config.student_slurm_limits),
+            Group::Staff | Group::Faculty => ("staff_slurm_limits", &config.staff_slurm_limits),
+        };
+        let limits: Vec<Self> = limits
+            .iter()
+            .map(|limit| {
+                limit.parse().with_context(|| {
+                    format!("Invalid Slurm limit in {} of the configuration", field)
+                })
+            })
+            .collect::<AppResult<_>>()?;
+        ensure_unique_names(&limits)?;
+        Ok(limits)
+    }
+}
+
+impl FromStr for SlurmLimit {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s.split_once(NAME_VALUE_SEPARATOR).ok_or_else(|| {
+            anyhow!(
+                "Slurm limit `{}` must be given in the form of name{}value e.g. MaxJobs=10",
+                s,
+                NAME_VALUE_SEPARATOR
+            )
+        })?;
+        let (name, kind) = LIMITS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "{} is not a supported Slurm limit. Supported are: {}",
+                    name.trim(),
+                    Self::names().collect::<Vec<_>>().join(", ")
+                )
+            })?;
+        let value = match kind {
+            _ if value.trim() == NO_LIMIT => TrimmedNonEmptyText::try_from(value)?,
+            LimitKind::Count => {
+                let count = TrimmedNonEmptyText::try_from(value)?;
+                if !count.as_ref().chars().all(|char| char.is_ascii_digit()) {
+                    bail!(
+                        "Value of Slurm limit {} must be a number, but got {}",
+                        name,
+                        count
+                    );
+                }
+                count
+            }
+            LimitKind::Tres => cli::slurm_tres(value)?,
+            LimitKind::Time => cli::slurm_time(value)?,
+        };
+        Ok(Self { name, value })
+    }
+}
+
+/// # Errors
+///
+/// - If a limit is given more than once in parameter `limits`.
+pub fn ensure_unique_names(limits: &[SlurmLimit]) -> AppResult {
+    for (index, limit) in limits.iter().enumerate() {
+        if limits[..index].iter().any(|other| other.name == limit.name) {
+            bail!("Slurm limit {} is given more than once", limit.name);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_limits_of_every_kind() {
+        let actual: Vec<String> = [
+            "grptres=cpu=64,gres/gpu=2",
+            " MaxJobs = 10 ",
+            "MaxWall=2-00:00:00",
+            "GrpTRESMins=-1",
+        ]
+        .into_iter()
+        .map(|limit| {
+            SlurmLimit::from_str(limit)
+                .expect("Should be a valid limit")
+                .to_string()
+        })
+        .collect();
+        assert_eq!(
+            vec![
+                "GrpTRES=cpu=64,gres/gpu=2",
+                "MaxJobs=10",
+                "MaxWall=2-00:00:00",
+                "GrpTRESMins=-1"
+            ],
+            actual
+        );
+    }
+
+    #[test]
+    fn error_for_invalid_limits() {
+        assert!(SlurmLimit::from_str("MaxJobs").is_err());
+        assert!(SlurmLimit::from_str("MaxCookies=10").is_err());
+        assert!(SlurmLimit::from_str("MaxJobs=ten").is_err());
+        assert!(SlurmLimit::from_str("MaxJobs=").is_err());
+        assert!(SlurmLimit::from_str("GrpTRES=cpu").is_err());
+        assert!(SlurmLimit::from_str("MaxWall=2 days").is_err());
+    }
+
+    #[test]
+    fn default_limits_of_group() {
+        let config = MgmtConfig {
+            student_slurm_limits: vec!["MaxJobs=10".to_string(), "GrpTRES=cpu=64".to_string()],
+            staff_slurm_limits: vec!["MaxJobs=100".to_string()],
+            ..Default::default()
+        };
+        let to_text = |limits: Vec<SlurmLimit>| -> Vec<String> {
+            limits.iter().map(ToString::to_string).collect()
+        };
+        assert_eq!(
+            vec!["MaxJobs=10", "GrpTRES=cpu=64"],
+            to_text(
+                SlurmLimit::from_group(Group::Student, &config).expect("Should be valid limits")
+            )
+        );
+        assert_eq!(
+            vec!["MaxJobs=100"],
+            to_text(
+                SlurmLimit::from_group(Group::Faculty, &config).expect("Should be valid limits")
+            )
+        );
+
+        let duplicated = MgmtConfig {
+            student_slurm_limits: vec!["MaxJobs=10".to_string(), "maxjobs=20".to_string()],
+            ..Default::default()
+        };
+        assert!(SlurmLimit::from_group(Group::Student, &duplicated).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use std::{fmt, fs, path::Path, str::FromStr};
+
+use base64::{
+    engine::general_purpose::{STANDARD, STANDARD_NO_PAD},
+    Engine,
+};
+use sha2::{Digest, Sha256};
+
+use crate::prelude::*;
+
+/// Key types accepted by OpenSSH for public keys.
+const KNOWN_KEY_TYPES: &[&str] = &[
+    "ssh-ed25519",
+    "ssh-rsa",
+    "ecdsa-sha2-nistp256",
+    "ecdsa-sha2-nistp384",
+    "ecdsa-sha2-nistp521",
+    "sk-ssh-ed25519@openssh.com",
+    "sk-ecdsa-sha2-nistp256@openssh.com",
+    "ssh-dss",
+];
+
+/// Public key in the OpenSSH format: `<type> <base64 encoded key> [comment]`.
+///
+/// The type must be a known key type and the base64 encoded key must declare the same type.
+/// Two keys are equal if their type and encoded key are equal. The comment is ignored.
+#[derive(Debug, Clone)]
+pub struct SshPublicKey {
+    key_type: String,
+    encoded_key: String,
+    comment: Option<String>,
+}
+
+impl SshPublicKey {
+    pub fn key_type(&self) -> &str {
+        &self.key_type
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Returns the fingerprint in the same format as `ssh-keygen -l`, e.g. `SHA256:v20uEHP...`.
+    pub fn fingerprint(&self) -> String {
+        let blob = STANDARD
+            .decode(&self.encoded_key)
+            .expect("Encoded key was validated as base64 while parsing");
+        let digest = Sha256::digest(blob);
+        format!("SHA256:{}", STANDARD_NO_PAD.encode(digest))
+    }
+
+    /// Parses every line of parameter `text` as a public key, like the content of a `.pub`
+    /// or `authorized_keys` file. Empty lines and lines starting with `#` are skipped.
+    ///
+    /// # Errors
+    ///
+    /// - If a line is not a valid public key.
+    /// - If the same key occurs more than once.
+    /// - If no key at all is given.
+    pub fn parse_many(text: &str) -> AppResult<Vec<Self>> {
+        let mut keys: Vec<Self> = Vec::new();
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        for line in lines {
+            let key: Self = line.parse()?;
+            ensure_not_duplicate(&key, &keys)?;
+            keys.push(key);
+        }
+        if keys.is_empty() {
+            bail!("No SSH public key was given");
+        }
+        Ok(keys)
+    }
+
+    /// # Errors
+    ///
+    /// - If the file could not be read.
+    /// - If the content of the file is not valid. See [`SshPublicKey::parse_many`]
+    pub fn read_many_from_file(path: &Path) -> AppResult<Vec<Self>> {
+        let content = fs::read_to_string(path).with_context(|| {
+            format!(
+                "Unable to read SSH public keys from file at {}",
+                path.to_string_lossy()
+            )
+        })?;
+        Self::parse_many(&content).with_context(|| {
+            format!(
+                "Invalid SSH public key in file at {}",
+                path.to_string_lossy()
+            )
+        })
+    }
+}
+
+/// # Errors
+///
+/// - If parameter `key` is already contained in parameter `existing`.
+pub fn ensure_not_duplicate(key: &SshPublicKey, existing: &[SshPublicKey]) -> AppResult {
+    if existing.contains(key) {
+        bail!(
+            "SSH public key with fingerprint {} is given more than once",
+            key.fingerprint()
+        );
+    }
+    Ok(())
+}
+
+impl PartialEq for SshPublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.key_type == other.key_type && self.encoded_key == other.encoded_key
+    }
+}
+
+impl Eq for SshPublicKey {}
+
+impl fmt::Display for SshPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.key_type, self.encoded_key)?;
+        if let Some(comment) = &self.comment {
+            write!(f, " {}", comment)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SshPublicKey {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let key_type = parts
+            .next()
+            .ok_or_else(|| anyhow!("SSH public key must not be empty"))?;
+        if !KNOWN_KEY_TYPES.contains(&key_type) {
+            bail!(
+                "Unknown type {} of SSH public key. Known types are: {}",
+                key_type,
+                KNOWN_KEY_TYPES.join(", ")
+            );
+        }
+        let encoded_key = parts
+            .next()
+            .ok_or_else(|| anyhow!("SSH public key of type {} has no key data", key_type))?;
+        let blob = STANDARD.decode(encoded_key).with_context(|| {
+            format!(
+                "Key data of SSH public key of type {} is not valid base64",
+                key_type
+            )
+        })?;
+        let declared_type = declared_key_type(&blob).ok_or_else(|| {
+            anyhow!(
+                "Key data of SSH public key of type {} is malformed",
+                key_type
+            )
+        })?;
+        if declared_type != key_type.as_bytes() {
+            bail!(
+                "SSH public key is of type {} but its key data is of type {}",
+                key_type,
+                String::from_utf8_lossy(declared_type)
+            );
+        }
+        let comment = parts.collect::<Vec<&str>>().join(" ");
+        let comment = if comment.is_empty() {
+            None
+        } else {
+            Some(comment)
+        };
+
+        Ok(Self {
+            key_type: key_type.to_string(),
+            encoded_key: encoded_key.to_string(),
+            comment,
+        })
+    }
+}
+
+/// The key data starts with the key type as a string prefixed by its length (big endian u32).
+fn declared_key_type(blob: &[u8]) -> Option<&[u8]> {
+    let length_prefix: [u8; 4] = blob.get(..4)?.try_into().ok()?;
+    let length = u32::from_be_bytes(length_prefix) as usize;
+    blob.get(4..4usize.checked_add(length)?)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    const ED25519: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIBSxyNRtflgd8DJXnINmlNjoEmPyajfAGqbtrP16+Qq4 alice@example";
+    const RSA: &str = "ssh-rsa AAAAB3NzaC1yc2EAAAADAQABAAAAgQDDI1N8bMrRK4c3Kx2Ej4zDjpb/t5PFFh16ulMm/b5+EYWZJ4x/5woozZKBQgwtJq7hHysZH4DMr7BM9QOEp83jx+Cog2qMrPHxg5Cy7fOZmOKmCBEVprUpEf8E0Ib9vbk1R6qUIw0zjs9g/gs+1S9KmSSv2XbOijLqDobR8fnquQ==";
+
+    #[test]
+    fn parse_key_with_and_without_comment() {
+        let with_comment: SshPublicKey = ED25519.parse().expect("Should be a valid key");
+        assert_eq!("ssh-ed25519", with_comment.key_type());
+        assert_eq!(Some("alice@example"), with_comment.comment());
+        assert_eq!(ED25519, with_comment.to_string());
+
+        let without_comment: SshPublicKey = RSA.parse().expect("Should be a valid key");
+        assert_eq!(None, without_comment.comment());
+        assert_eq!(RSA, without_comment.to_string());
+    }
+
+    #[test]
+    fn fingerprint_like_ssh_keygen() {
+        let ed25519: SshPublicKey = ED25519.parse().expect("Should be a valid key");
+        let rsa: SshPublicKey = RSA.parse().expect("Should be a valid key");
+        assert_eq!(
+            "SHA256:v20uEHPXRFQV3mAJxUUs6gN2Au/dkq4lsmxw0GW1z+M",
+            ed25519.fingerprint()
+        );
+        assert_eq!(
+            "SHA256:qAWpeHiweUT7RuNoAlkjGM9brROwHPUZpmzvGbPg8D4",
+            rsa.fingerprint()
+        );
+    }
+
+    #[test]
+    fn error_for_invalid_keys() {
+        let cases = [
+            "",
+            "ssh-foo AAAAC3NzaC1lZDI1NTE5AAAAIBSxyNRtflgd8DJXnINmlNjoEmPyajfAGqbtrP16+Qq4",
+            "ssh-ed25519",
+            "ssh-ed25519 not-base64!",
+            "ssh-ed25519 AAAA",
+            // Key data of an ed25519 key with the type rsa
+            "ssh-rsa AAAAC3NzaC1lZDI1NTE5AAAAIBSxyNRtflgd8DJXnINmlNjoEmPyajfAGqbtrP16+Qq4",
+        ];
+        for input in cases {
+            assert!(
+                input.parse::<SshPublicKey>().is_err(),
+                "Should be invalid: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_many_skips_comments_and_rejects_duplicates() {
+        let text = format!("# keys of alice\n{}\n\n{}\n", ED25519, RSA);
+        let keys = SshPublicKey::parse_many(&text).expect("Should be valid keys");
+        assert_eq!(2, keys.len());
+
+        let duplicate = format!("{}\n{} other comment", ED25519, ED25519);
+        assert!(SshPublicKey::parse_many(&duplicate).is_err());
+        assert!(SshPublicKey::parse_many("# only a comment").is_err());
+    }
+}
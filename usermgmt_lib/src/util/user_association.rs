@@ -0,0 +1,187 @@
+use std::{fmt, str::FromStr};
+
+use crate::{cli, prelude::*};
+
+use super::TrimmedNonEmptyText;
+
+const PART_SEPARATOR: char = ',';
+const KEY_VALUE_SEPARATOR: char = '=';
+const ACCOUNT: &str = "account";
+const PARTITION: &str = "partition";
+const CLUSTER: &str = "cluster";
+const QOS: &str = "qos";
+
+/// Association of a user with an account in Slurm, optionally restricted to a partition and/or cluster.
+///
+/// Parsed from text like `account=project-a,partition=gpu,cluster=alpha,qos=basic,qos=interactive`.
+/// The key `qos` can be given multiple times. A part without a key is taken as the account,
+/// so `project-a` alone is valid as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAssociation {
+    account: TrimmedNonEmptyText,
+    partition: Option<TrimmedNonEmptyText>,
+    cluster: Option<TrimmedNonEmptyText>,
+    qos: Vec<TrimmedNonEmptyText>,
+}
+
+impl UserAssociation {
+    pub fn new(
+        account: TrimmedNonEmptyText,
+        partition: Option<TrimmedNonEmptyText>,
+        cluster: Option<TrimmedNonEmptyText>,
+    ) -> Self {
+        Self {
+            account,
+            partition,
+            cluster,
+            qos: Vec::new(),
+        }
+    }
+
+    pub fn account(&self) -> &str {
+        self.account.as_ref()
+    }
+
+    pub fn partition(&self) -> Option<&str> {
+        self.partition
+            .as_ref()
+            .map(|partition| partition.as_ref().as_str())
+    }
+
+    pub fn cluster(&self) -> Option<&str> {
+        self.cluster
+            .as_ref()
+            .map(|cluster| cluster.as_ref().as_str())
+    }
+
+    /// QOS of this association. Slurm uses the QOS of the account if empty.
+    pub fn qos(&self) -> &[TrimmedNonEmptyText] {
+        &self.qos
+    }
+
+    /// Returns true if parameter `other` is matched by this association as a where clause of sacctmgr.
+    /// A partition or cluster which is not given matches any partition or cluster.
+    /// QOS are not considered.
+    pub fn matches(&self, other: &Self) -> bool {
+        let matches_optional = |pattern: Option<&str>, value: Option<&str>| {
+            pattern.map_or(true, |pattern| Some(pattern) == value)
+        };
+        self.account == other.account
+            && matches_optional(self.partition(), other.partition())
+            && matches_optional(self.cluster(), other.cluster())
+    }
+}
+
+impl fmt::Display for UserAssociation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.account)?;
+        match (&self.partition, &self.cluster) {
+            (Some(partition), Some(cluster)) => {
+                write!(f, " (partition {}, cluster {})", partition, cluster)
+            }
+            (Some(partition), None) => write!(f, " (partition {})", partition),
+            (None, Some(cluster)) => write!(f, " (cluster {})", cluster),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl FromStr for UserAssociation {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut account, mut partition, mut cluster, mut qos) = (None, None, None, Vec::new());
+        for part in s.split(PART_SEPARATOR) {
+            let (key, value) = part
+                .split_once(KEY_VALUE_SEPARATOR)
+                .unwrap_or((ACCOUNT, part));
+            let value = cli::slurm_name(value).with_context(|| {
+                format!("Invalid value of {} in Slurm association `{}`", key, s)
+            })?;
+            let field = match key.trim().to_lowercase().as_str() {
+                ACCOUNT => &mut account,
+                PARTITION => &mut partition,
+                CLUSTER => &mut cluster,
+                QOS => {
+                    qos.push(value);
+                    continue;
+                }
+                _ => bail!(
+                    "Unknown key {} in Slurm association `{}`. Known keys are {}, {}, {} and {}",
+                    key.trim(),
+                    s,
+                    ACCOUNT,
+                    PARTITION,
+                    CLUSTER,
+                    QOS
+                ),
+            };
+            if field.replace(value).is_some() {
+                bail!(
+                    "Key {} is given more than once in Slurm association `{}`",
+                    key.trim(),
+                    s
+                );
+            }
+        }
+        let account =
+            account.ok_or_else(|| anyhow!("Slurm association `{}` must name an account", s))?;
+        Ok(Self {
+            account,
+            partition,
+            cluster,
+            qos,
+        })
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_association() {
+        let actual = UserAssociation::from_str(
+            "account=project-a, partition=gpu,cluster=alpha,qos=basic,QOS=interactive",
+        )
+        .expect("Should be a valid association");
+        assert_eq!("project-a", actual.account());
+        assert_eq!(Some("gpu"), actual.partition());
+        assert_eq!(Some("alpha"), actual.cluster());
+        assert_eq!(
+            vec!["basic", "interactive"],
+            actual
+                .qos()
+                .iter()
+                .map(|qos| qos.as_ref().as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "project-a (partition gpu, cluster alpha)",
+            actual.to_string()
+        );
+
+        let only_account = UserAssociation::from_str("project-a").expect("Should be valid");
+        assert_eq!("project-a", only_account.to_string());
+    }
+
+    #[test]
+    fn error_for_invalid_association() {
+        assert!(UserAssociation::from_str("partition=gpu").is_err());
+        assert!(UserAssociation::from_str("account=a,account=b").is_err());
+        assert!(UserAssociation::from_str("account=a,node=n01").is_err());
+        assert!(UserAssociation::from_str("account=a b").is_err());
+        assert!(UserAssociation::from_str("").is_err());
+    }
+
+    #[test]
+    fn match_associations_like_where_clause() {
+        let parse = |s: &str| UserAssociation::from_str(s).expect("Should be valid");
+        let with_partition = parse("account=a,partition=gpu,cluster=alpha");
+        assert!(parse("a").matches(&with_partition));
+        assert!(parse("account=a,partition=gpu").matches(&with_partition));
+        assert!(!parse("account=a,partition=cpu").matches(&with_partition));
+        assert!(!parse("b").matches(&with_partition));
+        assert!(!with_partition.matches(&parse("a")));
+    }
+}